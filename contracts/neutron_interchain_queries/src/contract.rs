@@ -2,7 +2,7 @@ use cosmos_sdk_proto::cosmos::bank::v1beta1::MsgSend;
 use cosmos_sdk_proto::cosmos::tx::v1beta1::{TxBody, TxRaw};
 use cosmos_sdk_proto::traits::Message;
 use cosmwasm_std::{
-    entry_point, to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    entry_point, to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
     Response, StdError, StdResult, Uint128,
 };
 use cw2::set_contract_version;
@@ -10,9 +10,10 @@ use neutron_sdk::interchain_queries::v047::register_queries::new_register_valida
 use neutron_std::types::neutron::interchainqueries::{KvKey, RegisteredQuery};
 
 use crate::msg::{
-    Cw20BalanceResponse, ExecuteMsg, GetRecipientTxsResponse, InstantiateMsg, MigrateMsg, QueryMsg,
+    BalancesHistoryResponse, Cw20BalanceResponse, ExecuteMsg, GetRecipientTxsResponse,
+    InstantiateMsg, MigrateMsg, QueryMsg,
 };
-use crate::state::{Transfer, RECIPIENT_TXS, TRANSFERS};
+use crate::state::{Transfer, BALANCES_SNAPSHOTS, RECIPIENT_TXS, TRANSFERS};
 use neutron_sdk::interchain_queries::helpers::{
     remove_interchain_query as helpers_remove_interchain_query,
     update_interchain_query as helpers_update_interchain_query,
@@ -33,9 +34,10 @@ use neutron_sdk::interchain_queries::{
             new_register_staking_validators_query_msg, new_register_transfers_query_msg,
             new_register_wasm_contract_store_query_msg,
         },
-        types::{COSMOS_SDK_TRANSFER_MSG_URL, RECIPIENT_FIELD},
+        types::{BALANCES_PREFIX, BANK_STORE_KEY, COSMOS_SDK_TRANSFER_MSG_URL, RECIPIENT_FIELD},
     },
 };
+use neutron_sdk::sudo::handler::{dispatch, SudoHandler};
 use neutron_sdk::sudo::msg::{Height, SudoMsg};
 use neutron_sdk::{NeutronError, NeutronResult};
 
@@ -419,9 +421,25 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> NeutronResult<Binary> {
             Ok(to_json_binary(&get_registered_query(deps, query_id)?)?)
         }
         QueryMsg::GetRecipientTxs { recipient } => query_recipient_txs(deps, recipient),
+        QueryMsg::BalancesHistory {
+            query_id,
+            start_after,
+            limit,
+        } => query_balances_history(deps, query_id, start_after, limit),
     }
 }
 
+fn query_balances_history(
+    deps: Deps,
+    query_id: u64,
+    start_after: Option<Height>,
+    limit: Option<u32>,
+) -> NeutronResult<Binary> {
+    let snapshots =
+        BALANCES_SNAPSHOTS.range(deps.storage, query_id, start_after, limit, Order::Ascending)?;
+    Ok(to_json_binary(&BalancesHistoryResponse { snapshots })?)
+}
+
 fn query_recipient_txs(deps: Deps, recipient: String) -> NeutronResult<Binary> {
     let txs = RECIPIENT_TXS
         .load(deps.storage, &recipient)
@@ -448,22 +466,39 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response
     Ok(Response::default())
 }
 
-#[entry_point]
-pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> NeutronResult<Response> {
-    match msg {
-        // For handling tx query result
-        SudoMsg::TxQueryResult {
-            query_id,
-            height,
-            data,
-        } => sudo_tx_query_result(deps, env, query_id, height, data),
+/// Handles results of the Interchain Queries registered by the contract,
+/// the other sudo calls are ignored
+struct QueryResultHandler;
+
+impl SudoHandler for QueryResultHandler {
+    // For handling tx query result
+    fn on_tx_query_result(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        query_id: u64,
+        height: Height,
+        data: Binary,
+    ) -> NeutronResult<Response> {
+        sudo_tx_query_result(deps, env, query_id, height, data)
+    }
 
-        // For handling kv query result
-        SudoMsg::KVQueryResult { query_id } => sudo_kv_query_result(deps, env, query_id),
-        _ => Ok(Response::default()),
+    // For handling kv query result
+    fn on_kv_query_result(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        query_id: u64,
+    ) -> NeutronResult<Response> {
+        sudo_kv_query_result(deps, env, query_id)
     }
 }
 
+#[entry_point]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> NeutronResult<Response> {
+    dispatch(&QueryResultHandler, deps, env, msg)
+}
+
 /// sudo_check_tx_query_result is an example callback for transaction query results that stores the
 /// deposits received as a result on the registered query in the contract's state.
 pub fn sudo_tx_query_result(
@@ -592,8 +627,23 @@ pub fn sudo_kv_query_result(deps: DepsMut, _env: Env, query_id: u64) -> NeutronR
         .as_str(),
     );
 
-    // TODO: provide an actual example. Currently to many things are going to change
-    // after @pro0n00gler's PRs to implement this.
+    let registered_query = get_registered_query(deps.as_ref(), query_id)?;
+
+    // In this example only results of balances queries are kept, so that the contract
+    // can tell the balances at every remote height a result was submitted at.
+    let is_balances_query = !registered_query.keys.is_empty()
+        && registered_query
+            .keys
+            .iter()
+            .all(|key| key.path == BANK_STORE_KEY && key.key.first() == Some(&BALANCES_PREFIX));
+    if is_balances_query {
+        let snapshot = BALANCES_SNAPSHOTS.record(deps, query_id)?;
+        return Ok(Response::new()
+            .add_attribute("action", "balances_snapshot")
+            .add_attribute("query_id", query_id.to_string())
+            .add_attribute("revision", snapshot.revision.to_string())
+            .add_attribute("remote_height", snapshot.remote_height.to_string()));
+    }
 
     Ok(Response::default())
 }
@@ -1,5 +1,8 @@
 use crate::state::Transfer;
 use cosmwasm_std::Uint128;
+use neutron_sdk::interchain_queries::snapshots::QuerySnapshot;
+use neutron_sdk::interchain_queries::v047::types::Balances;
+use neutron_sdk::sudo::msg::Height;
 use neutron_std::types::neutron::interchainqueries::KvKey;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -78,17 +81,44 @@ pub enum ExecuteMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    Balance { query_id: u64 },
-    BankTotalSupply { query_id: u64 },
-    DistributionFeePool { query_id: u64 },
-    StakingValidators { query_id: u64 },
-    ValidatorsSigningInfos { query_id: u64 },
-    GovernmentProposals { query_id: u64 },
-    GetDelegations { query_id: u64 },
-    GetUnbondingDelegations { query_id: u64 },
-    Cw20Balance { query_id: u64 },
-    GetRegisteredQuery { query_id: u64 },
-    GetRecipientTxs { recipient: String },
+    Balance {
+        query_id: u64,
+    },
+    BankTotalSupply {
+        query_id: u64,
+    },
+    DistributionFeePool {
+        query_id: u64,
+    },
+    StakingValidators {
+        query_id: u64,
+    },
+    ValidatorsSigningInfos {
+        query_id: u64,
+    },
+    GovernmentProposals {
+        query_id: u64,
+    },
+    GetDelegations {
+        query_id: u64,
+    },
+    GetUnbondingDelegations {
+        query_id: u64,
+    },
+    Cw20Balance {
+        query_id: u64,
+    },
+    GetRegisteredQuery {
+        query_id: u64,
+    },
+    GetRecipientTxs {
+        recipient: String,
+    },
+    BalancesHistory {
+        query_id: u64,
+        start_after: Option<Height>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -103,5 +133,11 @@ pub struct GetRecipientTxsResponse {
     pub transfers: Vec<Transfer>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BalancesHistoryResponse {
+    pub snapshots: Vec<QuerySnapshot<Balances>>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -1,4 +1,6 @@
 use cw_storage_plus::{Item, Map};
+use neutron_sdk::interchain_queries::snapshots::QuerySnapshots;
+use neutron_sdk::interchain_queries::v047::types::Balances;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +10,9 @@ pub type Recipient = str;
 pub const RECIPIENT_TXS: Map<&Recipient, Vec<Transfer>> = Map::new("recipient_txs");
 /// contains number of transfers to addresses observed by the contract.
 pub const TRANSFERS: Item<u64> = Item::new("transfers");
+/// contains up to 100 latest results of every balances query observed by the contract.
+pub const BALANCES_SNAPSHOTS: QuerySnapshots<Balances> =
+    QuerySnapshots::new("balances_snapshots", 100);
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Transfer {
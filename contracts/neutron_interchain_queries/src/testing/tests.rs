@@ -2,8 +2,8 @@ use base64::{prelude::*, Engine};
 use std::str::FromStr;
 
 use super::mock_querier::mock_dependencies as dependencies;
-use crate::contract::{execute, query, sudo_tx_query_result};
-use crate::msg::{ExecuteMsg, QueryMsg};
+use crate::contract::{execute, query, sudo_kv_query_result, sudo_tx_query_result};
+use crate::msg::{BalancesHistoryResponse, ExecuteMsg, QueryMsg};
 use crate::state::{Transfer, RECIPIENT_TXS};
 use crate::testing::mock_querier::WasmMockQuerier;
 use cosmos_sdk_proto::cosmos::base::v1beta1::{Coin as CosmosCoin, DecCoin as CosmosDecCoin};
@@ -22,6 +22,7 @@ use cosmwasm_std::{
 use neutron_sdk::interchain_queries::helpers::{
     decode_and_convert, decode_hex, kv_key_from_string,
 };
+use neutron_sdk::interchain_queries::snapshots::QuerySnapshot;
 use neutron_sdk::interchain_queries::types::{
     QueryType, TransactionFilterItem, TransactionFilterOp, TransactionFilterValue,
 };
@@ -310,6 +311,81 @@ fn test_query_balance() {
     )
 }
 
+#[test]
+fn test_sudo_kv_query_result_balances_snapshots() {
+    let mut deps = dependencies(&[]);
+
+    let msg = ExecuteMsg::RegisterBalancesQuery {
+        connection_id: "connection".to_string(),
+        update_period: 10,
+        addr: "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+        denoms: vec!["uosmo".to_string()],
+    };
+    let keys = register_query(
+        &mut deps,
+        mock_env(),
+        message_info(&Addr::unchecked(""), &[]),
+        msg,
+    );
+    deps.querier.add_registered_queries(
+        1,
+        build_registered_query_response(1, QueryParam::Keys(keys), QueryType::KV, 987),
+    );
+    deps.querier.add_query_response(
+        1,
+        build_interchain_query_balances_response(
+            Addr::unchecked("osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs"),
+            vec![Coin::new(8278104u128, "uosmo")],
+        ),
+    );
+
+    // results of other queries aren't kept
+    deps.querier.add_registered_queries(
+        2,
+        build_registered_query_response(
+            2,
+            QueryParam::Keys(vec![KvKey {
+                path: "bank".to_string(),
+                key: create_total_denom_key("uosmo").unwrap(),
+            }]),
+            QueryType::KV,
+            987,
+        ),
+    );
+
+    sudo_kv_query_result(deps.as_mut(), mock_env(), 1).unwrap();
+    sudo_kv_query_result(deps.as_mut(), mock_env(), 2).unwrap();
+
+    let history = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, query_id: u64| {
+        let resp: BalancesHistoryResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::BalancesHistory {
+                    query_id,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        resp.snapshots
+    };
+    assert_eq!(
+        history(&deps, 1),
+        vec![QuerySnapshot {
+            query_id: 1,
+            remote_height: 123456,
+            revision: 2,
+            value: Balances {
+                coins: vec![Coin::new(8278104u128, "uosmo")]
+            },
+        }]
+    );
+    assert_eq!(history(&deps, 2), vec![]);
+}
+
 #[test]
 fn test_query_balances() {
     let mut deps = dependencies(&[]);
@@ -688,11 +764,11 @@ fn test_validators_signing_infos_query() {
     let mut deps = dependencies(&[]);
     let validators = vec![
         (
-            "cosmosvaloper132juzk0gdmwuxvx4phug7m3ymyatxlh9734g4w".to_string(),
+            "cosmosvalcons132juzk0gdmwuxvx4phug7m3ymyatxlh92zx5e0".to_string(),
             None,
         ),
         (
-            "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0".to_string(),
+            "cosmosvalcons1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u7vt07w".to_string(),
             Some(prost_types::Timestamp {
                 seconds: 1203981203,
                 nanos: 123123,
@@ -748,7 +824,7 @@ fn test_validators_signing_infos_query() {
             signing_infos: SigningInfo {
                 signing_infos: vec![
                     ValidatorSigningInfo {
-                        address: "cosmosvaloper132juzk0gdmwuxvx4phug7m3ymyatxlh9734g4w".to_string(),
+                        address: "cosmosvalcons132juzk0gdmwuxvx4phug7m3ymyatxlh92zx5e0".to_string(),
                         start_height: 1,
                         index_offset: 20,
                         jailed_until: None,
@@ -756,7 +832,7 @@ fn test_validators_signing_infos_query() {
                         missed_blocks_counter: 13,
                     },
                     ValidatorSigningInfo {
-                        address: "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0".to_string(),
+                        address: "cosmosvalcons1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u7vt07w".to_string(),
                         start_height: 1,
                         index_offset: 20,
                         jailed_until: Some(1203981203),
@@ -1076,7 +1152,7 @@ fn test_sudo_tx_query_result_min_height_callback() {
     // simulate neutron's SudoTxQueryResult call with the following payload:
     // a sending from neutron10h9stc5v6ntgeygf5xf945njqq5h32r54rf7kf to watched_addr of 10000 stake
     let data: Binary = Binary::from(BASE64_STANDARD.decode("CpMBCpABChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEnAKLm5ldXRyb24xMGg5c3RjNXY2bnRnZXlnZjV4Zjk0NW5qcXE1aDMycjU0cmY3a2YSLm5ldXRyb24xZmo2eXFya3B3NmZtcDdmN2poajU3ZHVqZnB3YWw0bTI1ZGFmengaDgoFc3Rha2USBTEwMDAwEmcKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJPYibh+Zef13ZkulPqI27rV5xswZ0H/vh1Tnymp1RHPhIECgIIARgAEhMKDQoFc3Rha2USBDEwMDAQwJoMGkAIiXNJXmA57KhyaWpKcLLr3602A5+hlvv/b4PgcDDm9y0qikC+biNZXin1dEMpHOvX9DwOWJ9utv6EKljiSyfT").unwrap());
-    sudo_tx_query_result(deps.as_mut(), env.clone(), query_id, height.clone(), data).unwrap();
+    sudo_tx_query_result(deps.as_mut(), env.clone(), query_id, height, data).unwrap();
 
     // ensure the callback has worked and contract's state has changed
     let txs = RECIPIENT_TXS.load(&deps.storage, &watched_addr).unwrap();
@@ -1093,7 +1169,7 @@ fn test_sudo_tx_query_result_min_height_callback() {
     // simulate neutron's SudoTxQueryResult call with the following payload:
     // a sending from neutron10h9stc5v6ntgeygf5xf945njqq5h32r54rf7kf to another addr of 10000 stake
     let data: Binary = Binary::from(BASE64_STANDARD.decode("CpMBCpABChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEnAKLm5ldXRyb24xMGg5c3RjNXY2bnRnZXlnZjV4Zjk0NW5qcXE1aDMycjU0cmY3a2YSLm5ldXRyb24xNHV4dnUyMmxocmF6eXhhZGFxdjVkNmxzd3UwcDI3NmxsN2hya2waDgoFc3Rha2USBTEwMDAwEmcKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJPYibh+Zef13ZkulPqI27rV5xswZ0H/vh1Tnymp1RHPhIECgIIARgAEhMKDQoFc3Rha2USBDEwMDAQwJoMGkBEv2CW/0gIrankNl4aGs9LXy2BKA6kAWyl4MUxmXnbnjRpgaNbQIyo4i7nUgVsuOpqzAdudM2M53OSU0Dmo5tF").unwrap());
-    let res = sudo_tx_query_result(deps.as_mut(), env.clone(), query_id, height.clone(), data);
+    let res = sudo_tx_query_result(deps.as_mut(), env.clone(), query_id, height, data);
 
     // ensure the callback has returned an error and contract's state hasn't changed
     let err = res.unwrap_err();
@@ -1,33 +1,27 @@
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::storage::{
-    add_error_to_queue, read_errors_from_queue, read_reply_payload, read_sudo_payload,
-    save_reply_payload, save_sudo_payload, AcknowledgementResult, SudoPayload,
+    add_error_to_queue, migrate_interchain_accounts, read_errors_from_queue, read_reply_payload,
+    read_sudo_payload, save_reply_payload, save_sudo_payload, AcknowledgementResult, SudoPayload,
     ACKNOWLEDGEMENT_RESULTS, INTERCHAIN_ACCOUNTS, SUDO_PAYLOAD_REPLY_ID,
 };
-use cosmos_sdk_proto::traits::Message;
 use cosmwasm_std::{
     to_json_binary, Binary, CosmosMsg, CustomQuery, Deps, DepsMut, Env, MessageInfo, Reply,
-    Response, StdError, StdResult, SubMsg,
+    Response, StdError, StdResult, SubMsg, Timestamp,
 };
 use cw2::set_contract_version;
-use neutron_sdk::interchain_txs::helpers::{
-    query_denom_min_ibc_fee, register_interchain_account, submit_tx,
-};
+use neutron_sdk::interchain_txs::helpers::query_denom_min_ibc_fee;
+use neutron_sdk::interchain_txs::ica_tx::IcaTx;
+use neutron_sdk::interchain_txs::responses::{AckDecoder, IcaMsgResponse};
 use neutron_sdk::{
     interchain_txs::helpers::{decode_message_response, get_port_id},
     interchain_txs::v047::helpers::decode_acknowledgement_response,
     sudo::msg::{RequestPacket, SudoMsg},
-    NeutronError, NeutronResult,
+    NeutronResult,
 };
-use neutron_std::shim::Timestamp;
 use neutron_std::types::cosmos::base::v1beta1::Coin;
-use neutron_std::types::cosmos::staking::v1beta1::{
-    MsgDelegate, MsgDelegateResponse, MsgUndelegate, MsgUndelegateResponse,
-};
+use neutron_std::types::cosmos::staking::v1beta1::{MsgDelegate, MsgUndelegate};
 use neutron_std::types::ibc::core::channel::v1::Order;
 use neutron_std::types::neutron::interchaintxs::v1::{InterchaintxsQuerier, MsgSubmitTxResponse};
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
 
 // Default timeout for SubmitTX is two weeks
 const DEFAULT_TIMEOUT_SECONDS: u64 = 60 * 60 * 24 * 7 * 2;
@@ -36,17 +30,6 @@ const FEE_DENOM: &str = "untrn";
 const CONTRACT_NAME: &str = concat!("crates.io:neutron-sdk__", env!("CARGO_PKG_NAME"));
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-struct OpenAckVersion {
-    version: String,
-    controller_connection_id: String,
-    host_connection_id: String,
-    address: String,
-    encoding: String,
-    tx_type: String,
-}
-
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -198,16 +181,15 @@ fn execute_register_ica(
     register_fee: Vec<Coin>,
     ordering: Option<Order>,
 ) -> NeutronResult<Response> {
-    let register = register_interchain_account(
-        env.contract.address.clone(),
+    // the account is tracked as registering until we handle response of registering ICA in sudo_open_ack method
+    let register = INTERCHAIN_ACCOUNTS.register(
+        deps.storage,
+        env.contract.address,
         connection_id,
-        interchain_account_id.clone(),
+        interchain_account_id,
         register_fee,
         ordering,
-    );
-    let key = get_port_id(&env.contract.address.to_string(), &interchain_account_id);
-    // we are saving empty data here because we handle response of registering ICA in sudo_open_ack method
-    INTERCHAIN_ACCOUNTS.save(deps.storage, key, &None)?;
+    )?;
     Ok(Response::new().add_message(register))
 }
 
@@ -232,29 +214,15 @@ fn execute_delegate(
             amount: amount.to_string(),
         }),
     };
-    let mut buf = Vec::with_capacity(delegate_msg.encoded_len());
-
-    if let Err(e) = delegate_msg.encode(&mut buf) {
-        return Err(NeutronError::Std(StdError::msg(format!(
-            "Encode error: {}",
-            e
-        ))));
-    }
 
-    let any_msg = neutron_std::shim::Any {
-        type_url: "/cosmos.staking.v1beta1.MsgDelegateResponse".to_string(),
-        value: buf,
-    };
-
-    let cosmos_msg = submit_tx(
-        env.contract.address.clone(),
+    let (cosmos_msg, _) = IcaTx::new(
         connection_id,
         interchain_account_id.clone(),
-        vec![any_msg],
-        "".to_string(),
         timeout.unwrap_or(DEFAULT_TIMEOUT_SECONDS),
-        fee,
-    );
+    )
+    .add_std_msg(delegate_msg)?
+    .with_fee(fee)
+    .build(env.contract.address.clone())?;
 
     // We use a submessage here because we need the process message reply to save
     // the outgoing IBC packet identifier for later.
@@ -291,29 +259,15 @@ fn execute_undelegate(
             amount: amount.to_string(),
         }),
     };
-    let mut buf = Vec::with_capacity(delegate_msg.encoded_len());
 
-    if let Err(e) = delegate_msg.encode(&mut buf) {
-        return Err(NeutronError::Std(StdError::msg(format!(
-            "Encode error: {}",
-            e
-        ))));
-    }
-
-    let any_msg = neutron_std::shim::Any {
-        type_url: "/cosmos.staking.v1beta1.MsgUndelegate".to_string(),
-        value: buf,
-    };
-
-    let cosmos_msg = submit_tx(
-        env.contract.address.clone(),
+    let (cosmos_msg, _) = IcaTx::new(
         connection_id,
         interchain_account_id.clone(),
-        vec![any_msg],
-        "".to_string(),
         timeout.unwrap_or(DEFAULT_TIMEOUT_SECONDS),
-        fee,
-    );
+    )
+    .add_std_msg(delegate_msg)?
+    .with_fee(fee)
+    .build(env.contract.address.clone())?;
 
     // We use a submessage here because we need the process message reply to save
     // the outgoing IBC packet identifier for later.
@@ -363,8 +317,14 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> StdResult<Response> {
 }
 
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
     deps.api.debug("WASMDEBUG: migrate");
+    let orderings = msg
+        .ica_orderings
+        .into_iter()
+        .map(|ica| (ica.interchain_account_id, ica.ordering))
+        .collect();
+    migrate_interchain_accounts(deps.storage, &orderings)?;
     Ok(Response::default())
 }
 
@@ -373,28 +333,23 @@ fn sudo_open_ack(
     deps: DepsMut,
     _env: Env,
     port_id: String,
-    _channel_id: String,
-    _counterparty_channel_id: String,
+    channel_id: String,
+    counterparty_channel_id: String,
     counterparty_version: String,
 ) -> StdResult<Response> {
     // The version variable contains a JSON value with multiple fields,
     // including the generated account address.
-    let parsed_version: Result<OpenAckVersion, _> =
-        serde_json_wasm::from_str(counterparty_version.as_str());
-
     // Update the storage record associated with the interchain account.
-    if let Ok(parsed_version) = parsed_version {
-        INTERCHAIN_ACCOUNTS.save(
+    INTERCHAIN_ACCOUNTS
+        .on_open_ack(
             deps.storage,
-            port_id,
-            &Some((
-                parsed_version.address,
-                parsed_version.controller_connection_id,
-            )),
-        )?;
-        return Ok(Response::default());
-    }
-    Err(StdError::msg("Can't parse counterparty_version"))
+            &port_id,
+            channel_id,
+            counterparty_channel_id,
+            &counterparty_version,
+        )
+        .map_err(|e| StdError::msg(format!("Can't handle open ack: {}", e)))?;
+    Ok(Response::default())
 }
 
 fn sudo_response(deps: DepsMut, request: RequestPacket, data: Binary) -> StdResult<Response> {
@@ -439,33 +394,30 @@ fn sudo_response(deps: DepsMut, request: RequestPacket, data: Binary) -> StdResu
     // Both cases require immediate attention.
     let parsed_data = decode_acknowledgement_response(data)?;
 
+    let decoder: AckDecoder = AckDecoder::new();
     let mut item_types = vec![];
     for item in parsed_data {
-        let item_type = item.type_url.as_str();
-        item_types.push(item_type.to_string());
-        match item_type {
-            "/cosmos.staking.v1beta1.MsgUndelegate" => {
-                // In this particular case, a mismatch between the string message type and the
-                // serialised data layout looks like a fatal error that has to be investigated.
-                let out: MsgUndelegateResponse = decode_message_response(&item.value)?;
-
+        item_types.push(item.type_url.clone());
+        // In this particular case, a mismatch between the string message type and the
+        // serialised data layout looks like a fatal error that has to be investigated.
+        let response = decoder
+            .decode_response(&item)
+            .map_err(|e| StdError::msg(e.to_string()))?;
+        match response {
+            IcaMsgResponse::MsgUndelegate { completion_time } => {
                 // In this particular case, we demonstrate that minor errors should not
                 // close the channel, and should be treated in a forgiving manner.
-                let completion_time = out.completion_time.or_else(|| {
+                let completion_time = completion_time.or_else(|| {
                     let error_msg = "WASMDEBUG: sudo_response: Recoverable error. Failed to get completion time";
                     deps.api
                         .debug(error_msg);
                     add_error_to_queue(deps.storage, error_msg.to_string());
-                    Some(Timestamp::default())
+                    Some(Timestamp::from_nanos(0))
                 });
                 deps.api
                     .debug(format!("Undelegation completion time: {:?}", completion_time).as_str());
             }
-            "/cosmos.staking.v1beta1.MsgDelegateResponse" => {
-                // In this particular case, a mismatch between the string message type and the
-                // serialised data layout looks like a fatal error that has to be investigated.
-                let _out: MsgDelegateResponse = decode_message_response(&item.value)?;
-            }
+            IcaMsgResponse::MsgDelegate => {}
             _ => {
                 deps.api.debug(
                     format!(
@@ -499,6 +451,13 @@ fn sudo_timeout(deps: DepsMut, _env: Env, request: RequestPacket) -> StdResult<R
     deps.api
         .debug(format!("WASMDEBUG: sudo timeout request: {:?}", request).as_str());
 
+    // A timeout closes an ORDERED channel, so the interchain account is marked as closed.
+    // The channel is closed only after this call, so the account can't be registered again here,
+    // register it again with ExecuteMsg::Register to be able to execute transactions.
+    INTERCHAIN_ACCOUNTS
+        .on_timeout(deps.storage, &request)
+        .map_err(|e| StdError::msg(format!("Can't handle timeout: {}", e)))?;
+
     // In this particular case, we return an error because not having the sequence id
     // in the request value implies that a fatal error occurred on Neutron side.
     let seq_id = request
@@ -610,11 +569,13 @@ fn get_ica(
     env: &Env,
     interchain_account_id: &str,
 ) -> Result<(String, String), StdError> {
-    let key = get_port_id(env.contract.address.as_str(), interchain_account_id);
-
-    INTERCHAIN_ACCOUNTS
-        .load(deps.storage, key)?
-        .ok_or_else(|| StdError::msg("Interchain account is not created yet"))
+    let record = INTERCHAIN_ACCOUNTS
+        .load(deps.storage, &env.contract.address, interchain_account_id)
+        .map_err(|e| StdError::msg(e.to_string()))?;
+    match record.address {
+        Some(address) if record.is_open() => Ok((address, record.connection_id)),
+        _ => Err(StdError::msg("Interchain account is not created yet")),
+    }
 }
 
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
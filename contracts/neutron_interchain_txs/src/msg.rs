@@ -25,7 +25,18 @@ pub enum QueryMsg {
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// orderings of channels of interchain accounts registered before the migration,
+    /// the contract didn't store them
+    #[serde(default)]
+    pub ica_orderings: Vec<IcaOrdering>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IcaOrdering {
+    pub interchain_account_id: String,
+    pub ordering: Order,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct InstantiateMsg {}
@@ -1,7 +1,10 @@
-use cosmwasm_std::{from_json, to_json_vec, Binary, Order, StdResult, Storage};
+use cosmwasm_std::{from_json, to_json_vec, Binary, Order, StdError, StdResult, Storage};
 use cw_storage_plus::{Item, Map};
+use neutron_sdk::interchain_txs::ica_manager::{IcaManager, IcaRecord, IcaState};
+use neutron_std::types::ibc::core::channel::v1::Order as ChannelOrder;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// SudoPayload is a type that stores information about a transaction that we try to execute
 /// on the host chain. This is a type introduced for our convenience.
@@ -16,7 +19,10 @@ pub const SUDO_PAYLOAD_REPLY_ID: u64 = 1;
 
 pub const REPLY_ID_STORAGE: Item<Vec<u8>> = Item::new("reply_queue_id");
 pub const SUDO_PAYLOAD: Map<(String, u64), Vec<u8>> = Map::new("sudo_payload");
-pub const INTERCHAIN_ACCOUNTS: Map<String, Option<(String, String)>> =
+pub const INTERCHAIN_ACCOUNTS: IcaManager = IcaManager::new("ica_records");
+/// Interchain accounts stored before `INTERCHAIN_ACCOUNTS`, port_id -> (address, controller_connection_id),
+/// None while the account is being registered. Moved to `INTERCHAIN_ACCOUNTS` on migration.
+pub const LEGACY_INTERCHAIN_ACCOUNTS: Map<String, Option<(String, String)>> =
     Map::new("interchain_accounts");
 
 // interchain transaction responses - ack/err/timeout state to query later
@@ -57,6 +63,52 @@ pub fn add_error_to_queue(store: &mut dyn Storage, error_msg: String) -> Option<
     result.and_then(|idx| ERRORS_QUEUE.save(store, idx, &error_msg).ok())
 }
 
+/// Moves interchain accounts from `LEGACY_INTERCHAIN_ACCOUNTS` to `INTERCHAIN_ACCOUNTS`.
+/// Orderings of the accounts' channels weren't stored, so they're passed in **orderings**
+/// by interchain_account_id. Fees and channels of the accounts are unknown.
+pub fn migrate_interchain_accounts(
+    store: &mut dyn Storage,
+    orderings: &HashMap<String, ChannelOrder>,
+) -> StdResult<()> {
+    let legacy = LEGACY_INTERCHAIN_ACCOUNTS
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (port_id, account) in legacy {
+        let (_, interchain_account_id) = port_id
+            .split_once('.')
+            .ok_or_else(|| StdError::msg(format!("invalid port_id {}", port_id)))?;
+        let ordering = *orderings.get(interchain_account_id).ok_or_else(|| {
+            StdError::msg(format!(
+                "ordering of interchain account {} is not provided",
+                interchain_account_id
+            ))
+        })?;
+        let (state, address, connection_id) = match account {
+            Some((address, connection_id)) => (IcaState::Open, Some(address), connection_id),
+            // the connection of an account being registered wasn't stored, it's set on OpenAck
+            None => (IcaState::Registering, None, String::new()),
+        };
+        INTERCHAIN_ACCOUNTS.save(
+            store,
+            &IcaRecord {
+                interchain_account_id: interchain_account_id.to_string(),
+                connection_id,
+                port_id: port_id.clone(),
+                state,
+                address,
+                channel_id: None,
+                counterparty_channel_id: None,
+                ordering,
+                register_fee: vec![],
+            },
+        )?;
+        LEGACY_INTERCHAIN_ACCOUNTS.remove(store, port_id);
+    }
+
+    Ok(())
+}
+
 pub fn read_errors_from_queue(store: &dyn Storage) -> StdResult<Vec<(Vec<u8>, String)>> {
     ERRORS_QUEUE
         .range_raw(store, None, None, Order::Ascending)
@@ -2,14 +2,21 @@ use std::marker::PhantomData;
 
 use crate::{
     contract::query_errors_queue,
-    storage::{add_error_to_queue, read_errors_from_queue, ERRORS_QUEUE},
+    storage::{
+        add_error_to_queue, migrate_interchain_accounts, read_errors_from_queue, ERRORS_QUEUE,
+        INTERCHAIN_ACCOUNTS, LEGACY_INTERCHAIN_ACCOUNTS,
+    },
 };
 
 use cosmwasm_std::{
     from_json,
-    testing::{MockApi, MockQuerier, MockStorage},
-    OwnedDeps,
+    testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR},
+    Order, OwnedDeps,
 };
+use neutron_sdk::interchain_txs::helpers::get_port_id;
+use neutron_sdk::interchain_txs::ica_manager::IcaState;
+use neutron_std::types::ibc::core::channel::v1::Order as ChannelOrder;
+use std::collections::HashMap;
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
     OwnedDeps {
@@ -76,3 +83,52 @@ fn test_errors_queue() {
         ]
     );
 }
+
+#[test]
+fn test_migrate_interchain_accounts() {
+    let mut deps = mock_dependencies();
+    let open_port_id = get_port_id(MOCK_CONTRACT_ADDR, "open");
+    let registering_port_id = get_port_id(MOCK_CONTRACT_ADDR, "registering");
+
+    LEGACY_INTERCHAIN_ACCOUNTS
+        .save(
+            &mut deps.storage,
+            open_port_id.clone(),
+            &Some(("cosmos1address".to_string(), "connection-0".to_string())),
+        )
+        .unwrap();
+    LEGACY_INTERCHAIN_ACCOUNTS
+        .save(&mut deps.storage, registering_port_id.clone(), &None)
+        .unwrap();
+
+    // orderings of all the legacy accounts must be provided
+    let mut orderings = HashMap::from([("open".to_string(), ChannelOrder::Unordered)]);
+    assert!(migrate_interchain_accounts(&mut deps.storage, &orderings).is_err());
+    orderings.insert("registering".to_string(), ChannelOrder::Ordered);
+    migrate_interchain_accounts(&mut deps.storage, &orderings).unwrap();
+
+    let open = INTERCHAIN_ACCOUNTS
+        .load_by_port(&deps.storage, &open_port_id)
+        .unwrap();
+    assert_eq!(open.interchain_account_id, "open");
+    assert_eq!(open.connection_id, "connection-0");
+    assert_eq!(open.state, IcaState::Open);
+    assert_eq!(open.address, Some("cosmos1address".to_string()));
+    assert_eq!(open.ordering, ChannelOrder::Unordered);
+
+    let registering = INTERCHAIN_ACCOUNTS
+        .load_by_port(&deps.storage, &registering_port_id)
+        .unwrap();
+    assert_eq!(registering.interchain_account_id, "registering");
+    assert_eq!(registering.state, IcaState::Registering);
+    assert_eq!(registering.address, None);
+    assert_eq!(registering.connection_id, "");
+    assert_eq!(registering.ordering, ChannelOrder::Ordered);
+
+    assert_eq!(
+        LEGACY_INTERCHAIN_ACCOUNTS
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .count(),
+        0
+    );
+}
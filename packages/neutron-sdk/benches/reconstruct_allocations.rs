@@ -0,0 +1,196 @@
+//! Measures heap allocations made by owned (`KVReconstruct`) and zero-copy (`KVReconstructRef`)
+//! reconstructors of the biggest KV query results.
+//!
+//! Every allocation costs gas inside a contract, so the numbers are a cheap proxy of gas spent
+//! in `sudo_kv_query_result` handlers. Run with:
+//! ```text
+//! cargo bench -p neutron-sdk --bench reconstruct_allocations
+//! ```
+
+use cosmos_sdk_proto::cosmos::staking::v1beta1::Validator as CosmosValidator;
+use cosmwasm_std::{Addr, Coin, Decimal};
+use neutron_sdk::interchain_queries::legacy_dec::LegacyDec;
+use neutron_sdk::interchain_queries::types::{KVConstruct, KVReconstruct, KVReconstructRef};
+use neutron_sdk::interchain_queries::v045::types::{
+    Balances, BalancesRef, Delegations, DelegationsContext, DelegationsRef, StakingValidator,
+    StakingValidatorRef, StdDelegation, Validator,
+};
+use neutron_sdk::interchain_queries::v047::types::{
+    Balances as V047Balances, BalancesRef as V047BalancesRef, Delegations as V047Delegations,
+    DelegationsRef as V047DelegationsRef,
+};
+use neutron_std::types::neutron::interchainqueries::StorageValue;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts allocations and allocated bytes, reallocations are counted as new allocations
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns number of allocations and allocated bytes made by **f**
+fn measure<T>(f: impl FnOnce() -> T) -> (usize, usize) {
+    let (allocations, bytes) = (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    );
+    let result = black_box(f());
+    let measured = (
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes,
+    );
+    drop(result);
+
+    measured
+}
+
+fn report<'a, T, R>(name: &str, storage_values: &'a [StorageValue])
+where
+    T: KVReconstruct,
+    R: KVReconstructRef<'a>,
+{
+    let (owned, owned_bytes) = measure(|| T::reconstruct(storage_values).unwrap());
+    let (borrowed, borrowed_bytes) = measure(|| R::reconstruct_ref(storage_values).unwrap());
+
+    println!(
+        "{:<24} {:>6} {:>12} {:>12} {:>12} {:>12}",
+        name,
+        storage_values.len(),
+        owned,
+        owned_bytes,
+        borrowed,
+        borrowed_bytes
+    );
+}
+
+const DELEGATOR: &str = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs";
+const VALIDATORS: [&str; 2] = [
+    "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3",
+    "osmovaloper1lzhlnpahvznwfv4jmay2tgaha5kmz5qxwmj9we",
+];
+
+fn balances(count: usize) -> Balances {
+    Balances {
+        coins: (0..count)
+            .map(|i| Coin::new(1_000_000u128 + i as u128, format!("ibc/{:064X}", i)))
+            .collect(),
+    }
+}
+
+fn delegations(count: usize) -> Delegations {
+    Delegations {
+        delegations: (0..count)
+            .map(|i| StdDelegation {
+                delegator: Addr::unchecked(DELEGATOR),
+                validator: VALIDATORS[i % VALIDATORS.len()].to_string(),
+                amount: Coin::new(1_000_000u128 + i as u128, "uosmo"),
+            })
+            .collect(),
+    }
+}
+
+fn delegations_context() -> DelegationsContext {
+    DelegationsContext {
+        denom: "uosmo".to_string(),
+        validators: VALIDATORS
+            .iter()
+            .map(|operator_address| CosmosValidator {
+                operator_address: operator_address.to_string(),
+                tokens: "1000000000000".to_string(),
+                delegator_shares: "1000000000000000000000000000000".to_string(),
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+fn validators(count: usize) -> StakingValidator {
+    StakingValidator {
+        validators: (0..count)
+            .map(|i| Validator {
+                operator_address: VALIDATORS[i % VALIDATORS.len()].to_string(),
+                jailed: false,
+                status: 3,
+                tokens: "1000000000000".to_string(),
+                delegator_shares: "1000000000000000000000000000000".to_string(),
+                consensus_pubkey: Some(vec![7; 32]),
+                moniker: Some(format!("validator {}", i)),
+                identity: Some("5A2F3B4C6D7E8F90".to_string()),
+                website: Some("https://neutron.org".to_string()),
+                security_contact: Some("security@neutron.org".to_string()),
+                details: Some("A validator of the remote chain".to_string()),
+                unbonding_height: 0,
+                unbonding_time: Some(1_700_000_000),
+                rate: Some(LegacyDec::from_str("0.05").unwrap()),
+                max_rate: Some(LegacyDec::from_str("0.2").unwrap()),
+                max_change_rate: Some(LegacyDec::from_str("0.01").unwrap()),
+                update_time: Some(1_700_000_000),
+                min_self_delegation: Decimal::one(),
+            })
+            .collect(),
+    }
+}
+
+fn main() {
+    println!(
+        "{:<24} {:>6} {:>12} {:>12} {:>12} {:>12}",
+        "reconstructor", "values", "owned", "owned B", "borrowed", "borrowed B"
+    );
+
+    for count in [10, 50, 100] {
+        report::<Balances, BalancesRef>(
+            "v045 balances",
+            &balances(count).construct(DELEGATOR).unwrap(),
+        );
+        report::<V047Balances, V047BalancesRef>(
+            "v047 balances",
+            &V047Balances {
+                coins: balances(count).coins,
+            }
+            .construct(DELEGATOR)
+            .unwrap(),
+        );
+        report::<Delegations, DelegationsRef>(
+            "v045 delegations",
+            &delegations(count)
+                .construct(&delegations_context())
+                .unwrap(),
+        );
+        report::<V047Delegations, V047DelegationsRef>(
+            "v047 delegations",
+            &V047Delegations {
+                delegations: delegations(count).delegations,
+            }
+            .construct(&delegations_context())
+            .unwrap(),
+        );
+        report::<StakingValidator, StakingValidatorRef>(
+            "v045 staking validators",
+            &validators(count).construct(&()).unwrap(),
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Per connection profiles of remote chains.
+//!
+//! A contract working with several chains keeps a `ChainProfile` for every IBC connection and
+//! uses it to pick the right Cosmos SDK version module (`v045` or `v047`) for Interchain Queries
+//! and to check addresses, denoms and ICA channels of the chain instead of passing versions,
+//! prefixes and denoms around by hand.
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::address::{decode_address, split_hrp, AddressKind};
+use crate::interchain_queries::types::KVReconstruct;
+use crate::interchain_queries::{v045, v047};
+use crate::interchain_txs::helpers::decode_tx_msg_data;
+pub use crate::sudo::msg::IcaEncoding;
+use crate::sudo::msg::IcaMetadata;
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, StdResult, Storage};
+use cw_storage_plus::Map;
+use neutron_std::types::neutron::interchainqueries::StorageValue;
+use prost_types::Any;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Version of Cosmos SDK of a remote chain, which defines storage layout of queried modules
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SdkVersion {
+    /// Cosmos SDK 0.45 and 0.46
+    V045,
+    /// Cosmos SDK 0.47 and newer
+    V047,
+}
+
+/// Profile of a remote chain on the other side of an IBC connection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ChainProfile {
+    pub sdk_version: SdkVersion,
+    /// HRP of account addresses, e.g. `cosmos`
+    pub account_hrp: String,
+    /// HRP of validator operator addresses, e.g. `cosmosvaloper`
+    pub validator_hrp: String,
+    /// denom of the staking token, e.g. `uatom`
+    pub bond_denom: String,
+    pub ica_encoding: IcaEncoding,
+}
+
+impl ChainProfile {
+    /// Checks HRPs are of account and validator operator addresses and bond denom is set
+    pub fn validate(&self) -> NeutronResult<()> {
+        if self.account_hrp.is_empty() || split_hrp(&self.account_hrp).1 != AddressKind::Account {
+            return Err(NeutronError::InvalidChainProfile(format!(
+                "{:?} is not an account address prefix",
+                self.account_hrp
+            )));
+        }
+        let (chain_prefix, kind) = split_hrp(&self.validator_hrp);
+        if chain_prefix.is_empty() || kind != AddressKind::ValidatorOperator {
+            return Err(NeutronError::InvalidChainProfile(format!(
+                "{:?} is not a validator operator address prefix",
+                self.validator_hrp
+            )));
+        }
+        if self.bond_denom.is_empty() {
+            return Err(NeutronError::InvalidChainProfile(
+                "bond denom is empty".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks **addr** is an account address of the chain
+    pub fn check_account_address(&self, addr: &str) -> NeutronResult<()> {
+        decode_address(addr, &self.account_hrp).map(|_| ())
+    }
+
+    /// Checks **addr** is a validator operator address of the chain
+    pub fn check_validator_address(&self, addr: &str) -> NeutronResult<()> {
+        decode_address(addr, &self.validator_hrp).map(|_| ())
+    }
+
+    /// Creates a message to register an Interchain Query to get balances of account with **addr**,
+    /// see `new_register_balances_query_msg`
+    pub fn new_register_balances_query_msg(
+        &self,
+        contract: Addr,
+        connection_id: String,
+        addr: String,
+        denoms: Vec<String>,
+        update_period: u64,
+    ) -> NeutronResult<CosmosMsg> {
+        self.check_account_address(&addr)?;
+
+        match self.sdk_version {
+            SdkVersion::V045 => v045::register_queries::new_register_balances_query_msg(
+                contract,
+                connection_id,
+                addr,
+                denoms,
+                update_period,
+            ),
+            SdkVersion::V047 => v047::register_queries::new_register_balances_query_msg(
+                contract,
+                connection_id,
+                addr,
+                denoms,
+                update_period,
+            ),
+        }
+    }
+
+    /// Creates a message to register an Interchain Query to get delegations of **delegator**
+    /// to **validators**, see `new_register_delegator_delegations_query_msg`
+    pub fn new_register_delegator_delegations_query_msg(
+        &self,
+        contract: Addr,
+        connection_id: String,
+        delegator: String,
+        validators: Vec<String>,
+        update_period: u64,
+    ) -> NeutronResult<CosmosMsg> {
+        self.check_account_address(&delegator)?;
+        for validator in &validators {
+            self.check_validator_address(validator)?;
+        }
+
+        match self.sdk_version {
+            SdkVersion::V045 => {
+                v045::register_queries::new_register_delegator_delegations_query_msg(
+                    contract,
+                    connection_id,
+                    delegator,
+                    validators,
+                    update_period,
+                )
+            }
+            SdkVersion::V047 => {
+                v047::register_queries::new_register_delegator_delegations_query_msg(
+                    contract,
+                    connection_id,
+                    delegator,
+                    validators,
+                    update_period,
+                )
+            }
+        }
+    }
+
+    /// Reconstructs balances from result of a query registered with `new_register_balances_query_msg`
+    pub fn reconstruct_balances(
+        &self,
+        storage_values: &[StorageValue],
+    ) -> NeutronResult<Vec<Coin>> {
+        Ok(match self.sdk_version {
+            SdkVersion::V045 => v045::types::Balances::reconstruct(storage_values)?.coins,
+            SdkVersion::V047 => v047::types::Balances::reconstruct(storage_values)?.coins,
+        })
+    }
+
+    /// Reconstructs delegations from result of a query registered with
+    /// `new_register_delegator_delegations_query_msg`, the delegations must be in the bond denom
+    pub fn reconstruct_delegations(
+        &self,
+        storage_values: &[StorageValue],
+    ) -> NeutronResult<Vec<v045::types::StdDelegation>> {
+        let delegations = match self.sdk_version {
+            SdkVersion::V045 => v045::types::Delegations::reconstruct(storage_values)?.delegations,
+            SdkVersion::V047 => v047::types::Delegations::reconstruct(storage_values)?.delegations,
+        };
+        if let Some(delegation) = delegations
+            .iter()
+            .find(|delegation| delegation.amount.denom != self.bond_denom)
+        {
+            return Err(NeutronError::InvalidQueryResultFormat(format!(
+                "delegation denom {:?} is not the bond denom {:?} of the chain",
+                delegation.amount.denom, self.bond_denom
+            )));
+        }
+
+        Ok(delegations)
+    }
+
+    /// Checks **metadata** of an ICA channel opened to the chain negotiated the encoding of the
+    /// chain and the interchain account address is an account address of the chain
+    pub fn check_ica_metadata(&self, metadata: &IcaMetadata) -> NeutronResult<()> {
+        if metadata.encoding != self.ica_encoding {
+            return Err(NeutronError::InvalidIcaMetadata(format!(
+                "encoding {:?} of the channel is not the encoding {:?} of the chain",
+                metadata.encoding.as_str(),
+                self.ica_encoding.as_str()
+            )));
+        }
+
+        self.check_account_address(&metadata.address)
+    }
+
+    /// Decodes an ICA acknowledgement into responses of the executed messages, see
+    /// `decode_tx_msg_data`. The format of the acknowledgement is detected from its fields,
+    /// so it doesn't depend on the SDK version.
+    pub fn decode_acknowledgement_response(&self, data: Binary) -> StdResult<Vec<Any>> {
+        decode_tx_msg_data(data)
+    }
+}
+
+/// Keeps `ChainProfile` of every IBC connection a contract works with
+pub struct ChainProfiles {
+    profiles: Map<String, ChainProfile>,
+}
+
+impl ChainProfiles {
+    /// Creates a storage of profiles under **namespace**
+    pub const fn new(namespace: &'static str) -> Self {
+        ChainProfiles {
+            profiles: Map::new(namespace),
+        }
+    }
+
+    /// Validates and saves **profile** of a chain on the other side of connection with **connection_id**
+    pub fn save(
+        &self,
+        storage: &mut dyn Storage,
+        connection_id: &str,
+        profile: &ChainProfile,
+    ) -> NeutronResult<()> {
+        profile.validate()?;
+        self.profiles
+            .save(storage, connection_id.to_string(), profile)?;
+
+        Ok(())
+    }
+
+    /// Loads profile of a chain on the other side of connection with **connection_id**
+    pub fn load(&self, storage: &dyn Storage, connection_id: &str) -> StdResult<ChainProfile> {
+        self.profiles.load(storage, connection_id.to_string())
+    }
+
+    /// Loads profile of a chain on the other side of connection with **connection_id** if there is one
+    pub fn may_load(
+        &self,
+        storage: &dyn Storage,
+        connection_id: &str,
+    ) -> StdResult<Option<ChainProfile>> {
+        self.profiles.may_load(storage, connection_id.to_string())
+    }
+
+    /// Removes profile of a chain on the other side of connection with **connection_id**
+    pub fn remove(&self, storage: &mut dyn Storage, connection_id: &str) {
+        self.profiles.remove(storage, connection_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod testing;
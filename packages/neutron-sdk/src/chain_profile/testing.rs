@@ -0,0 +1,203 @@
+use crate::chain_profile::{ChainProfile, ChainProfiles, IcaEncoding, SdkVersion};
+use crate::interchain_queries::types::KVConstruct;
+use crate::interchain_queries::v045::types::{Delegations, DelegationsContext, StdDelegation};
+use crate::interchain_queries::v047::types::Delegations as V047Delegations;
+use crate::sudo::msg::IcaMetadata;
+use crate::NeutronError;
+use cosmos_sdk_proto::cosmos::staking::v1beta1::Validator;
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::testing::{MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Addr, Coin as StdCoin};
+
+/// Context to construct delegations to **validators** which have 1.5 shares per token
+fn delegations_context(denom: &str, validators: &[&str]) -> DelegationsContext {
+    DelegationsContext {
+        denom: denom.to_string(),
+        validators: validators
+            .iter()
+            .map(|operator_address| Validator {
+                operator_address: operator_address.to_string(),
+                tokens: "2000000".to_string(),
+                delegator_shares: "3000000000000000000000000".to_string(),
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_chain_profile() {
+    const PROFILES: ChainProfiles = ChainProfiles::new("chain_profiles");
+
+    let delegator = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string();
+    let validator = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string();
+    let contract = Addr::unchecked(MOCK_CONTRACT_ADDR);
+    let mut profile = ChainProfile {
+        sdk_version: SdkVersion::V045,
+        account_hrp: "osmo".to_string(),
+        validator_hrp: "osmovaloper".to_string(),
+        bond_denom: "uosmo".to_string(),
+        ica_encoding: IcaEncoding::Proto3,
+    };
+
+    let mut storage = MockStorage::new();
+    PROFILES
+        .save(&mut storage, "connection-0", &profile)
+        .unwrap();
+    assert_eq!(PROFILES.load(&storage, "connection-0").unwrap(), profile);
+    assert_eq!(PROFILES.may_load(&storage, "connection-1").unwrap(), None);
+    assert!(matches!(
+        PROFILES.save(
+            &mut storage,
+            "connection-1",
+            &ChainProfile {
+                validator_hrp: "osmo".to_string(),
+                ..profile.clone()
+            }
+        ),
+        Err(NeutronError::InvalidChainProfile(_))
+    ));
+
+    // registration is routed to the module of the SDK version
+    assert_eq!(
+        profile
+            .new_register_delegator_delegations_query_msg(
+                contract.clone(),
+                "connection-0".to_string(),
+                delegator.clone(),
+                vec![validator.clone()],
+                10
+            )
+            .unwrap(),
+        crate::interchain_queries::v045::register_queries::new_register_delegator_delegations_query_msg(
+            contract.clone(),
+            "connection-0".to_string(),
+            delegator.clone(),
+            vec![validator.clone()],
+            10
+        )
+        .unwrap()
+    );
+    // addresses of another chain are rejected
+    assert!(matches!(
+        profile.new_register_balances_query_msg(
+            contract.clone(),
+            "connection-0".to_string(),
+            "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z".to_string(),
+            vec!["uosmo".to_string()],
+            10
+        ),
+        Err(NeutronError::Bech32HrpMismatch { .. })
+    ));
+
+    let delegations = Delegations {
+        delegations: vec![StdDelegation {
+            delegator: Addr::unchecked(&delegator),
+            validator: validator.clone(),
+            amount: StdCoin::new(1000u128, "uosmo"),
+        }],
+    };
+    assert_eq!(
+        profile
+            .reconstruct_delegations(
+                &delegations
+                    .construct(&delegations_context("uosmo", &[validator.as_str()]))
+                    .unwrap()
+            )
+            .unwrap(),
+        delegations.delegations
+    );
+    // delegations in another denom are not delegations of the chain
+    assert!(matches!(
+        profile.reconstruct_delegations(
+            &delegations
+                .construct(&delegations_context("uatom", &[validator.as_str()]))
+                .unwrap()
+        ),
+        Err(NeutronError::InvalidQueryResultFormat(_))
+    ));
+
+    // responses of Cosmos SDK 0.45 chains are typed with response type URLs
+    let ack = cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxMsgData {
+        #[allow(deprecated)]
+        data: vec![cosmos_sdk_proto::cosmos::base::abci::v1beta1::MsgData {
+            msg_type: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+            data: vec![1, 2, 3],
+        }],
+        msg_responses: vec![],
+    };
+    let responses = vec![prost_types::Any {
+        type_url: "/cosmos.staking.v1beta1.MsgDelegateResponse".to_string(),
+        value: vec![1, 2, 3],
+    }];
+    assert_eq!(
+        profile
+            .decode_acknowledgement_response(ack.encode_to_vec().into())
+            .unwrap(),
+        responses
+    );
+
+    let metadata = |address: &str, encoding: IcaEncoding| IcaMetadata {
+        version: "ics27-1".to_string(),
+        controller_connection_id: "connection-0".to_string(),
+        host_connection_id: "connection-1".to_string(),
+        address: address.to_string(),
+        encoding,
+        tx_type: "sdk_multi_msg".to_string(),
+    };
+    profile
+        .check_ica_metadata(&metadata(&delegator, IcaEncoding::Proto3))
+        .unwrap();
+    assert!(matches!(
+        profile.check_ica_metadata(&metadata(&delegator, IcaEncoding::Proto3Json)),
+        Err(NeutronError::InvalidIcaMetadata(_))
+    ));
+    assert!(matches!(
+        profile.check_ica_metadata(&metadata(
+            "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z",
+            IcaEncoding::Proto3
+        )),
+        Err(NeutronError::Bech32HrpMismatch { .. })
+    ));
+
+    profile.sdk_version = SdkVersion::V047;
+    assert_eq!(
+        profile
+            .new_register_delegator_delegations_query_msg(
+                contract.clone(),
+                "connection-0".to_string(),
+                delegator.clone(),
+                vec![validator.clone()],
+                10
+            )
+            .unwrap(),
+        crate::interchain_queries::v047::register_queries::new_register_delegator_delegations_query_msg(
+            contract,
+            "connection-0".to_string(),
+            delegator,
+            vec![validator.clone()],
+            10
+        )
+        .unwrap()
+    );
+    let delegations = V047Delegations {
+        delegations: delegations.delegations,
+    };
+    assert_eq!(
+        profile
+            .reconstruct_delegations(
+                &delegations
+                    .construct(&delegations_context("uosmo", &[validator.as_str()]))
+                    .unwrap()
+            )
+            .unwrap(),
+        delegations.delegations
+    );
+    // the format of acknowledgements doesn't depend on the SDK version
+    assert_eq!(
+        profile
+            .decode_acknowledgement_response(ack.encode_to_vec().into())
+            .unwrap(),
+        responses
+    );
+}
@@ -15,12 +15,33 @@ pub enum NeutronError {
     #[error("{0}")]
     FromUTF8Error(#[from] std::string::FromUtf8Error),
 
+    #[error("{0}")]
+    Utf8Error(#[from] std::str::Utf8Error),
+
     #[error("Bech32 error")]
     Bech32(#[from] bech32::Error),
 
+    #[error("bech32 prefix should be {expected:?}, got {actual:?}")]
+    Bech32HrpMismatch { expected: String, actual: String },
+
+    #[error("address {address:?} should be encoded with bech32, not bech32m")]
+    InvalidBech32Variant { address: String },
+
+    #[error("invalid bech32 chain prefix {prefix:?}")]
+    InvalidBech32Prefix { prefix: String },
+
+    #[error("address {address:?} should be {expected}")]
+    AddressKindMismatch { address: String, expected: String },
+
+    #[error("address length should be 20 or 32 bytes, got {actual:?}")]
+    InvalidAddressLength { actual: usize },
+
     #[error("Prost protobuf error")]
     ProstProtobuf(#[from] prost::DecodeError),
 
+    #[error("Invalid protobuf wire format: {0}")]
+    ProtobufWireFormat(String),
+
     #[error("Serde JSON (Wasm) error")]
     SerdeJSONWasm(String),
 
@@ -30,9 +51,27 @@ pub enum NeutronError {
     #[error("invalid reply id: {0}")]
     InvalidReplyID(u64),
 
+    #[error("interchain query is already registered under key {key:?}")]
+    QueryKeyAlreadyRegistered { key: String },
+
+    #[error("Invalid chain profile: {0}")]
+    InvalidChainProfile(String),
+
     #[error("invalid query type: {query_type:?}")]
     InvalidQueryType { query_type: String },
 
+    #[error("Registered query keys are incompatible: {0}")]
+    IncompatibleQueryKeys(String),
+
+    #[error("interchain query can have at most {max} KV keys, got {actual}")]
+    TooManyKvQueryKeys { max: u64, actual: u64 },
+
+    #[error("interchain query can have at most {max} transactions filters, got {actual}")]
+    TooManyTransactionsFilters { max: u64, actual: u64 },
+
+    #[error("contract balance {balance} is not enough to pay interchain query deposit {deposit}")]
+    InsufficientQueryDeposit { deposit: String, balance: String },
+
     #[error("Decimal range exceeded")]
     DecimalRangeExceeded(#[from] DecimalRangeExceeded),
 
@@ -42,14 +81,60 @@ pub enum NeutronError {
     #[error("Overflow error")]
     OverflowError(#[from] OverflowError),
 
+    #[error("Invalid update period controller: {0}")]
+    InvalidUpdatePeriodController(String),
+
+    #[error("snapshot retention must be positive")]
+    ZeroSnapshotRetention {},
+
     #[error("Invalid query result format: {0}")]
     InvalidQueryResultFormat(String),
 
+    #[error("Invalid KV construct context: {0}")]
+    InvalidKVConstructContext(String),
+
     #[error("Integration tests mock is active")]
     IntegrationTestsMock {},
 
     #[error("Can't deconstruct account denom balance key: {0}")]
     AccountDenomBalanceKeyDeconstructionError(String),
+
+    #[error("ICA host is disabled on remote chain")]
+    IcaHostDisabled {},
+
+    #[error("message {type_url:?} is not allowed by ICA host on remote chain")]
+    IcaHostMessageNotAllowed { type_url: String },
+
+    #[error("Invalid interchain transaction: {0}")]
+    InvalidIcaTxMsg(String),
+
+    #[error("interchain transaction can have at most {max} messages, got {actual}")]
+    TooManyIcaTxMsgs { max: usize, actual: usize },
+
+    #[error("acknowledgement should have {expected} message responses, got {actual}")]
+    MsgResponsesCountMismatch { expected: usize, actual: usize },
+
+    #[error("message response {index} should be {expected:?}, got {actual:?}")]
+    UnexpectedMsgResponse {
+        index: usize,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("interchain account on port {port_id:?} is not tracked")]
+    IcaNotFound { port_id: String },
+
+    #[error("interchain account on port {port_id:?} is in unexpected state {state:?}")]
+    UnexpectedIcaState { port_id: String, state: String },
+
+    #[error("Invalid request packet: {0}")]
+    InvalidRequestPacket(String),
+
+    #[error("Invalid ICA metadata: {0}")]
+    InvalidIcaMetadata(String),
+
+    #[error("unsupported ICA encoding {encoding:?}, expected \"proto3\" or \"proto3json\"")]
+    UnsupportedIcaEncoding { encoding: String },
 }
 
 impl From<serde_json_wasm::de::Error> for NeutronError {
@@ -0,0 +1,146 @@
+//! Bech32 addresses of remote chains: decoding with a check of the prefix (HRP) and length,
+//! encoding and re-encoding of addresses under prefixes of other chains.
+//!
+//! Cosmos SDK chains use one chain prefix for all the addresses and mark validator addresses
+//! with a suffix, e.g. `cosmos`, `cosmosvaloper` and `cosmosvalcons`.
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::types::AddressBytes;
+use bech32::{ToBase32, Variant};
+use std::fmt;
+
+/// Suffix of a chain prefix for validator operator addresses
+pub const VALOPER_SUFFIX: &str = "valoper";
+
+/// Suffix of a chain prefix for validator consensus addresses
+pub const VALCONS_SUFFIX: &str = "valcons";
+
+/// Length of an address derived from a public key
+pub const ADDRESS_LENGTH: usize = 20;
+
+/// Length of a derived address, e.g. an address of a module account, a contract or an ICA
+pub const DERIVED_ADDRESS_LENGTH: usize = 32;
+
+/// Kind of address which is defined by a suffix of its chain prefix
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressKind {
+    /// an account address, e.g. `cosmos1...`, the prefix has no suffix
+    Account,
+    /// a validator operator address, e.g. `cosmosvaloper1...`
+    ValidatorOperator,
+    /// a validator consensus address, e.g. `cosmosvalcons1...`
+    ValidatorConsensus,
+}
+
+impl AddressKind {
+    /// Returns suffix of a chain prefix for this kind of addresses
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            AddressKind::Account => "",
+            AddressKind::ValidatorOperator => VALOPER_SUFFIX,
+            AddressKind::ValidatorConsensus => VALCONS_SUFFIX,
+        }
+    }
+
+    /// Returns HRP of this kind of addresses on a chain with **chain_prefix**, e.g. `cosmosvaloper`
+    pub fn hrp(&self, chain_prefix: &str) -> String {
+        format!("{}{}", chain_prefix, self.suffix())
+    }
+}
+
+impl fmt::Display for AddressKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressKind::Account => write!(f, "an account address"),
+            AddressKind::ValidatorOperator => write!(f, "a validator operator address"),
+            AddressKind::ValidatorConsensus => write!(f, "a validator consensus address"),
+        }
+    }
+}
+
+/// Splits **hrp** into a chain prefix and a kind of addresses, e.g. `cosmosvaloper` into
+/// `cosmos` and `AddressKind::ValidatorOperator`
+pub fn split_hrp(hrp: &str) -> (&str, AddressKind) {
+    for kind in [
+        AddressKind::ValidatorOperator,
+        AddressKind::ValidatorConsensus,
+    ] {
+        if let Some(chain_prefix) = hrp.strip_suffix(kind.suffix()) {
+            return (chain_prefix, kind);
+        }
+    }
+
+    (hrp, AddressKind::Account)
+}
+
+/// Checks length of decoded address **bytes**, it's either 20 or 32 bytes
+pub fn validate_address_bytes(bytes: &[u8]) -> NeutronResult<()> {
+    match bytes.len() {
+        ADDRESS_LENGTH | DERIVED_ADDRESS_LENGTH => Ok(()),
+        actual => Err(NeutronError::InvalidAddressLength { actual }),
+    }
+}
+
+/// Decodes a bech32 **encoded** address of any prefix. Returns its HRP and bytes of valid length
+pub fn decode_address_any(encoded: &str) -> NeutronResult<(String, AddressBytes)> {
+    let (hrp, data, variant) = bech32::decode(encoded)?;
+    if variant != Variant::Bech32 {
+        return Err(NeutronError::InvalidBech32Variant {
+            address: encoded.to_string(),
+        });
+    }
+
+    let bytes = bech32::convert_bits(&data, 5, 8, false)?;
+    validate_address_bytes(&bytes)?;
+
+    Ok((hrp, bytes))
+}
+
+/// Decodes a bech32 **encoded** address checking its HRP equals to **expected_hrp**
+pub fn decode_address(encoded: &str, expected_hrp: &str) -> NeutronResult<AddressBytes> {
+    let (hrp, bytes) = decode_address_any(encoded)?;
+    if hrp != expected_hrp {
+        return Err(NeutronError::Bech32HrpMismatch {
+            expected: expected_hrp.to_string(),
+            actual: hrp,
+        });
+    }
+
+    Ok(bytes)
+}
+
+/// Decodes a bech32 **encoded** address of any chain checking it's the **kind** of address,
+/// e.g. that a validator operator address is not passed instead of an account address
+pub fn decode_address_of_kind(encoded: &str, kind: AddressKind) -> NeutronResult<AddressBytes> {
+    let (hrp, bytes) = decode_address_any(encoded)?;
+    if split_hrp(&hrp).1 != kind {
+        return Err(NeutronError::AddressKindMismatch {
+            address: encoded.to_string(),
+            expected: kind.to_string(),
+        });
+    }
+
+    Ok(bytes)
+}
+
+/// Encodes address **bytes** of valid length with **hrp**
+pub fn encode_address(bytes: &[u8], hrp: &str) -> NeutronResult<String> {
+    validate_address_bytes(bytes)?;
+
+    Ok(bech32::encode(hrp, bytes.to_base32(), Variant::Bech32)?)
+}
+
+/// Re-encodes an **encoded** address for a chain with **chain_prefix** keeping its kind,
+/// e.g. `cosmosvaloper1...` is converted into `osmovaloper1...` for `osmo` chain prefix
+pub fn convert_address(encoded: &str, chain_prefix: &str) -> NeutronResult<String> {
+    if chain_prefix.is_empty() || split_hrp(chain_prefix).1 != AddressKind::Account {
+        return Err(NeutronError::InvalidBech32Prefix {
+            prefix: chain_prefix.to_string(),
+        });
+    }
+
+    let (hrp, bytes) = decode_address_any(encoded)?;
+    let (_, kind) = split_hrp(&hrp);
+
+    encode_address(&bytes, &kind.hrp(chain_prefix))
+}
@@ -0,0 +1,172 @@
+//! Remote chain block time from consensus states of IBC light clients on Neutron.
+//!
+//! Interchain Query results carry only a remote height, but the light client of a connection keeps
+//! a consensus state with a block timestamp for every height it was updated to. These helpers
+//! query the consensus state with Neutron's IBC gRPC queries and convert a `Height` to a remote
+//! `Timestamp`, e.g. to compute unbonding completion or vesting on a remote chain.
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::sudo::msg::Height;
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::{Binary, Deps, StdError, Timestamp};
+use prost_types::Any;
+
+/// gRPC path of IBC query of a connection
+pub const CONNECTION_QUERY_PATH: &str = "/ibc.core.connection.v1.Query/Connection";
+
+/// gRPC path of IBC query of a light client consensus state
+pub const CONSENSUS_STATE_QUERY_PATH: &str = "/ibc.core.client.v1.Query/ConsensusState";
+
+/// Type URL of the Tendermint light client consensus state
+pub const TENDERMINT_CONSENSUS_STATE_TYPE_URL: &str =
+    "/ibc.lightclients.tendermint.v1.ConsensusState";
+
+/// QueryConnectionRequest protobuf type
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryConnectionRequest {
+    #[prost(string, tag = "1")]
+    pub connection_id: String,
+}
+
+/// Subset of QueryConnectionResponse protobuf type fields
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryConnectionResponse {
+    #[prost(message, optional, tag = "1")]
+    pub connection: Option<ConnectionEnd>,
+}
+
+/// Subset of ConnectionEnd protobuf type fields
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ConnectionEnd {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+}
+
+/// QueryConsensusStateRequest protobuf type
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryConsensusStateRequest {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(uint64, tag = "2")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "3")]
+    pub revision_height: u64,
+    #[prost(bool, tag = "4")]
+    pub latest_height: bool,
+}
+
+/// Subset of QueryConsensusStateResponse protobuf type fields
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryConsensusStateResponse {
+    #[prost(message, optional, tag = "1")]
+    pub consensus_state: Option<Any>,
+}
+
+/// Subset of Tendermint light client ConsensusState protobuf type fields
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TendermintConsensusState {
+    #[prost(message, optional, tag = "1")]
+    pub timestamp: Option<prost_types::Timestamp>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub next_validators_hash: Vec<u8>,
+}
+
+fn query_grpc<Req: Message, Res: Message + Default>(
+    deps: Deps,
+    path: &str,
+    request: Req,
+) -> NeutronResult<Res> {
+    let data = deps
+        .querier
+        .query_grpc(path.to_string(), Binary::new(request.encode_to_vec()))?;
+
+    Ok(Res::decode(data.as_slice())?)
+}
+
+/// Queries ID of the light client of an IBC connection with **connection_id**
+pub fn get_connection_client_id(deps: Deps, connection_id: &str) -> NeutronResult<String> {
+    let res: QueryConnectionResponse = query_grpc(
+        deps,
+        CONNECTION_QUERY_PATH,
+        QueryConnectionRequest {
+            connection_id: connection_id.to_string(),
+        },
+    )?;
+
+    Ok(res
+        .connection
+        .ok_or_else(|| StdError::msg(format!("no connection {}", connection_id)))?
+        .client_id)
+}
+
+/// Queries consensus state of the light client of an IBC connection with **connection_id**
+/// at remote **height**.
+///
+/// A light client keeps consensus states only for the heights it was updated to, so the query
+/// fails if the client was never updated to exactly this height.
+pub fn get_consensus_state(
+    deps: Deps,
+    connection_id: &str,
+    height: Height,
+) -> NeutronResult<TendermintConsensusState> {
+    let res: QueryConsensusStateResponse = query_grpc(
+        deps,
+        CONSENSUS_STATE_QUERY_PATH,
+        QueryConsensusStateRequest {
+            client_id: get_connection_client_id(deps, connection_id)?,
+            revision_number: height.revision_number,
+            revision_height: height.revision_height,
+            latest_height: false,
+        },
+    )?;
+
+    let consensus_state = res.consensus_state.ok_or_else(|| {
+        StdError::msg(format!(
+            "no consensus state of {} at height {}-{}",
+            connection_id, height.revision_number, height.revision_height
+        ))
+    })?;
+    if consensus_state.type_url != TENDERMINT_CONSENSUS_STATE_TYPE_URL {
+        return Err(NeutronError::InvalidQueryResultFormat(format!(
+            "expected {} consensus state, got {}",
+            TENDERMINT_CONSENSUS_STATE_TYPE_URL, consensus_state.type_url
+        )));
+    }
+
+    Ok(TendermintConsensusState::decode(
+        consensus_state.value.as_slice(),
+    )?)
+}
+
+/// Converts a protobuf **timestamp** of a consensus state into `Timestamp`
+pub fn consensus_state_timestamp(
+    consensus_state: &TendermintConsensusState,
+) -> NeutronResult<Timestamp> {
+    let timestamp = consensus_state
+        .timestamp
+        .as_ref()
+        .ok_or_else(|| StdError::msg("no timestamp in consensus state"))?;
+
+    // a timestamp before the Unix epoch or too far in the future can't be represented
+    u64::try_from(timestamp.seconds)
+        .ok()
+        .zip(u64::try_from(timestamp.nanos).ok())
+        .and_then(|(seconds, nanos)| seconds.checked_mul(1_000_000_000)?.checked_add(nanos))
+        .map(Timestamp::from_nanos)
+        .ok_or_else(|| {
+            NeutronError::InvalidQueryResultFormat(format!(
+                "invalid consensus state timestamp {:?}",
+                timestamp
+            ))
+        })
+}
+
+/// Returns time of the block at remote **height** of a chain on the other side of an IBC
+/// connection with **connection_id**, e.g. the height of `SudoMsg::TxQueryResult`
+pub fn get_remote_block_time(
+    deps: Deps,
+    connection_id: &str,
+    height: Height,
+) -> NeutronResult<Timestamp> {
+    consensus_state_timestamp(&get_consensus_state(deps, connection_id, height)?)
+}
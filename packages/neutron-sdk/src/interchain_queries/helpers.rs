@@ -1,14 +1,19 @@
 use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::queries::{
+    get_interchain_queries_params, get_owned_registered_queries,
+};
 use crate::interchain_queries::types::{
     AddressBytes, QueryPayload, QueryType, TransactionFilterItem, MAX_ADDR_LEN,
 };
-use cosmwasm_std::{Addr, CosmosMsg, StdError};
+use cosmwasm_std::{Addr, CosmosMsg, Deps, StdError, Uint256};
+use neutron_std::types::cosmos::base::v1beta1::Coin;
 use neutron_std::types::neutron::interchainqueries::{
     KvKey, MsgRegisterInterchainQuery, MsgRemoveInterchainQueryRequest,
-    MsgUpdateInterchainQueryRequest,
+    MsgUpdateInterchainQueryRequest, Params,
 };
 use serde_json_wasm::to_string;
 use std::fmt::Write as _;
+use std::str::FromStr;
 
 /// Decodes a bech32 encoded string and converts to base64 encoded bytes
 /// <https://github.com/cosmos/cosmos-sdk/blob/ad9e5620fb3445c716e9de45cfcdb56e8f1745bf/types/bech32/bech32.go#L20>
@@ -49,6 +54,10 @@ pub fn length_prefix<AddrBytes: AsRef<[u8]>>(addr: AddrBytes) -> NeutronResult<V
 ///     maximum allowed number of filters is 32.
 /// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
 /// * **update_period** is used to say how often (in neutron blocks) the query must be updated.
+///
+/// The message is not checked against params of Interchain Queries Module, so a registration with
+/// too many keys or without enough balance to pay the deposit fails on chain, see
+/// `register_interchain_query_checked` to check it before sending.
 pub fn register_interchain_query(
     contract: Addr,
     query: QueryPayload,
@@ -77,6 +86,91 @@ pub fn register_interchain_query(
     .into())
 }
 
+/// Same as `register_interchain_query`, but reads params of Interchain Queries Module first to
+/// check the message is accepted by the module:
+/// * the number of KV keys or transactions filters of **query** is within the module limits;
+/// * the **contract** balance is enough to pay the query deposit.
+///
+/// `MsgRegisterInterchainQuery` has no deposit field and no funds are attached to it: the module
+/// charges `query_deposit` of its params from the balance of the sender on registration and returns
+/// it to the one who removes the query. So only the balance of **contract** is checked here.
+///
+/// Typed `new_register_*_query_msg` builders use the unchecked `register_interchain_query`, since
+/// the checks need to query the chain and the builders don't take `Deps`. To check a typed query,
+/// build its keys with the `create_*_query_keys` helpers of the SDK version module and pass them
+/// here as `QueryPayload::KV`.
+pub fn register_interchain_query_checked(
+    deps: Deps,
+    contract: Addr,
+    query: QueryPayload,
+    connection_id: String,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    let params = get_interchain_queries_params(deps)?;
+    check_query_payload(&params, &query)?;
+    check_query_deposit(deps, &contract, &params.query_deposit)?;
+
+    register_interchain_query(contract, query, connection_id, update_period)
+}
+
+/// Checks the number of KV keys or transactions filters of **query** doesn't exceed the limits
+/// set in **params** of Interchain Queries Module
+pub fn check_query_payload(params: &Params, query: &QueryPayload) -> NeutronResult<()> {
+    match query {
+        QueryPayload::KV(keys) if keys.len() as u64 > params.max_kv_query_keys_count => {
+            Err(NeutronError::TooManyKvQueryKeys {
+                max: params.max_kv_query_keys_count,
+                actual: keys.len() as u64,
+            })
+        }
+        QueryPayload::TX(filters) if filters.len() as u64 > params.max_transactions_filters => {
+            Err(NeutronError::TooManyTransactionsFilters {
+                max: params.max_transactions_filters,
+                actual: filters.len() as u64,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks balance of the **contract** is enough to pay query **deposit**
+pub fn check_query_deposit(deps: Deps, contract: &Addr, deposit: &[Coin]) -> NeutronResult<()> {
+    for coin in deposit {
+        let amount = Uint256::from_str(&coin.amount)?;
+        let balance = deps.querier.query_balance(contract, &coin.denom)?;
+        if balance.amount < amount {
+            return Err(NeutronError::InsufficientQueryDeposit {
+                deposit: format!("{}{}", coin.amount, coin.denom),
+                balance: balance.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Concatenates KV keys of several queries into a single payload for `register_interchain_query`,
+/// so they are registered as one Interchain Query and paid with a single deposit.
+///
+/// Results of such a query can be reconstructed as a tuple of the parts in the same order,
+/// every part except the last one must have a known number of keys (see `KVKeysCount` and `Composite`).
+pub fn composite_kv_query_payload(parts: Vec<Vec<KvKey>>) -> QueryPayload {
+    QueryPayload::KV(parts.into_iter().flatten().collect())
+}
+
+/// Appends **new_keys** to **keys** of a registered Interchain Query, so the result has no
+/// duplicated keys. The order of the keys is preserved.
+pub fn merge_kv_keys(keys: Vec<KvKey>, new_keys: Vec<KvKey>) -> Vec<KvKey> {
+    let mut merged: Vec<KvKey> = Vec::with_capacity(keys.len() + new_keys.len());
+    for key in keys.into_iter().chain(new_keys) {
+        if !merged.contains(&key) {
+            merged.push(key)
+        }
+    }
+
+    merged
+}
+
 /// Basic helper to define a update interchain query message:
 /// * **contract** is a contract address that updates the interchain query.
 ///   Must be equal to the contract that sends the message.
@@ -115,6 +209,41 @@ pub fn remove_interchain_query(contract: Addr, query_id: u64) -> NeutronResult<C
     .into())
 }
 
+/// Creates messages to remove Interchain Queries with **query_ids** in a batch, deposits of the
+/// removed queries are returned to the **contract**.
+/// Usually used together with `get_owned_registered_queries` to clean up all the queries of a
+/// contract, e.g. during migration or when a connection is retired.
+pub fn remove_interchain_queries(
+    contract: Addr,
+    query_ids: impl IntoIterator<Item = u64>,
+) -> NeutronResult<Vec<CosmosMsg>> {
+    query_ids
+        .into_iter()
+        .map(|query_id| remove_interchain_query(contract.clone(), query_id))
+        .collect()
+}
+
+/// Number of registered queries requested per page by `remove_owned_interchain_queries`
+const OWNED_QUERIES_PAGE_LIMIT: u64 = 100;
+
+/// Creates messages to remove all Interchain Queries owned by the **contract** and to return
+/// their deposits to it
+///
+/// * **connection_id** is used to remove only the queries on a particular IBC connection, e.g. a retired one.
+pub fn remove_owned_interchain_queries(
+    deps: Deps,
+    contract: Addr,
+    connection_id: Option<String>,
+) -> NeutronResult<Vec<CosmosMsg>> {
+    let registered_queries =
+        get_owned_registered_queries(deps, &contract, connection_id, OWNED_QUERIES_PAGE_LIMIT)?;
+
+    remove_interchain_queries(
+        contract,
+        registered_queries.into_iter().map(|query| query.id),
+    )
+}
+
 const KV_PATH_KEY_DELIMITER: &str = "/";
 
 pub fn kv_key_from_string<S: AsRef<str>>(s: S) -> Option<KvKey> {
@@ -0,0 +1,199 @@
+//! `LegacyDec` (`sdk.Dec` before Cosmos SDK v0.50) which is used by staking, distribution and
+//! other modules of remote chains for shares, rates and amounts of decimal coins.
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::math::{
+    mul_atomics, mul_truncate_atomics, quo_atomics, quo_truncate_atomics,
+};
+use cosmwasm_std::{Decimal256, StdError, Uint256};
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// An unsigned fixed-point decimal with 18 decimal places which reproduces parsing, formatting
+/// and rounding of Cosmos SDK `LegacyDec`, so computed numbers match the ones returned by
+/// queries of remote chain to the last unit.
+///
+/// The SDK uses two encodings for it:
+/// * protobuf (gogoproto `customtype`) stores atomics as an integer string without
+///   a decimal point, e.g. `1` is `1000000000000000000`, see [`LegacyDec::from_atomics_str`];
+/// * JSON (legacy amino, params subspaces) uses a decimal string with exactly 18 decimal places,
+///   e.g. `1.000000000000000000`, see [`FromStr`] and [`fmt::Display`] implementations.
+///
+/// Negative values are not supported since none of the queried values can be negative.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+pub struct LegacyDec(Decimal256);
+
+impl LegacyDec {
+    /// Number of decimal places of `LegacyDec`
+    pub const DECIMAL_PLACES: u32 = Decimal256::DECIMAL_PLACES;
+
+    pub const fn new(value: Decimal256) -> Self {
+        Self(value)
+    }
+
+    pub const fn zero() -> Self {
+        Self(Decimal256::zero())
+    }
+
+    pub const fn one() -> Self {
+        Self(Decimal256::one())
+    }
+
+    /// Creates a decimal from an integer amount, like `LegacyNewDecFromInt` does
+    pub fn from_int(value: Uint256) -> NeutronResult<Self> {
+        Ok(Self(Decimal256::from_atomics(value, 0)?))
+    }
+
+    /// Parses protobuf encoded decimal which is a string of atomics without a decimal point.
+    /// An empty string is a nil decimal, which is zero.
+    pub fn from_atomics_str(value: &str) -> NeutronResult<Self> {
+        if value.is_empty() {
+            return Ok(Self::zero());
+        }
+
+        Ok(Self(Decimal256::new(Uint256::from_str(value)?)))
+    }
+
+    /// Returns protobuf encoding of the decimal which is a string of atomics without a decimal point
+    pub fn to_atomics_string(&self) -> String {
+        self.0.atomics().to_string()
+    }
+
+    pub fn atomics(&self) -> Uint256 {
+        self.0.atomics()
+    }
+
+    pub fn decimal(&self) -> Decimal256 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn checked_add(self, other: Self) -> NeutronResult<Self> {
+        Ok(Self(self.0.checked_add(other.0)?))
+    }
+
+    pub fn checked_sub(self, other: Self) -> NeutronResult<Self> {
+        Ok(Self(self.0.checked_sub(other.0)?))
+    }
+
+    /// Multiplies decimals rounding the result half to even (`Mul`)
+    pub fn checked_mul(self, other: Self) -> NeutronResult<Self> {
+        Ok(Self::from_atomics(mul_atomics(
+            self.atomics(),
+            other.atomics(),
+            Self::DECIMAL_PLACES,
+        )?))
+    }
+
+    /// Multiplies decimals truncating the result (`MulTruncate`)
+    pub fn checked_mul_truncate(self, other: Self) -> NeutronResult<Self> {
+        Ok(Self::from_atomics(mul_truncate_atomics(
+            self.atomics(),
+            other.atomics(),
+            Self::DECIMAL_PLACES,
+        )?))
+    }
+
+    /// Multiplies the decimal by an integer, the result is exact (`MulInt`)
+    pub fn checked_mul_int(self, other: Uint256) -> NeutronResult<Self> {
+        Ok(Self::from_atomics(self.atomics().checked_mul(other)?))
+    }
+
+    /// Divides decimals rounding the result half to even (`Quo`)
+    pub fn checked_quo(self, other: Self) -> NeutronResult<Self> {
+        Ok(Self::from_atomics(quo_atomics(
+            self.atomics(),
+            other.atomics(),
+            Self::DECIMAL_PLACES,
+        )?))
+    }
+
+    /// Divides decimals truncating the result (`QuoTruncate`)
+    pub fn checked_quo_truncate(self, other: Self) -> NeutronResult<Self> {
+        Ok(Self::from_atomics(quo_truncate_atomics(
+            self.atomics(),
+            other.atomics(),
+            Self::DECIMAL_PLACES,
+        )?))
+    }
+
+    /// Divides the decimal by an integer truncating the result (`QuoInt`)
+    pub fn checked_quo_int(self, other: Uint256) -> NeutronResult<Self> {
+        Ok(Self::from_atomics(
+            self.atomics().checked_div(other).map_err(StdError::from)?,
+        ))
+    }
+
+    /// Returns the integer part of the decimal (`TruncateInt`)
+    pub fn truncate_int(&self) -> Uint256 {
+        self.0.to_uint_floor()
+    }
+
+    fn from_atomics(atomics: Uint256) -> Self {
+        Self(Decimal256::new(atomics))
+    }
+}
+
+impl From<Decimal256> for LegacyDec {
+    fn from(value: Decimal256) -> Self {
+        Self(value)
+    }
+}
+
+impl From<LegacyDec> for Decimal256 {
+    fn from(value: LegacyDec) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for LegacyDec {
+    type Err = NeutronError;
+
+    /// Parses a decimal string like `LegacyNewDecFromStr` does, e.g. `0.05` or `5`.
+    /// Strings with more than 18 decimal places, an empty whole or fractional part are rejected.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Decimal256::from_str(value)?))
+    }
+}
+
+impl fmt::Display for LegacyDec {
+    /// Formats the decimal with exactly 18 decimal places like `LegacyDec.String` does
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = Uint256::from(10u128.pow(Self::DECIMAL_PLACES));
+        let whole = self.atomics() / precision;
+        let fractional = (self.atomics() % precision).to_string();
+
+        write!(
+            f,
+            "{}.{:0>width$}",
+            whole,
+            fractional,
+            width = Self::DECIMAL_PLACES as usize
+        )
+    }
+}
+
+impl Serialize for LegacyDec {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for LegacyDec {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        LegacyDec::from_str(&value).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::errors::error::NeutronResult;
+use crate::interchain_queries::address::{decode_address_of_kind, AddressKind};
+use crate::interchain_queries::lsm::types::{
+    TOKENIZE_SHARE_RECORD_PREFIX, TOTAL_LIQUID_STAKED_TOKENS_KEY,
+};
+use crate::interchain_queries::v045::helpers::create_validator_key;
+use crate::interchain_queries::v045::types::STAKING_STORE_KEY;
+use crate::interchain_queries::v047::types::STAKING_PARAMS_KEY;
+use neutron_std::types::neutron::interchainqueries::KvKey;
+
+/// Creates LSM storage key for tokenize share record with **record_id**
+pub fn create_tokenize_share_record_key(record_id: u64) -> NeutronResult<Vec<u8>> {
+    let mut key: Vec<u8> = vec![TOKENIZE_SHARE_RECORD_PREFIX];
+    key.extend_from_slice(record_id.to_be_bytes().as_slice());
+
+    Ok(key)
+}
+
+/// Creates keys for an Interchain Query to get tokenize share records with **records_ids**
+pub fn create_tokenize_share_records_keys(records_ids: Vec<u64>) -> NeutronResult<Vec<KvKey>> {
+    let mut kv_keys: Vec<KvKey> = Vec::with_capacity(records_ids.len());
+
+    for id in records_ids {
+        kv_keys.push(KvKey {
+            path: STAKING_STORE_KEY.to_string(),
+            key: create_tokenize_share_record_key(id)?,
+        })
+    }
+
+    Ok(kv_keys)
+}
+
+/// Creates LSM storage key for total liquid staked tokens
+pub fn create_total_liquid_staked_tokens_key() -> NeutronResult<Vec<u8>> {
+    Ok(vec![TOTAL_LIQUID_STAKED_TOKENS_KEY])
+}
+
+/// Creates keys for an Interchain Query to get liquid shares of **validators**.
+/// Liquid shares are a part of the validator structure in LSM, so these are usual validator keys.
+pub fn create_validators_liquid_shares_keys(validators: Vec<String>) -> NeutronResult<Vec<KvKey>> {
+    let mut kv_keys: Vec<KvKey> = Vec::with_capacity(validators.len());
+
+    for validator in validators {
+        let val_addr = decode_address_of_kind(&validator, AddressKind::ValidatorOperator)?;
+
+        kv_keys.push(KvKey {
+            path: STAKING_STORE_KEY.to_string(),
+            key: create_validator_key(&val_addr)?,
+        })
+    }
+
+    Ok(kv_keys)
+}
+
+/// Creates key for an Interchain Query to get staking params including LSM caps
+pub fn create_lsm_params_key() -> KvKey {
+    KvKey {
+        path: STAKING_STORE_KEY.to_string(),
+        key: vec![STAKING_PARAMS_KEY],
+    }
+}
@@ -0,0 +1,13 @@
+//! Interchain Queries helpers for the Gaia liquid staking module (LSM).
+//!
+//! LSM extends the Cosmos SDK **staking** module of the Cosmos Hub with tokenize share records
+//! and liquid staking caps. Its data is kept in the **staking** module's storage, so the keys
+//! below are built on top of the usual staking keys.
+
+pub mod helpers;
+pub mod queries;
+pub mod register_queries;
+pub mod types;
+
+#[cfg(test)]
+mod testing;
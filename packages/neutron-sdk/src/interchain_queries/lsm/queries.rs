@@ -0,0 +1,117 @@
+use crate::{
+    interchain_queries::{
+        lsm::types::{
+            LsmParams, TokenizeShareRecords, TotalLiquidStakedTokens, ValidatorsLiquidShares,
+        },
+        queries::{check_query_type, get_registered_query, query_kv_result},
+        types::QueryType,
+    },
+    NeutronResult,
+};
+use cosmwasm_std::{Deps, Env};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TokenizeShareRecordsResponse {
+    pub records: TokenizeShareRecords,
+    pub last_submitted_local_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TotalLiquidStakedTokensResponse {
+    pub total: TotalLiquidStakedTokens,
+    pub last_submitted_local_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ValidatorsLiquidSharesResponse {
+    pub validators: ValidatorsLiquidShares,
+    pub last_submitted_local_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct LsmParamsResponse {
+    pub params: LsmParams,
+    pub last_submitted_local_height: u64,
+}
+
+/// Returns tokenize share records from remote chain
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_tokenize_share_records(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<TokenizeShareRecordsResponse> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let records: TokenizeShareRecords = query_kv_result(deps, registered_query_id)?;
+
+    Ok(TokenizeShareRecordsResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        records,
+    })
+}
+
+/// Returns total liquid staked tokens on remote chain
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_total_liquid_staked_tokens(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<TotalLiquidStakedTokensResponse> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let total: TotalLiquidStakedTokens = query_kv_result(deps, registered_query_id)?;
+
+    Ok(TotalLiquidStakedTokensResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        total,
+    })
+}
+
+/// Returns liquid shares of validators on remote chain
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_validators_liquid_shares(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<ValidatorsLiquidSharesResponse> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let validators: ValidatorsLiquidShares = query_kv_result(deps, registered_query_id)?;
+
+    Ok(ValidatorsLiquidSharesResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        validators,
+    })
+}
+
+/// Returns LSM params of remote chain
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_lsm_params(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<LsmParamsResponse> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let params: LsmParams = query_kv_result(deps, registered_query_id)?;
+
+    Ok(LsmParamsResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        params,
+    })
+}
@@ -0,0 +1,96 @@
+use crate::errors::error::NeutronResult;
+use crate::interchain_queries::helpers::register_interchain_query;
+use crate::interchain_queries::lsm::helpers::{
+    create_lsm_params_key, create_tokenize_share_records_keys,
+    create_total_liquid_staked_tokens_key, create_validators_liquid_shares_keys,
+};
+use crate::interchain_queries::types::QueryPayload;
+use crate::interchain_queries::v045::types::STAKING_STORE_KEY;
+use cosmwasm_std::{Addr, CosmosMsg};
+use neutron_std::types::neutron::interchainqueries::KvKey;
+
+/// Creates a message to register an Interchain Query to get tokenize share records on remote chain
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **records_ids** is a list of tokenize share records ids from remote chain;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_tokenize_share_records_query_msg(
+    contract: Addr,
+    connection_id: String,
+    records_ids: Vec<u64>,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    let kv_keys = create_tokenize_share_records_keys(records_ids)?;
+
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(kv_keys),
+        connection_id,
+        update_period,
+    )
+}
+
+/// Creates a message to register an Interchain Query to get total liquid staked tokens on remote chain
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_total_liquid_staked_tokens_query_msg(
+    contract: Addr,
+    connection_id: String,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    let kv_key = KvKey {
+        path: STAKING_STORE_KEY.to_string(),
+        key: create_total_liquid_staked_tokens_key()?,
+    };
+
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(vec![kv_key]),
+        connection_id,
+        update_period,
+    )
+}
+
+/// Creates a message to register an Interchain Query to get liquid shares of validators on remote chain
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **validators** is a list of validators operator addresses on remote chain;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_validators_liquid_shares_query_msg(
+    contract: Addr,
+    connection_id: String,
+    validators: Vec<String>,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    let kv_keys = create_validators_liquid_shares_keys(validators)?;
+
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(kv_keys),
+        connection_id,
+        update_period,
+    )
+}
+
+/// Creates a message to register an Interchain Query to get LSM params (global and per-validator caps)
+/// on remote chain
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_lsm_params_query_msg(
+    contract: Addr,
+    connection_id: String,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(vec![create_lsm_params_key()]),
+        connection_id,
+        update_period,
+    )
+}
@@ -0,0 +1,204 @@
+use crate::interchain_queries::lsm::helpers::{
+    create_tokenize_share_record_key, create_total_liquid_staked_tokens_key,
+};
+use crate::interchain_queries::lsm::types::{
+    LsmParams, LsmStakingParams, LsmTokenizeShareRecord, LsmValidator, TokenizeShareRecord,
+    TokenizeShareRecords, TotalLiquidStakedTokens, ValidatorLiquidShares, ValidatorsLiquidShares,
+    TOKENIZE_SHARE_RECORD_PREFIX,
+};
+use crate::interchain_queries::types::KVReconstruct;
+use crate::interchain_queries::v045::types::STAKING_STORE_KEY;
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::{Decimal256, Uint256};
+use neutron_std::types::neutron::interchainqueries::StorageValue;
+use std::str::FromStr;
+
+fn storage_value(key: Vec<u8>, value: Vec<u8>) -> StorageValue {
+    StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key,
+        value,
+        proof: None,
+    }
+}
+
+#[test]
+fn test_create_tokenize_share_record_key() {
+    assert_eq!(
+        create_tokenize_share_record_key(1).unwrap(),
+        vec![TOKENIZE_SHARE_RECORD_PREFIX, 0, 0, 0, 0, 0, 0, 0, 1]
+    );
+}
+
+#[test]
+fn test_tokenize_share_records_reconstruct() {
+    let record = LsmTokenizeShareRecord {
+        id: 1,
+        owner: "cosmos1owner".to_string(),
+        module_account: "tokenizeshare_1".to_string(),
+        validator: "cosmosvaloper1validator".to_string(),
+    };
+
+    let st_values = vec![
+        storage_value(
+            create_tokenize_share_record_key(1).unwrap(),
+            record.encode_to_vec(),
+        ),
+        // record 2 doesn't exist on remote chain
+        storage_value(create_tokenize_share_record_key(2).unwrap(), vec![]),
+    ];
+
+    assert_eq!(
+        TokenizeShareRecords::reconstruct(&st_values).unwrap(),
+        TokenizeShareRecords {
+            records: vec![TokenizeShareRecord {
+                id: 1,
+                owner: "cosmos1owner".to_string(),
+                module_account: "tokenizeshare_1".to_string(),
+                validator: "cosmosvaloper1validator".to_string(),
+            }]
+        }
+    );
+}
+
+#[test]
+fn test_total_liquid_staked_tokens_reconstruct() {
+    let st_values = vec![storage_value(
+        create_total_liquid_staked_tokens_key().unwrap(),
+        "123456789".as_bytes().to_vec(),
+    )];
+
+    assert_eq!(
+        TotalLiquidStakedTokens::reconstruct(&st_values).unwrap(),
+        TotalLiquidStakedTokens {
+            amount: Uint256::from(123456789u64)
+        }
+    );
+    assert!(TotalLiquidStakedTokens::reconstruct(&[]).is_err());
+}
+
+#[test]
+fn test_lsm_caps() {
+    let params = LsmParams::reconstruct(&[storage_value(
+        vec![0x51],
+        LsmStakingParams {
+            bond_denom: "uatom".to_string(),
+            validator_bond_factor: "250000000000000000000".to_string(),
+            global_liquid_staking_cap: "250000000000000000".to_string(),
+            validator_liquid_staking_cap: "500000000000000000".to_string(),
+        }
+        .encode_to_vec(),
+    )])
+    .unwrap();
+    assert_eq!(
+        params,
+        LsmParams {
+            bond_denom: "uatom".to_string(),
+            validator_bond_factor: Some(Decimal256::from_str("250").unwrap()),
+            global_liquid_staking_cap: Decimal256::from_str("0.25").unwrap(),
+            validator_liquid_staking_cap: Decimal256::from_str("0.5").unwrap(),
+        }
+    );
+
+    let validators = ValidatorsLiquidShares::reconstruct(&[storage_value(
+        vec![],
+        LsmValidator {
+            operator_address: "cosmosvaloper1validator".to_string(),
+            tokens: "1000".to_string(),
+            delegator_shares: "1000000000000000000000".to_string(),
+            validator_bond_shares: "1000000000000000000".to_string(),
+            liquid_shares: "400000000000000000000".to_string(),
+        }
+        .encode_to_vec(),
+    )])
+    .unwrap();
+    let validator: &ValidatorLiquidShares = &validators.validators[0];
+    assert_eq!(
+        validator.liquid_shares,
+        Decimal256::from_str("400").unwrap()
+    );
+
+    // 200 liquid of 1000 staked, adding 100 gives 300 / 1100 > 0.25
+    assert!(params
+        .exceeds_global_cap(
+            Uint256::from(200u64),
+            Uint256::from(1000u64),
+            Uint256::from(100u64),
+            false
+        )
+        .unwrap());
+    // 200 liquid of 1000 staked, tokenizing bonded 50 gives 250 / 1000 == 0.25
+    assert!(!params
+        .exceeds_global_cap(
+            Uint256::from(200u64),
+            Uint256::from(1000u64),
+            Uint256::from(50u64),
+            true
+        )
+        .unwrap());
+
+    // 375000000000000001 / 1500000000000000000 is 0.25 and 2/3 of the last decimal place,
+    // which is rounded up to exceed the cap like LSM does
+    assert!(params
+        .exceeds_global_cap(
+            Uint256::from(375000000000000000u64),
+            Uint256::from(1500000000000000000u64),
+            Uint256::one(),
+            true
+        )
+        .unwrap());
+    // a half of the last decimal place is rounded to even which is the cap
+    assert!(!params
+        .exceeds_global_cap(
+            Uint256::from(500000000000000000u64),
+            Uint256::from(2000000000000000000u64),
+            Uint256::one(),
+            true
+        )
+        .unwrap());
+
+    // nothing is staked, like LSM the cap isn't exceeded
+    assert!(!params
+        .exceeds_global_cap(Uint256::zero(), Uint256::zero(), Uint256::zero(), true)
+        .unwrap());
+
+    // (400 + 100) / 1000 == 0.5
+    assert!(!params
+        .exceeds_validator_cap(validator, Decimal256::from_str("100").unwrap(), true)
+        .unwrap());
+    // (400 + 100.0000000000000007) / 1000 is rounded up to exceed the cap like LSM does
+    assert!(params
+        .exceeds_validator_cap(
+            validator,
+            Decimal256::from_str("100.0000000000000007").unwrap(),
+            true
+        )
+        .unwrap());
+    // (400 + 101) / 1000 > 0.5
+    assert!(params
+        .exceeds_validator_cap(validator, Decimal256::from_str("101").unwrap(), true)
+        .unwrap());
+
+    let empty_validator = ValidatorLiquidShares {
+        tokens: Uint256::zero(),
+        delegator_shares: Decimal256::zero(),
+        liquid_shares: Decimal256::zero(),
+        ..validator.clone()
+    };
+    assert!(!params
+        .exceeds_validator_cap(&empty_validator, Decimal256::zero(), true)
+        .unwrap());
+
+    // max liquid shares are 1 * 250 = 250 which are exceeded already
+    assert!(params
+        .exceeds_validator_bond_cap(validator, Decimal256::one())
+        .unwrap());
+
+    let params = LsmParams {
+        validator_bond_factor: None,
+        ..params
+    };
+    assert!(!params
+        .exceeds_validator_bond_cap(validator, Decimal256::one())
+        .unwrap());
+}
@@ -0,0 +1,305 @@
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::legacy_dec::LegacyDec;
+use crate::interchain_queries::types::{KVKeysCount, KVReconstruct};
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::{Decimal256, Uint256};
+use neutron_std::types::neutron::interchainqueries::StorageValue;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::str::{from_utf8, FromStr};
+
+/// Key for tokenize share records in the LSM **staking** module's storage
+pub const TOKENIZE_SHARE_RECORD_PREFIX: u8 = 0x81;
+
+/// Key for total liquid staked tokens in the LSM **staking** module's storage
+pub const TOTAL_LIQUID_STAKED_TOKENS_KEY: u8 = 0x85;
+
+/// Value of the validator bond factor param which disables the validator bond cap
+const VALIDATOR_BOND_FACTOR_DISABLED: &str = "-1000000000000000000";
+
+/// TokenizeShareRecord protobuf type as it is stored by LSM. Not a part of upstream Cosmos-SDK protobuf types.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LsmTokenizeShareRecord {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(string, tag = "3")]
+    pub module_account: String,
+    #[prost(string, tag = "4")]
+    pub validator: String,
+}
+
+/// Subset of LSM staking Params protobuf type fields. Other fields are skipped while decoding.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LsmStakingParams {
+    #[prost(string, tag = "5")]
+    pub bond_denom: String,
+    #[prost(string, tag = "7")]
+    pub validator_bond_factor: String,
+    #[prost(string, tag = "8")]
+    pub global_liquid_staking_cap: String,
+    #[prost(string, tag = "9")]
+    pub validator_liquid_staking_cap: String,
+}
+
+/// Subset of LSM Validator protobuf type fields. Other fields are skipped while decoding.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LsmValidator {
+    #[prost(string, tag = "1")]
+    pub operator_address: String,
+    #[prost(string, tag = "5")]
+    pub tokens: String,
+    #[prost(string, tag = "6")]
+    pub delegator_shares: String,
+    #[prost(string, tag = "14")]
+    pub validator_bond_shares: String,
+    #[prost(string, tag = "15")]
+    pub liquid_shares: String,
+}
+
+/// Parses gogoproto encoded `sdk.Dec` which is a string without a decimal point, e.g. Dec(1) is 1 + 18 zeros
+fn parse_dec(value: &str) -> NeutronResult<Decimal256> {
+    Ok(LegacyDec::from_atomics_str(value)?.decimal())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// Tokenize share record which binds tokenized delegation shares to their owner
+pub struct TokenizeShareRecord {
+    pub id: u64,
+    /// owner of the record
+    pub owner: String,
+    /// module account which holds tokenized delegation
+    pub module_account: String,
+    /// validator operator address of the tokenized delegation
+    pub validator: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **Tokenize Share Records Interchain Query**.
+/// Contains tokenize share records from remote chain. Records which don't exist on remote chain are skipped.
+pub struct TokenizeShareRecords {
+    pub records: Vec<TokenizeShareRecord>,
+}
+
+impl KVReconstruct for TokenizeShareRecords {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<TokenizeShareRecords> {
+        let mut records = Vec::with_capacity(storage_values.len());
+
+        for kv in storage_values {
+            if kv.value.is_empty() {
+                continue;
+            }
+
+            let record: LsmTokenizeShareRecord =
+                LsmTokenizeShareRecord::decode(kv.value.as_slice())?;
+
+            records.push(TokenizeShareRecord {
+                id: record.id,
+                owner: record.owner,
+                module_account: record.module_account,
+                validator: record.validator,
+            })
+        }
+
+        Ok(TokenizeShareRecords { records })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **Total Liquid Staked Tokens Interchain Query**.
+/// Contains total amount of liquid staked tokens on remote chain.
+pub struct TotalLiquidStakedTokens {
+    pub amount: Uint256,
+}
+
+impl KVReconstruct for TotalLiquidStakedTokens {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<TotalLiquidStakedTokens> {
+        let value = storage_values.first().ok_or_else(|| {
+            NeutronError::InvalidQueryResultFormat("storage_values length is 0".into())
+        })?;
+
+        // value is stored as `math.Int` marshaled to a decimal string
+        let amount = if value.value.is_empty() {
+            Uint256::zero()
+        } else {
+            Uint256::from_str(from_utf8(&value.value).map_err(|e| {
+                NeutronError::InvalidQueryResultFormat(format!(
+                    "total liquid staked tokens is not a valid string: {}",
+                    e
+                ))
+            })?)?
+        };
+
+        Ok(TotalLiquidStakedTokens { amount })
+    }
+}
+
+impl KVKeysCount for TotalLiquidStakedTokens {
+    const KEYS_COUNT: usize = 1;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// Liquid staking related part of the validator structure on remote chain
+pub struct ValidatorLiquidShares {
+    pub operator_address: String,
+    /// tokens define the delegated tokens (incl. self-delegation).
+    pub tokens: Uint256,
+    /// delegator_shares defines total shares issued to a validator's delegators.
+    pub delegator_shares: Decimal256,
+    /// validator_bond_shares is a number of shares self bonded from the validator.
+    pub validator_bond_shares: Decimal256,
+    /// liquid_shares is a number of shares either tokenized or owned by a liquid staking provider.
+    pub liquid_shares: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **Validators Liquid Shares Interchain Query**.
+pub struct ValidatorsLiquidShares {
+    pub validators: Vec<ValidatorLiquidShares>,
+}
+
+impl KVReconstruct for ValidatorsLiquidShares {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<ValidatorsLiquidShares> {
+        let mut validators = Vec::with_capacity(storage_values.len());
+
+        for kv in storage_values {
+            let validator: LsmValidator = LsmValidator::decode(kv.value.as_slice())?;
+
+            validators.push(ValidatorLiquidShares {
+                operator_address: validator.operator_address,
+                tokens: Uint256::from_str(&validator.tokens)?,
+                delegator_shares: parse_dec(&validator.delegator_shares)?,
+                validator_bond_shares: parse_dec(&validator.validator_bond_shares)?,
+                liquid_shares: parse_dec(&validator.liquid_shares)?,
+            })
+        }
+
+        Ok(ValidatorsLiquidShares { validators })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **LSM Params Interchain Query**.
+/// Contains liquid staking caps of remote chain.
+pub struct LsmParams {
+    pub bond_denom: String,
+    /// validator_bond_factor is required as a safety check for tokenizing shares and
+    /// delegations from liquid staking providers. `None` means the check is disabled.
+    pub validator_bond_factor: Option<Decimal256>,
+    /// global_liquid_staking_cap represents a cap on the portion of stake that
+    /// comes from liquid staking providers.
+    pub global_liquid_staking_cap: Decimal256,
+    /// validator_liquid_staking_cap represents a cap on the portion of stake that
+    /// comes from liquid staking providers for a specific validator.
+    pub validator_liquid_staking_cap: Decimal256,
+}
+
+impl KVReconstruct for LsmParams {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<LsmParams> {
+        let value = storage_values.first().ok_or_else(|| {
+            NeutronError::InvalidQueryResultFormat("storage_values length is 0".into())
+        })?;
+        if value.value.is_empty() {
+            return Err(NeutronError::InvalidQueryResultFormat(
+                "params is empty".into(),
+            ));
+        }
+
+        let params: LsmStakingParams = LsmStakingParams::decode(value.value.as_slice())?;
+
+        Ok(LsmParams {
+            bond_denom: params.bond_denom,
+            validator_bond_factor: if params.validator_bond_factor == VALIDATOR_BOND_FACTOR_DISABLED
+            {
+                None
+            } else {
+                Some(parse_dec(&params.validator_bond_factor)?)
+            },
+            global_liquid_staking_cap: parse_dec(&params.global_liquid_staking_cap)?,
+            validator_liquid_staking_cap: parse_dec(&params.validator_liquid_staking_cap)?,
+        })
+    }
+}
+
+impl KVKeysCount for LsmParams {
+    const KEYS_COUNT: usize = 1;
+}
+
+impl LsmParams {
+    /// Checks whether liquid staking of **tokens** would exceed the global liquid staking cap.
+    /// Mirrors `CheckExceedsGlobalLiquidStakingCap` of LSM.
+    ///
+    /// * **total_liquid_staked** is a result of the **Total Liquid Staked Tokens Interchain Query**;
+    /// * **total_staked** is a balance of the bonded tokens pool on remote chain;
+    /// * **tokens** is an amount of tokens to be liquid staked;
+    /// * **shares_already_bonded** must be true for tokenization of existing delegations.
+    pub fn exceeds_global_cap(
+        &self,
+        total_liquid_staked: Uint256,
+        total_staked: Uint256,
+        tokens: Uint256,
+        shares_already_bonded: bool,
+    ) -> NeutronResult<bool> {
+        let total_staked = if shares_already_bonded {
+            total_staked
+        } else {
+            total_staked.checked_add(tokens)?
+        };
+        // the cap can't be exceeded if there are no staked tokens
+        if total_staked.is_zero() {
+            return Ok(false);
+        }
+
+        // LSM divides sdk.Dec's, which rounds the percent half to even
+        let liquid_stake_percent = LegacyDec::from_int(total_liquid_staked.checked_add(tokens)?)?
+            .checked_quo(LegacyDec::from_int(total_staked)?)?;
+
+        Ok(liquid_stake_percent.decimal() > self.global_liquid_staking_cap)
+    }
+
+    /// Checks whether liquid staking of **shares** would exceed the validator liquid staking cap.
+    /// Mirrors `CheckExceedsValidatorLiquidStakingCap` of LSM.
+    pub fn exceeds_validator_cap(
+        &self,
+        validator: &ValidatorLiquidShares,
+        shares: Decimal256,
+        shares_already_bonded: bool,
+    ) -> NeutronResult<bool> {
+        let updated_liquid_shares = validator.liquid_shares.checked_add(shares)?;
+        let updated_total_shares = if shares_already_bonded {
+            validator.delegator_shares
+        } else {
+            validator.delegator_shares.checked_add(shares)?
+        };
+        // the cap can't be exceeded if there are no delegator shares
+        if updated_total_shares.is_zero() {
+            return Ok(false);
+        }
+
+        // LSM divides sdk.Dec's, which rounds the percent half to even
+        let liquid_stake_percent = LegacyDec::new(updated_liquid_shares)
+            .checked_quo(LegacyDec::new(updated_total_shares))?;
+
+        Ok(liquid_stake_percent.decimal() > self.validator_liquid_staking_cap)
+    }
+
+    /// Checks whether liquid staking of **shares** would exceed the validator bond cap.
+    /// Mirrors `CheckExceedsValidatorBondCap` of LSM.
+    pub fn exceeds_validator_bond_cap(
+        &self,
+        validator: &ValidatorLiquidShares,
+        shares: Decimal256,
+    ) -> NeutronResult<bool> {
+        let validator_bond_factor = match self.validator_bond_factor {
+            Some(factor) => factor,
+            None => return Ok(false),
+        };
+
+        let max_validator_liquid_shares = validator
+            .validator_bond_shares
+            .checked_mul(validator_bond_factor)?;
+
+        Ok(validator.liquid_shares.checked_add(shares)? > max_validator_liquid_shares)
+    }
+}
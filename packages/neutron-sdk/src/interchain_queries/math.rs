@@ -0,0 +1,88 @@
+//! Arithmetic on atomics of fixed-point decimals which reproduces the rounding of Cosmos SDK
+//! `sdk.Dec` (`LegacyDec`) and Osmosis `osmomath.BigDec`, shared by [`LegacyDec`] and the Osmosis
+//! [`math`] helpers.
+//!
+//! [`LegacyDec`]: crate::interchain_queries::legacy_dec::LegacyDec
+//! [`math`]: crate::interchain_queries::osmosis::math
+
+use crate::errors::error::NeutronResult;
+use cosmwasm_std::{StdError, Uint256, Uint512};
+use std::cmp::Ordering;
+
+/// Returns 10^**decimal_places**
+fn precision(decimal_places: u32) -> Uint512 {
+    Uint512::from(10u128.pow(decimal_places))
+}
+
+/// Divides **value** by **precision** and rounds the result half to even
+/// the same way `chopPrecisionAndRound` does on chain
+fn chop_precision_and_round(value: Uint512, precision: Uint512) -> NeutronResult<Uint256> {
+    let rounded = chop_precision_and_round_512(value, precision)?;
+
+    Ok(Uint256::try_from(rounded).map_err(StdError::from)?)
+}
+
+/// The same as `chop_precision_and_round` for results which don't fit into [`Uint256`],
+/// e.g. powers of `osmomath.BigDec` reserves
+pub(crate) fn chop_precision_and_round_512(
+    value: Uint512,
+    precision: Uint512,
+) -> NeutronResult<Uint512> {
+    let quo = value.checked_div(precision).map_err(StdError::from)?;
+    let rem = value.checked_rem(precision).map_err(StdError::from)?;
+    let two = Uint512::from(2u128);
+
+    let round_up = match rem.cmp(&(precision / two)) {
+        Ordering::Less => false,
+        Ordering::Greater => true,
+        // bankers rounding
+        Ordering::Equal => !(quo % two).is_zero(),
+    };
+
+    if round_up {
+        Ok(quo.checked_add(Uint512::one())?)
+    } else {
+        Ok(quo)
+    }
+}
+
+/// Multiplies two decimals represented by their **atomics** with **decimal_places** and rounds
+/// the result half to even (`Dec.Mul` / `BigDec.Mul`)
+pub fn mul_atomics(a: Uint256, b: Uint256, decimal_places: u32) -> NeutronResult<Uint256> {
+    chop_precision_and_round(a.full_mul(b), precision(decimal_places))
+}
+
+/// Multiplies two decimals represented by their **atomics** with **decimal_places** and truncates
+/// the result (`Dec.MulTruncate` / `BigDec.MulTruncate`)
+pub fn mul_truncate_atomics(a: Uint256, b: Uint256, decimal_places: u32) -> NeutronResult<Uint256> {
+    let quo = a
+        .full_mul(b)
+        .checked_div(precision(decimal_places))
+        .map_err(StdError::from)?;
+
+    Ok(Uint256::try_from(quo).map_err(StdError::from)?)
+}
+
+/// Divides two decimals represented by their **atomics** with **decimal_places** and rounds
+/// the result half to even (`Dec.Quo` / `BigDec.Quo`)
+pub fn quo_atomics(a: Uint256, b: Uint256, decimal_places: u32) -> NeutronResult<Uint256> {
+    let precision = precision(decimal_places);
+    let quo = Uint512::from(a)
+        .checked_mul(precision)?
+        .checked_mul(precision)?
+        .checked_div(Uint512::from(b))
+        .map_err(StdError::from)?;
+
+    chop_precision_and_round(quo, precision)
+}
+
+/// Divides two decimals represented by their **atomics** with **decimal_places** and truncates
+/// the result (`Dec.QuoTruncate` / `BigDec.QuoTruncate`)
+pub fn quo_truncate_atomics(a: Uint256, b: Uint256, decimal_places: u32) -> NeutronResult<Uint256> {
+    let quo = Uint512::from(a)
+        .checked_mul(precision(decimal_places))?
+        .checked_div(Uint512::from(b))
+        .map_err(StdError::from)?;
+
+    Ok(Uint256::try_from(quo).map_err(StdError::from)?)
+}
@@ -1,7 +1,20 @@
+pub mod address;
+pub mod consensus_state;
 pub mod helpers;
+pub mod legacy_dec;
+pub mod lsm;
+pub mod math;
+pub mod osmosis;
+pub(crate) mod proto_reader;
 pub mod queries;
+pub mod registry;
+pub mod snapshots;
 pub mod types;
+pub mod update_period;
 pub mod v045;
 pub mod v047;
 
 pub use queries::{check_query_type, get_registered_query, query_kv_result};
+
+#[cfg(test)]
+mod testing;
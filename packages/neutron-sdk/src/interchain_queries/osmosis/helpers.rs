@@ -0,0 +1,40 @@
+use crate::errors::error::NeutronResult;
+use crate::interchain_queries::osmosis::types::{
+    CONCENTRATED_LIQUIDITY_POOL_PREFIX, CONCENTRATED_LIQUIDITY_STORE_KEY, GAMM_POOL_PREFIX,
+    GAMM_STORE_KEY,
+};
+use neutron_std::types::neutron::interchainqueries::KvKey;
+
+/// Creates **gamm** storage key for pool with **pool_id**.
+/// Balancer and stableswap pools share the same storage.
+pub fn create_gamm_pool_key(pool_id: u64) -> NeutronResult<Vec<u8>> {
+    let mut key: Vec<u8> = vec![GAMM_POOL_PREFIX];
+    key.extend_from_slice(pool_id.to_be_bytes().as_slice());
+
+    Ok(key)
+}
+
+/// Creates key for an Interchain Query to get balancer or stableswap pool with **pool_id**
+pub fn create_gamm_pool_kv_key(pool_id: u64) -> NeutronResult<KvKey> {
+    Ok(KvKey {
+        path: GAMM_STORE_KEY.to_string(),
+        key: create_gamm_pool_key(pool_id)?,
+    })
+}
+
+/// Creates **concentratedliquidity** storage key for pool with **pool_id**.
+/// Unlike **gamm**, pool id is appended to the prefix as a decimal string.
+pub fn create_concentrated_liquidity_pool_key(pool_id: u64) -> NeutronResult<Vec<u8>> {
+    let mut key: Vec<u8> = vec![CONCENTRATED_LIQUIDITY_POOL_PREFIX];
+    key.extend_from_slice(pool_id.to_string().as_bytes());
+
+    Ok(key)
+}
+
+/// Creates key for an Interchain Query to get concentrated liquidity pool with **pool_id**
+pub fn create_concentrated_liquidity_pool_kv_key(pool_id: u64) -> NeutronResult<KvKey> {
+    Ok(KvKey {
+        path: CONCENTRATED_LIQUIDITY_STORE_KEY.to_string(),
+        key: create_concentrated_liquidity_pool_key(pool_id)?,
+    })
+}
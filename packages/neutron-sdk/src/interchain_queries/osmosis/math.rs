@@ -0,0 +1,327 @@
+//! Decimal arithmetic which reproduces the rounding of Cosmos SDK `sdk.Dec` and Osmosis `osmomath`.
+//!
+//! [`Decimal256`] operations truncate results of multiplication and division, while the chain
+//! rounds them half to even. Prices and swap amounts computed with plain [`Decimal256`] operations
+//! may therefore differ from the ones computed on chain, so the functions below should be used instead.
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::math::chop_precision_and_round_512;
+pub use crate::interchain_queries::math::{
+    mul_atomics, mul_truncate_atomics, quo_atomics, quo_truncate_atomics,
+};
+use cosmwasm_std::{Decimal256, StdError, Uint256, Uint512, Uint64};
+use std::cmp::Ordering;
+
+/// Number of decimal places of the Osmosis `osmomath.BigDec` type
+pub const BIG_DEC_PLACES: u32 = 36;
+
+/// Precision of a fractional power approximation in `osmomath.Pow`, which is 10^-8
+const POW_PRECISION: Decimal256 = Decimal256::raw(10_000_000_000);
+
+/// Maximum number of iterations of the approximation algorithms
+const MAX_APPROX_ITERATIONS: u64 = 300;
+
+/// Maximum number of iterations of the stableswap CFMM binary search
+const MAX_CFMM_ITERATIONS: u64 = 256;
+
+/// Multiplicative error tolerance of the stableswap CFMM binary search, which is 10^-12,
+/// as `osmomath.BigDec` atomics
+const CFMM_ERR_TOLERANCE: u128 = 1_000_000_000_000_000_000_000_000;
+
+/// Multiplies **a** by **b** rounding the result half to even like `sdk.Dec.Mul` does
+pub fn dec_mul(a: Decimal256, b: Decimal256) -> NeutronResult<Decimal256> {
+    Ok(Decimal256::new(mul_atomics(
+        a.atomics(),
+        b.atomics(),
+        Decimal256::DECIMAL_PLACES,
+    )?))
+}
+
+/// Multiplies **a** by **b** truncating the result like `sdk.Dec.MulTruncate` does
+pub fn dec_mul_truncate(a: Decimal256, b: Decimal256) -> NeutronResult<Decimal256> {
+    Ok(Decimal256::new(mul_truncate_atomics(
+        a.atomics(),
+        b.atomics(),
+        Decimal256::DECIMAL_PLACES,
+    )?))
+}
+
+/// Divides **a** by **b** rounding the result half to even like `sdk.Dec.Quo` does
+pub fn dec_quo(a: Decimal256, b: Decimal256) -> NeutronResult<Decimal256> {
+    Ok(Decimal256::new(quo_atomics(
+        a.atomics(),
+        b.atomics(),
+        Decimal256::DECIMAL_PLACES,
+    )?))
+}
+
+/// Divides **a** by **b** truncating the result like `sdk.Dec.QuoTruncate` does
+pub fn dec_quo_truncate(a: Decimal256, b: Decimal256) -> NeutronResult<Decimal256> {
+    Ok(Decimal256::new(quo_truncate_atomics(
+        a.atomics(),
+        b.atomics(),
+        Decimal256::DECIMAL_PLACES,
+    )?))
+}
+
+/// Raises **base** to the integer **power** by squaring like `sdk.Dec.Power` does
+pub fn dec_power(base: Decimal256, power: u64) -> NeutronResult<Decimal256> {
+    if power == 0 {
+        return Ok(Decimal256::one());
+    }
+
+    let mut result = base;
+    let mut tmp = Decimal256::one();
+    let mut i = power;
+    while i > 1 {
+        if i % 2 != 0 {
+            tmp = dec_mul(tmp, result)?;
+        }
+        i /= 2;
+        result = dec_mul(result, result)?;
+    }
+
+    dec_mul(result, tmp)
+}
+
+/// Returns square root of **value** computed with Newton's method like `sdk.Dec.ApproxSqrt` does
+pub fn dec_approx_sqrt(value: Decimal256) -> NeutronResult<Decimal256> {
+    if value.is_zero() || value == Decimal256::one() {
+        return Ok(value);
+    }
+
+    let smallest = Decimal256::raw(1);
+    let mut guess = Decimal256::one();
+    let mut delta = Decimal256::one();
+    let mut iteration = 0;
+    while delta > smallest && iteration < MAX_APPROX_ITERATIONS {
+        let prev = if guess.is_zero() { smallest } else { guess };
+
+        // delta = (value / guess - guess) / 2, the division by 2 is truncated towards zero
+        let (diff, negative) = abs_difference_with_sign(dec_quo(value, prev)?, guess);
+        delta = Decimal256::new(diff.atomics() / Uint256::from(2u128));
+
+        guess = if negative {
+            guess.checked_sub(delta)?
+        } else {
+            guess.checked_add(delta)?
+        };
+        iteration += 1;
+    }
+
+    Ok(guess)
+}
+
+/// Raises **base** to the decimal **exp** like `osmomath.Pow` does.
+/// The integer part of the exponent is computed exactly, the fractional one is approximated.
+///
+/// **base** must be greater than 0 and lesser than 2.
+pub fn pow(base: Decimal256, exp: Decimal256) -> NeutronResult<Decimal256> {
+    if base.is_zero() {
+        return Err(NeutronError::Std(StdError::msg(
+            "base must be greater than 0",
+        )));
+    }
+    if base >= Decimal256::percent(200) {
+        return Err(NeutronError::Std(StdError::msg(
+            "base must be lesser than two",
+        )));
+    }
+
+    let integer = exp.floor();
+    let fractional = exp.checked_sub(integer)?;
+
+    let integer_power = Uint64::try_from(integer.to_uint_floor())
+        .map_err(StdError::from)?
+        .u64();
+    let integer_pow = dec_power(base, integer_power)?;
+    if fractional.is_zero() {
+        return Ok(integer_pow);
+    }
+
+    let fractional_pow = pow_approx(base, fractional, POW_PRECISION)?;
+
+    dec_mul(integer_pow, fractional_pow)
+}
+
+/// Approximates **base** raised to the fractional **exp** with a Maclaurin series of (1 + x)^a
+/// like `osmomath.PowApprox` does. The series stops when a term becomes lesser than **precision**.
+pub fn pow_approx(
+    base: Decimal256,
+    exp: Decimal256,
+    precision: Decimal256,
+) -> NeutronResult<Decimal256> {
+    if exp.is_zero() {
+        return Ok(Decimal256::one());
+    }
+    if exp == Decimal256::percent(50) {
+        return dec_approx_sqrt(base);
+    }
+
+    let (x, x_negative) = abs_difference_with_sign(base, Decimal256::one());
+    let mut term = Decimal256::one();
+    let mut sum = Decimal256::one();
+    let mut negative = false;
+    let mut k = Decimal256::zero();
+
+    let mut i: u64 = 1;
+    while term >= precision {
+        // binom(a, i) * x^i = binom(a, i - 1) * x^(i - 1) * (a - (i - 1)) * x / i
+        let (c, c_negative) = abs_difference_with_sign(exp, k);
+        k = Decimal256::from_atomics(Uint256::from(i), 0)?;
+        term = dec_quo(dec_mul(dec_mul(term, c)?, x)?, k)?;
+
+        if term.is_zero() {
+            break;
+        }
+        if x_negative {
+            negative = !negative;
+        }
+        if c_negative {
+            negative = !negative;
+        }
+
+        sum = if negative {
+            sum.checked_sub(term)?
+        } else {
+            sum.checked_add(term)?
+        };
+
+        if i == MAX_APPROX_ITERATIONS {
+            return Err(NeutronError::Std(StdError::msg(
+                "failed to reach precision within max iterations",
+            )));
+        }
+        i += 1;
+    }
+
+    Ok(sum)
+}
+
+/// Returns |**a** - **b**| and whether the difference is negative
+fn abs_difference_with_sign(a: Decimal256, b: Decimal256) -> (Decimal256, bool) {
+    if a >= b {
+        (a - b, false)
+    } else {
+        (b - a, true)
+    }
+}
+
+/// Returns 10^[`BIG_DEC_PLACES`] which is one in `osmomath.BigDec` atomics
+pub fn big_dec_one() -> Uint512 {
+    Uint512::from(10u128.pow(BIG_DEC_PLACES))
+}
+
+/// Multiplies `osmomath.BigDec` atomics **a** and **b** rounding the result half to even like
+/// `BigDec.Mul` does. Unlike [`mul_atomics`] the result may not fit into [`Uint256`].
+pub fn big_dec_mul(a: Uint512, b: Uint512) -> NeutronResult<Uint512> {
+    chop_precision_and_round_512(a.checked_mul(b)?, big_dec_one())
+}
+
+/// Returns the constant of the stableswap CFMM `xy(x^2 + y^2 + w)` where **w_sum_squares** is
+/// a sum of squares of the rest reserves, like `cfmmConstantMultiNoV` does.
+/// All the values are `osmomath.BigDec` atomics.
+pub fn stableswap_cfmm_constant(
+    x_reserve: Uint512,
+    y_reserve: Uint512,
+    w_sum_squares: Uint512,
+) -> NeutronResult<Uint512> {
+    if x_reserve.is_zero() || y_reserve.is_zero() {
+        return Err(NeutronError::Std(StdError::msg(
+            "reserves must be positive",
+        )));
+    }
+
+    let squares = big_dec_mul(x_reserve, x_reserve)?
+        .checked_add(big_dec_mul(y_reserve, y_reserve)?)?
+        .checked_add(w_sum_squares)?;
+
+    big_dec_mul(big_dec_mul(x_reserve, squares)?, y_reserve)
+}
+
+/// Returns the amount of X the stableswap CFMM gives for **y_in** of Y searching the reserve of X
+/// which keeps the CFMM constant the same way `solveCFMMBinarySearchMulti` does.
+/// All the values are `osmomath.BigDec` atomics, **y_in** must be lesser than **y_reserve**.
+pub fn stableswap_solve_cfmm(
+    x_reserve: Uint512,
+    y_reserve: Uint512,
+    w_sum_squares: Uint512,
+    y_in: Uint512,
+) -> NeutronResult<Uint512> {
+    if y_in >= y_reserve {
+        return Err(NeutronError::Std(StdError::msg(
+            "cannot input more than pool reserves",
+        )));
+    }
+
+    let k = stableswap_cfmm_constant(x_reserve, y_reserve, w_sum_squares)?;
+    let y_final = y_reserve.checked_add(y_in)?;
+
+    // the CFMM constant grows with the reserve of X, so the final reserve is searched between
+    // zero and the current reserve
+    let (mut lower, mut upper) = (Uint512::zero(), x_reserve);
+    let mut estimate = binary_search_midpoint(lower, upper)?;
+    let mut output = stableswap_cfmm_constant(estimate, y_final, w_sum_squares)?;
+    for _ in 0..MAX_CFMM_ITERATIONS {
+        match compare_with_cfmm_tolerance(k, output)? {
+            Ordering::Less => upper = estimate,
+            Ordering::Greater => lower = estimate,
+            Ordering::Equal => return Ok(x_reserve.checked_sub(estimate)?),
+        }
+        estimate = binary_search_midpoint(lower, upper)?;
+        output = stableswap_cfmm_constant(estimate, y_final, w_sum_squares)?;
+    }
+
+    Err(NeutronError::Std(StdError::msg(
+        "hit maximum iterations, did not converge fast enough",
+    )))
+}
+
+/// Returns (**lower** + **upper**) / 2 rounded half to even like `BigDec.QuoRaw(2)` does
+fn binary_search_midpoint(lower: Uint512, upper: Uint512) -> NeutronResult<Uint512> {
+    let two = Uint512::from(2u128);
+    chop_precision_and_round_512(lower.checked_add(upper)?, two)
+}
+
+/// Compares **expected** with **actual** like `ErrTolerance.CompareBigDec` does with
+/// the multiplicative tolerance of the CFMM binary search: the values are equal if
+/// |expected - actual| / min(expected, actual) doesn't exceed the tolerance.
+fn compare_with_cfmm_tolerance(expected: Uint512, actual: Uint512) -> NeutronResult<Ordering> {
+    let ordering = expected.cmp(&actual);
+    let (diff, min) = match ordering {
+        Ordering::Equal => return Ok(Ordering::Equal),
+        Ordering::Greater => (expected - actual, actual),
+        Ordering::Less => (actual - expected, expected),
+    };
+
+    // BigDec.Quo truncates diff * 10^72 / min and rounds the result half to even to 36 decimal
+    // places, so the error term exceeds the tolerance if the truncated quotient exceeds
+    // (tolerance + 0.5) * 10^36 (a half is rounded to the even tolerance)
+    let quo = mul_pow10_div_floor(diff, min, 2 * BIG_DEC_PLACES)?;
+    let threshold = Uint512::from(CFMM_ERR_TOLERANCE)
+        .checked_mul(big_dec_one())?
+        .checked_add(big_dec_one() / Uint512::from(2u128))?;
+
+    Ok(if quo > threshold {
+        ordering
+    } else {
+        Ordering::Equal
+    })
+}
+
+/// Returns floor(**a** * 10^**exp** / **b**) by long division, so **a** * 10^**exp** doesn't
+/// have to fit into [`Uint512`]. **exp** must be a multiple of 18.
+fn mul_pow10_div_floor(a: Uint512, b: Uint512, exp: u32) -> NeutronResult<Uint512> {
+    let step = Uint512::from(10u128.pow(18));
+    let mut quo = a.checked_div(b).map_err(StdError::from)?;
+    let mut rem = a.checked_rem(b).map_err(StdError::from)?;
+    for _ in 0..exp / 18 {
+        let shifted = rem.checked_mul(step)?;
+        quo = quo
+            .checked_mul(step)?
+            .checked_add(shifted.checked_div(b).map_err(StdError::from)?)?;
+        rem = shifted.checked_rem(b).map_err(StdError::from)?;
+    }
+
+    Ok(quo)
+}
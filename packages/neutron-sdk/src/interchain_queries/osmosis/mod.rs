@@ -0,0 +1,14 @@
+//! Interchain Queries helpers for the Osmosis **gamm** and **concentratedliquidity** modules.
+//!
+//! Osmosis is not a part of the Cosmos SDK, so its storage layout and protobuf types are
+//! described here separately. Pool reserves fetched by these queries can be used to price remote
+//! assets with the helpers from [`math`] which reproduce the chain's decimal rounding.
+
+pub mod helpers;
+pub mod math;
+pub mod queries;
+pub mod register_queries;
+pub mod types;
+
+#[cfg(test)]
+mod testing;
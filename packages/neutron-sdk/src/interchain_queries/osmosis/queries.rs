@@ -0,0 +1,89 @@
+use crate::{
+    interchain_queries::{
+        osmosis::types::{BalancerPool, ConcentratedLiquidityPool, StableswapPool},
+        queries::{check_query_type, get_registered_query, query_kv_result},
+        types::QueryType,
+    },
+    NeutronResult,
+};
+use cosmwasm_std::{Deps, Env};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BalancerPoolResponse {
+    pub pool: BalancerPool,
+    pub last_submitted_local_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StableswapPoolResponse {
+    pub pool: StableswapPool,
+    pub last_submitted_local_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ConcentratedLiquidityPoolResponse {
+    pub pool: ConcentratedLiquidityPool,
+    pub last_submitted_local_height: u64,
+}
+
+/// Returns balancer pool from remote Osmosis chain
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_balancer_pool(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<BalancerPoolResponse> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let pool: BalancerPool = query_kv_result(deps, registered_query_id)?;
+
+    Ok(BalancerPoolResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        pool,
+    })
+}
+
+/// Returns stableswap pool from remote Osmosis chain
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_stableswap_pool(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<StableswapPoolResponse> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let pool: StableswapPool = query_kv_result(deps, registered_query_id)?;
+
+    Ok(StableswapPoolResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        pool,
+    })
+}
+
+/// Returns concentrated liquidity pool from remote Osmosis chain
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_concentrated_liquidity_pool(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<ConcentratedLiquidityPoolResponse> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let pool: ConcentratedLiquidityPool = query_kv_result(deps, registered_query_id)?;
+
+    Ok(ConcentratedLiquidityPoolResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        pool,
+    })
+}
@@ -0,0 +1,49 @@
+use crate::errors::error::NeutronResult;
+use crate::interchain_queries::helpers::register_interchain_query;
+use crate::interchain_queries::osmosis::helpers::{
+    create_concentrated_liquidity_pool_kv_key, create_gamm_pool_kv_key,
+};
+use crate::interchain_queries::types::QueryPayload;
+use cosmwasm_std::{Addr, CosmosMsg};
+
+/// Creates a message to register an Interchain Query to get balancer or stableswap pool reserves
+/// on remote Osmosis chain
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **pool_id** is an identifier of the **gamm** pool on remote chain;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_gamm_pool_query_msg(
+    contract: Addr,
+    connection_id: String,
+    pool_id: u64,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(vec![create_gamm_pool_kv_key(pool_id)?]),
+        connection_id,
+        update_period,
+    )
+}
+
+/// Creates a message to register an Interchain Query to get concentrated liquidity pool state
+/// on remote Osmosis chain
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **pool_id** is an identifier of the concentrated liquidity pool on remote chain;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_concentrated_liquidity_pool_query_msg(
+    contract: Addr,
+    connection_id: String,
+    pool_id: u64,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(vec![create_concentrated_liquidity_pool_kv_key(pool_id)?]),
+        connection_id,
+        update_period,
+    )
+}
@@ -0,0 +1,431 @@
+use crate::interchain_queries::osmosis::helpers::{
+    create_concentrated_liquidity_pool_key, create_gamm_pool_key,
+};
+use crate::interchain_queries::osmosis::math::{
+    dec_approx_sqrt, dec_mul, dec_mul_truncate, dec_power, dec_quo, dec_quo_truncate, pow,
+};
+use crate::interchain_queries::osmosis::types::{
+    BalancerPool, ConcentratedLiquidityPool, OsmosisBalancerPool, OsmosisConcentratedLiquidityPool,
+    OsmosisPoolAsset, OsmosisPoolParams, OsmosisStableswapPool, PoolAsset, StableswapPool,
+    BALANCER_POOL_TYPE_URL, CONCENTRATED_LIQUIDITY_POOL_PREFIX, CONCENTRATED_LIQUIDITY_STORE_KEY,
+    GAMM_POOL_PREFIX, GAMM_STORE_KEY, STABLESWAP_POOL_TYPE_URL,
+};
+use crate::interchain_queries::types::KVReconstruct;
+use crate::NeutronError;
+use cosmos_sdk_proto::cosmos::base::v1beta1::Coin as CosmosCoin;
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::{Coin, Decimal256, Uint256};
+use neutron_std::types::neutron::interchainqueries::StorageValue;
+use std::str::FromStr;
+
+fn cosmos_coin(amount: &str, denom: &str) -> CosmosCoin {
+    CosmosCoin {
+        denom: denom.to_string(),
+        amount: amount.to_string(),
+    }
+}
+
+fn gamm_storage_value(type_url: &str, pool: Vec<u8>) -> StorageValue {
+    StorageValue {
+        storage_prefix: GAMM_STORE_KEY.to_string(),
+        key: create_gamm_pool_key(1).unwrap(),
+        value: prost_types::Any {
+            type_url: type_url.to_string(),
+            value: pool,
+        }
+        .encode_to_vec(),
+        proof: None,
+    }
+}
+
+fn balancer_pool(assets: Vec<(u128, u128, &str)>, swap_fee: Decimal256) -> BalancerPool {
+    BalancerPool {
+        id: 1,
+        address: "osmo1pool".to_string(),
+        swap_fee,
+        exit_fee: Decimal256::zero(),
+        total_shares: Coin::new(100u128, "gamm/pool/1"),
+        pool_assets: assets
+            .iter()
+            .map(|(amount, weight, denom)| PoolAsset {
+                token: Coin::new(*amount, *denom),
+                weight: Uint256::from(*weight),
+            })
+            .collect(),
+        total_weight: Uint256::from(assets.iter().map(|(_, w, _)| w).sum::<u128>()),
+    }
+}
+
+#[test]
+fn test_create_pool_keys() {
+    assert_eq!(
+        create_gamm_pool_key(1).unwrap(),
+        vec![GAMM_POOL_PREFIX, 0, 0, 0, 0, 0, 0, 0, 1]
+    );
+    assert_eq!(
+        create_concentrated_liquidity_pool_key(1066).unwrap(),
+        vec![CONCENTRATED_LIQUIDITY_POOL_PREFIX, b'1', b'0', b'6', b'6']
+    );
+}
+
+#[test]
+fn test_balancer_pool_reconstruct() {
+    let pool = OsmosisBalancerPool {
+        address: "osmo1pool".to_string(),
+        id: 1,
+        pool_params: Some(OsmosisPoolParams {
+            swap_fee: "2000000000000000".to_string(),
+            exit_fee: "0".to_string(),
+        }),
+        total_shares: Some(cosmos_coin("100000000000000000000", "gamm/pool/1")),
+        pool_assets: vec![
+            OsmosisPoolAsset {
+                token: Some(cosmos_coin("1000000", "uatom")),
+                weight: "536870912000000".to_string(),
+            },
+            OsmosisPoolAsset {
+                token: Some(cosmos_coin("4000000", "uosmo")),
+                weight: "536870912000000".to_string(),
+            },
+        ],
+        total_weight: "1073741824000000".to_string(),
+    };
+
+    let result = BalancerPool::reconstruct(&[gamm_storage_value(
+        BALANCER_POOL_TYPE_URL,
+        pool.encode_to_vec(),
+    )])
+    .unwrap();
+
+    assert_eq!(
+        result,
+        BalancerPool {
+            id: 1,
+            address: "osmo1pool".to_string(),
+            swap_fee: Decimal256::from_str("0.002").unwrap(),
+            exit_fee: Decimal256::zero(),
+            total_shares: Coin::new(100000000000000000000u128, "gamm/pool/1"),
+            pool_assets: vec![
+                PoolAsset {
+                    token: Coin::new(1000000u128, "uatom"),
+                    weight: Uint256::from(536870912000000u128),
+                },
+                PoolAsset {
+                    token: Coin::new(4000000u128, "uosmo"),
+                    weight: Uint256::from(536870912000000u128),
+                },
+            ],
+            total_weight: Uint256::from(1073741824000000u128),
+        }
+    );
+    assert_eq!(
+        result.spot_price("uatom", "uosmo").unwrap(),
+        Decimal256::from_str("4").unwrap()
+    );
+
+    // stableswap pool can't be reconstructed as a balancer one
+    let err = BalancerPool::reconstruct(&[gamm_storage_value(
+        STABLESWAP_POOL_TYPE_URL,
+        pool.encode_to_vec(),
+    )])
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        NeutronError::InvalidQueryResultFormat(format!(
+            "expected pool of type {}, got {}",
+            BALANCER_POOL_TYPE_URL, STABLESWAP_POOL_TYPE_URL
+        ))
+        .to_string()
+    );
+
+    // missing pool
+    let mut empty = gamm_storage_value(BALANCER_POOL_TYPE_URL, vec![]);
+    empty.value = vec![];
+    assert!(BalancerPool::reconstruct(&[empty]).is_err());
+    assert!(BalancerPool::reconstruct(&[]).is_err());
+}
+
+#[test]
+fn test_stableswap_pool_reconstruct() {
+    let pool = OsmosisStableswapPool {
+        address: "osmo1pool".to_string(),
+        id: 1,
+        pool_params: Some(OsmosisPoolParams {
+            swap_fee: "1000000000000000".to_string(),
+            exit_fee: "".to_string(),
+        }),
+        total_shares: Some(cosmos_coin("1000", "gamm/pool/1")),
+        pool_liquidity: vec![cosmos_coin("5000", "uusdc"), cosmos_coin("6000", "uusdt")],
+        scaling_factors: vec![1, 1],
+    };
+
+    let result = StableswapPool::reconstruct(&[gamm_storage_value(
+        STABLESWAP_POOL_TYPE_URL,
+        pool.encode_to_vec(),
+    )])
+    .unwrap();
+
+    assert_eq!(
+        result,
+        StableswapPool {
+            id: 1,
+            address: "osmo1pool".to_string(),
+            swap_fee: Decimal256::from_str("0.001").unwrap(),
+            exit_fee: Decimal256::zero(),
+            total_shares: Coin::new(1000u128, "gamm/pool/1"),
+            pool_liquidity: vec![Coin::new(5000u128, "uusdc"), Coin::new(6000u128, "uusdt")],
+            scaling_factors: vec![1, 1],
+        }
+    );
+}
+
+fn stableswap_pool(
+    assets: Vec<(u128, &'static str)>,
+    scaling_factors: Vec<u64>,
+    swap_fee: Decimal256,
+) -> StableswapPool {
+    StableswapPool {
+        id: 1,
+        address: "osmo1pool".to_string(),
+        swap_fee,
+        exit_fee: Decimal256::zero(),
+        total_shares: Coin::new(1000u128, "gamm/pool/1"),
+        pool_liquidity: assets
+            .into_iter()
+            .map(|(amount, denom)| Coin::new(amount, denom))
+            .collect(),
+        scaling_factors,
+    }
+}
+
+#[test]
+fn test_stableswap_pool_spot_price_and_swap() {
+    let pool = stableswap_pool(
+        vec![(1000000000, "uusdc"), (1000000000, "uusdt")],
+        vec![1, 1],
+        Decimal256::zero(),
+    );
+
+    // a balanced pool is priced close to one
+    let price = pool.spot_price("uusdc", "uusdt").unwrap();
+    assert!(price > Decimal256::percent(99) && price <= Decimal256::one());
+
+    // a small swap of a balanced pool loses almost nothing
+    let amount_out = pool
+        .swap_exact_amount_in(&Coin::new(1000u128, "uusdc"), "uusdt")
+        .unwrap();
+    assert!(amount_out > Uint256::from(990u128) && amount_out < Uint256::from(1000u128));
+
+    // the swap fee is taken from the input
+    let with_fee = stableswap_pool(
+        vec![(1000000000, "uusdc"), (1000000000, "uusdt")],
+        vec![1, 1],
+        Decimal256::percent(1),
+    );
+    assert!(
+        with_fee
+            .swap_exact_amount_in(&Coin::new(1000u128, "uusdc"), "uusdt")
+            .unwrap()
+            < amount_out
+    );
+
+    // scaling factors scale the price
+    let scaled = stableswap_pool(
+        vec![(1000000000, "uusdc"), (1000000000000, "uusdt")],
+        vec![1, 1000],
+        Decimal256::zero(),
+    );
+    let price = scaled.spot_price("uusdc", "uusdt").unwrap();
+    assert!(
+        price > Decimal256::from_str("990").unwrap()
+            && price <= Decimal256::from_str("1000").unwrap()
+    );
+
+    assert!(pool.spot_price("uusdc", "uusdc").is_err());
+    assert!(pool.spot_price("uusdc", "uatom").is_err());
+    assert!(pool
+        .swap_exact_amount_in(&Coin::new(100u128, "uusdc"), "uusdc")
+        .is_err());
+    assert!(pool
+        .swap_exact_amount_in(&Coin::new(100u128, "uatom"), "uusdt")
+        .is_err());
+    assert!(pool
+        .swap_exact_amount_in(&Coin::new(1000000000u128, "uusdc"), "uusdt")
+        .is_err());
+}
+
+#[test]
+fn test_concentrated_liquidity_pool_reconstruct() {
+    let pool = OsmosisConcentratedLiquidityPool {
+        address: "osmo1pool".to_string(),
+        id: 1066,
+        current_tick_liquidity: "1000000000000000000000".to_string(),
+        token0: "uosmo".to_string(),
+        token1: "uatom".to_string(),
+        // 1.5 with 36 decimal places
+        current_sqrt_price: "1500000000000000000000000000000000000".to_string(),
+        current_tick: 1250000,
+        tick_spacing: 100,
+        exponent_at_price_one: -6,
+        spread_factor: "2000000000000000".to_string(),
+    };
+
+    let result = ConcentratedLiquidityPool::reconstruct(&[StorageValue {
+        storage_prefix: CONCENTRATED_LIQUIDITY_STORE_KEY.to_string(),
+        key: create_concentrated_liquidity_pool_key(1066).unwrap(),
+        value: pool.encode_to_vec(),
+        proof: None,
+    }])
+    .unwrap();
+
+    assert_eq!(
+        result,
+        ConcentratedLiquidityPool {
+            id: 1066,
+            address: "osmo1pool".to_string(),
+            token0: "uosmo".to_string(),
+            token1: "uatom".to_string(),
+            current_tick_liquidity: Decimal256::from_str("1000").unwrap(),
+            current_sqrt_price: Uint256::from_str("1500000000000000000000000000000000000").unwrap(),
+            current_tick: 1250000,
+            tick_spacing: 100,
+            exponent_at_price_one: -6,
+            spread_factor: Decimal256::from_str("0.002").unwrap(),
+        }
+    );
+
+    assert_eq!(
+        result.spot_price("uosmo", "uatom").unwrap(),
+        Decimal256::from_str("2.25").unwrap()
+    );
+    assert_eq!(
+        result.spot_price("uatom", "uosmo").unwrap(),
+        Decimal256::from_str("0.444444444444444444").unwrap()
+    );
+    assert!(result.spot_price("uosmo", "uusdc").is_err());
+}
+
+#[test]
+fn test_dec_rounding() {
+    let smallest = Decimal256::raw(1);
+    let half = Decimal256::percent(50);
+
+    // half to even
+    assert_eq!(dec_mul(smallest, half).unwrap(), Decimal256::zero());
+    assert_eq!(
+        dec_mul(Decimal256::raw(3), half).unwrap(),
+        Decimal256::raw(2)
+    );
+    assert_eq!(
+        dec_mul_truncate(Decimal256::raw(3), half).unwrap(),
+        smallest
+    );
+
+    let two = Decimal256::from_str("2").unwrap();
+    let three = Decimal256::from_str("3").unwrap();
+    assert_eq!(
+        dec_quo(two, three).unwrap(),
+        Decimal256::from_str("0.666666666666666667").unwrap()
+    );
+    assert_eq!(
+        dec_quo_truncate(two, three).unwrap(),
+        Decimal256::from_str("0.666666666666666666").unwrap()
+    );
+    assert!(dec_quo(two, Decimal256::zero()).is_err());
+
+    assert_eq!(
+        dec_power(Decimal256::from_str("1.1").unwrap(), 3).unwrap(),
+        Decimal256::from_str("1.331").unwrap()
+    );
+    assert_eq!(
+        dec_approx_sqrt(two).unwrap(),
+        Decimal256::from_str("1.414213562373095049").unwrap()
+    );
+}
+
+#[test]
+fn test_pow() {
+    let base = Decimal256::from_str("0.25").unwrap();
+    assert_eq!(pow(base, Decimal256::one()).unwrap(), base);
+    assert_eq!(
+        pow(base, Decimal256::percent(50)).unwrap(),
+        Decimal256::from_str("0.5").unwrap()
+    );
+    assert!(pow(Decimal256::zero(), Decimal256::one()).is_err());
+    assert!(pow(Decimal256::percent(200), Decimal256::one()).is_err());
+}
+
+#[test]
+fn test_balancer_pool_swap_exact_amount_in() {
+    struct TestCase {
+        // (amount, weight, denom)
+        assets: Vec<(u128, u128, &'static str)>,
+        swap_fee: Decimal256,
+        token_in: Coin,
+        expected_amount_out: u128,
+    }
+    let test_cases: Vec<TestCase> = vec![
+        // equal weights
+        TestCase {
+            assets: vec![(1000, 1, "uatom"), (1000, 1, "uosmo")],
+            swap_fee: Decimal256::permille(3),
+            token_in: Coin::new(100u128, "uatom"),
+            expected_amount_out: 90,
+        },
+        // weight ratio is 0.5, computed with a square root
+        TestCase {
+            assets: vec![(1000000, 1, "uatom"), (4000000, 2, "uosmo")],
+            swap_fee: Decimal256::permille(2),
+            token_in: Coin::new(10000u128, "uatom"),
+            expected_amount_out: 19811,
+        },
+        // weight ratio is 1.5, integer and fractional parts are computed separately
+        TestCase {
+            assets: vec![(1000000, 3, "uatom"), (4000000, 2, "uosmo")],
+            swap_fee: Decimal256::permille(2),
+            token_in: Coin::new(10000u128, "uatom"),
+            expected_amount_out: 59141,
+        },
+        // weight ratio is 1/3, approximated with a series
+        TestCase {
+            assets: vec![(1000000, 1, "uatom"), (4000000, 3, "uosmo")],
+            swap_fee: Decimal256::zero(),
+            token_in: Coin::new(10000u128, "uatom"),
+            expected_amount_out: 13245,
+        },
+    ];
+
+    for ts in test_cases {
+        let pool = balancer_pool(ts.assets, ts.swap_fee);
+        assert_eq!(
+            pool.swap_exact_amount_in(&ts.token_in, "uosmo").unwrap(),
+            Uint256::from(ts.expected_amount_out)
+        );
+    }
+
+    let pool = balancer_pool(
+        vec![(1000, 1, "uatom"), (1000, 1, "uosmo")],
+        Decimal256::zero(),
+    );
+    assert!(pool
+        .swap_exact_amount_in(&Coin::new(100u128, "uatom"), "uatom")
+        .is_err());
+    assert!(pool
+        .swap_exact_amount_in(&Coin::new(100u128, "uusdc"), "uosmo")
+        .is_err());
+}
+
+#[test]
+fn test_balancer_pool_spot_price() {
+    let pool = balancer_pool(
+        vec![(1000000, 1, "uatom"), (4000000, 3, "uosmo")],
+        Decimal256::zero(),
+    );
+
+    // (4000000 / 3) / (1000000 / 1) with the weight ratio rounded before the multiplication
+    assert_eq!(
+        pool.spot_price("uatom", "uosmo").unwrap(),
+        Decimal256::from_str("1.333333333333333332").unwrap()
+    );
+    assert!(pool.spot_price("uatom", "uusdc").is_err());
+}
@@ -0,0 +1,532 @@
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::math::chop_precision_and_round_512;
+use crate::interchain_queries::osmosis::math::{
+    big_dec_mul, big_dec_one, dec_mul, dec_quo, mul_atomics, pow, quo_atomics,
+    stableswap_solve_cfmm, BIG_DEC_PLACES,
+};
+use crate::interchain_queries::types::{KVKeysCount, KVReconstruct};
+use cosmos_sdk_proto::cosmos::base::v1beta1::Coin as CosmosCoin;
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::{Coin, Decimal256, StdError, Uint256, Uint512};
+use neutron_std::types::neutron::interchainqueries::StorageValue;
+use prost_types::Any;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Name of the Osmosis **gamm** module's storage
+pub const GAMM_STORE_KEY: &str = "gamm";
+
+/// Key for pools in the **gamm** module's storage
+/// <https://github.com/osmosis-labs/osmosis/blob/v25.0.0/x/gamm/types/keys.go>
+pub const GAMM_POOL_PREFIX: u8 = 0x02;
+
+/// Name of the Osmosis **concentratedliquidity** module's storage
+pub const CONCENTRATED_LIQUIDITY_STORE_KEY: &str = "concentratedliquidity";
+
+/// Key for pools in the **concentratedliquidity** module's storage
+/// <https://github.com/osmosis-labs/osmosis/blob/v25.0.0/x/concentrated-liquidity/types/keys.go>
+pub const CONCENTRATED_LIQUIDITY_POOL_PREFIX: u8 = 0x03;
+
+/// Type URL of the balancer pool, the **gamm** module stores pools wrapped into `Any`
+pub const BALANCER_POOL_TYPE_URL: &str = "/osmosis.gamm.v1beta1.Pool";
+
+/// Type URL of the stableswap pool, the **gamm** module stores pools wrapped into `Any`
+pub const STABLESWAP_POOL_TYPE_URL: &str = "/osmosis.gamm.poolmodels.stableswap.v1beta1.Pool";
+
+/// Subset of gamm PoolParams protobuf type fields shared by balancer and stableswap pools
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OsmosisPoolParams {
+    #[prost(string, tag = "1")]
+    pub swap_fee: String,
+    #[prost(string, tag = "2")]
+    pub exit_fee: String,
+}
+
+/// Balancer PoolAsset protobuf type
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OsmosisPoolAsset {
+    #[prost(message, optional, tag = "1")]
+    pub token: Option<CosmosCoin>,
+    #[prost(string, tag = "2")]
+    pub weight: String,
+}
+
+/// Balancer Pool protobuf type
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OsmosisBalancerPool {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(uint64, tag = "2")]
+    pub id: u64,
+    #[prost(message, optional, tag = "3")]
+    pub pool_params: Option<OsmosisPoolParams>,
+    #[prost(message, optional, tag = "5")]
+    pub total_shares: Option<CosmosCoin>,
+    #[prost(message, repeated, tag = "6")]
+    pub pool_assets: Vec<OsmosisPoolAsset>,
+    #[prost(string, tag = "7")]
+    pub total_weight: String,
+}
+
+/// Stableswap Pool protobuf type
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OsmosisStableswapPool {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(uint64, tag = "2")]
+    pub id: u64,
+    #[prost(message, optional, tag = "3")]
+    pub pool_params: Option<OsmosisPoolParams>,
+    #[prost(message, optional, tag = "5")]
+    pub total_shares: Option<CosmosCoin>,
+    #[prost(message, repeated, tag = "6")]
+    pub pool_liquidity: Vec<CosmosCoin>,
+    #[prost(uint64, repeated, tag = "7")]
+    pub scaling_factors: Vec<u64>,
+}
+
+/// Subset of concentrated liquidity Pool protobuf type fields. Other fields are skipped while decoding.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OsmosisConcentratedLiquidityPool {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(uint64, tag = "4")]
+    pub id: u64,
+    #[prost(string, tag = "5")]
+    pub current_tick_liquidity: String,
+    #[prost(string, tag = "6")]
+    pub token0: String,
+    #[prost(string, tag = "7")]
+    pub token1: String,
+    #[prost(string, tag = "8")]
+    pub current_sqrt_price: String,
+    #[prost(int64, tag = "9")]
+    pub current_tick: i64,
+    #[prost(uint64, tag = "10")]
+    pub tick_spacing: u64,
+    #[prost(int64, tag = "11")]
+    pub exponent_at_price_one: i64,
+    #[prost(string, tag = "12")]
+    pub spread_factor: String,
+}
+
+/// Parses gogoproto encoded `sdk.Dec` which is a string without a decimal point, e.g. Dec(1) is 1 + 18 zeros
+fn parse_dec(value: &str) -> NeutronResult<Decimal256> {
+    Ok(Decimal256::new(parse_atomics(value)?))
+}
+
+/// Parses gogoproto encoded `sdk.Int` or atomics of `sdk.Dec` and `osmomath.BigDec`
+fn parse_atomics(value: &str) -> NeutronResult<Uint256> {
+    if value.is_empty() {
+        return Ok(Uint256::zero());
+    }
+
+    Ok(Uint256::from_str(value)?)
+}
+
+fn parse_coin(coin: Option<CosmosCoin>) -> NeutronResult<Coin> {
+    let coin = coin.unwrap_or_default();
+
+    Ok(Coin::new(parse_atomics(&coin.amount)?, coin.denom))
+}
+
+/// Converts integer **amount** into `sdk.Dec`
+fn int_to_dec(amount: Uint256) -> NeutronResult<Decimal256> {
+    Ok(Decimal256::from_atomics(amount, 0)?)
+}
+
+/// Returns a value of the only **StorageValue** that must be present for a pool query
+fn pool_value(storage_values: &[StorageValue]) -> NeutronResult<&[u8]> {
+    if storage_values.len() != 1 {
+        return Err(NeutronError::InvalidQueryResultFormat(format!(
+            "expected 1 storage value for pool, got {}",
+            storage_values.len()
+        )));
+    }
+    if storage_values[0].value.is_empty() {
+        return Err(NeutronError::InvalidQueryResultFormat(
+            "pool is not found".into(),
+        ));
+    }
+
+    Ok(storage_values[0].value.as_slice())
+}
+
+/// Decodes `Any` wrapped gamm pool checking its **type_url**
+fn decode_gamm_pool<T: Message + Default>(value: &[u8], type_url: &str) -> NeutronResult<T> {
+    let any = Any::decode(value)?;
+    if any.type_url != type_url {
+        return Err(NeutronError::InvalidQueryResultFormat(format!(
+            "expected pool of type {}, got {}",
+            type_url, any.type_url
+        )));
+    }
+
+    Ok(T::decode(any.value.as_slice())?)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// Token reserve of a balancer pool with its weight
+pub struct PoolAsset {
+    pub token: Coin,
+    pub weight: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **Osmosis Balancer Pool Interchain Query**.
+/// Contains reserves and weights of a balancer pool on remote chain.
+pub struct BalancerPool {
+    pub id: u64,
+    pub address: String,
+    pub swap_fee: Decimal256,
+    pub exit_fee: Decimal256,
+    pub total_shares: Coin,
+    pub pool_assets: Vec<PoolAsset>,
+    pub total_weight: Uint256,
+}
+
+impl BalancerPool {
+    fn pool_asset(&self, denom: &str) -> NeutronResult<&PoolAsset> {
+        self.pool_assets
+            .iter()
+            .find(|asset| asset.token.denom == denom)
+            .ok_or_else(|| {
+                NeutronError::InvalidQueryResultFormat(format!(
+                    "denom {} is not found in pool {}",
+                    denom, self.id
+                ))
+            })
+    }
+
+    /// Returns the amount of **quote_denom** tokens for one **base_denom** token, i.e.
+    /// (quote_balance / quote_weight) / (base_balance / base_weight), rounded the same way as on chain.
+    pub fn spot_price(&self, base_denom: &str, quote_denom: &str) -> NeutronResult<Decimal256> {
+        let base = self.pool_asset(base_denom)?;
+        let quote = self.pool_asset(quote_denom)?;
+
+        let weight_ratio = dec_quo(int_to_dec(base.weight)?, int_to_dec(quote.weight)?)?;
+        let supply_ratio = dec_quo(
+            int_to_dec(quote.token.amount)?,
+            int_to_dec(base.token.amount)?,
+        )?;
+
+        dec_mul(supply_ratio, weight_ratio)
+    }
+
+    /// Returns the amount of **token_out_denom** tokens the pool gives for **token_in** after
+    /// the swap fee is charged. Solves the constant function invariant the same way as on chain:
+    /// balance_out * (1 - (balance_in / (balance_in + amount_in * (1 - swap_fee))) ^ (weight_in / weight_out))
+    pub fn swap_exact_amount_in(
+        &self,
+        token_in: &Coin,
+        token_out_denom: &str,
+    ) -> NeutronResult<Uint256> {
+        if token_in.denom == token_out_denom {
+            return Err(NeutronError::InvalidQueryResultFormat(format!(
+                "cannot swap {} to itself",
+                token_out_denom
+            )));
+        }
+        let asset_in = self.pool_asset(&token_in.denom)?;
+        let asset_out = self.pool_asset(token_out_denom)?;
+
+        let amount_in_after_fee = dec_mul(
+            int_to_dec(token_in.amount)?,
+            Decimal256::one().checked_sub(self.swap_fee)?,
+        )?;
+        let balance_in = int_to_dec(asset_in.token.amount)?;
+        let balance_in_after = balance_in.checked_add(amount_in_after_fee)?;
+
+        let weight_ratio = dec_quo(int_to_dec(asset_in.weight)?, int_to_dec(asset_out.weight)?)?;
+        let y = dec_quo(balance_in, balance_in_after)?;
+        let y_to_weight_ratio = pow(y, weight_ratio)?;
+
+        let amount_out = dec_mul(
+            int_to_dec(asset_out.token.amount)?,
+            Decimal256::one().checked_sub(y_to_weight_ratio)?,
+        )?;
+
+        // decimal part is ignored, token amount out is rounded down
+        Ok(amount_out.to_uint_floor())
+    }
+}
+
+impl KVReconstruct for BalancerPool {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<BalancerPool> {
+        let pool: OsmosisBalancerPool =
+            decode_gamm_pool(pool_value(storage_values)?, BALANCER_POOL_TYPE_URL)?;
+        let pool_params = pool.pool_params.unwrap_or_default();
+
+        let mut pool_assets: Vec<PoolAsset> = Vec::with_capacity(pool.pool_assets.len());
+        for asset in pool.pool_assets {
+            pool_assets.push(PoolAsset {
+                token: parse_coin(asset.token)?,
+                weight: parse_atomics(&asset.weight)?,
+            })
+        }
+
+        Ok(BalancerPool {
+            id: pool.id,
+            address: pool.address,
+            swap_fee: parse_dec(&pool_params.swap_fee)?,
+            exit_fee: parse_dec(&pool_params.exit_fee)?,
+            total_shares: parse_coin(pool.total_shares)?,
+            pool_assets,
+            total_weight: parse_atomics(&pool.total_weight)?,
+        })
+    }
+}
+
+impl KVKeysCount for BalancerPool {
+    const KEYS_COUNT: usize = 1;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **Osmosis Stableswap Pool Interchain Query**.
+/// Contains reserves and scaling factors of a stableswap pool on remote chain.
+pub struct StableswapPool {
+    pub id: u64,
+    pub address: String,
+    pub swap_fee: Decimal256,
+    pub exit_fee: Decimal256,
+    pub total_shares: Coin,
+    pub pool_liquidity: Vec<Coin>,
+    /// scaling factors of **pool_liquidity** coins in the same order
+    pub scaling_factors: Vec<u64>,
+}
+
+impl StableswapPool {
+    /// Returns index of **denom** in **pool_liquidity** and its scaling factor
+    fn liquidity_index(&self, denom: &str) -> NeutronResult<(usize, u64)> {
+        let index = self
+            .pool_liquidity
+            .iter()
+            .position(|coin| coin.denom == denom)
+            .ok_or_else(|| {
+                NeutronError::InvalidQueryResultFormat(format!(
+                    "denom {} is not found in pool {}",
+                    denom, self.id
+                ))
+            })?;
+        match self.scaling_factors.get(index) {
+            Some(&scaling_factor) if scaling_factor > 0 => Ok((index, scaling_factor)),
+            _ => Err(NeutronError::InvalidQueryResultFormat(format!(
+                "pool {} has no scaling factor of {}",
+                self.id, denom
+            ))),
+        }
+    }
+
+    /// Returns reserves of **first_denom** and **second_denom** and a sum of squares of the rest
+    /// reserves divided by their scaling factors as `osmomath.BigDec` atomics, like
+    /// `scaledSortedPoolReserves` does. The division is truncated if **round_down** is set and
+    /// rounded half to even otherwise.
+    fn scaled_reserves(
+        &self,
+        first_denom: &str,
+        second_denom: &str,
+        round_down: bool,
+    ) -> NeutronResult<(Uint512, Uint512, Uint512)> {
+        if first_denom == second_denom {
+            return Err(NeutronError::InvalidQueryResultFormat(format!(
+                "cannot swap {} to itself",
+                first_denom
+            )));
+        }
+        let (first, _) = self.liquidity_index(first_denom)?;
+        let (second, _) = self.liquidity_index(second_denom)?;
+
+        let mut first_reserve = Uint512::zero();
+        let mut second_reserve = Uint512::zero();
+        let mut w_sum_squares = Uint512::zero();
+        for (i, coin) in self.pool_liquidity.iter().enumerate() {
+            let (_, scaling_factor) = self.liquidity_index(&coin.denom)?;
+            let reserve = scale_amount(coin.amount, scaling_factor, round_down)?;
+            if i == first {
+                first_reserve = reserve;
+            } else if i == second {
+                second_reserve = reserve;
+            } else {
+                w_sum_squares = w_sum_squares.checked_add(big_dec_mul(reserve, reserve)?)?;
+            }
+        }
+
+        Ok((first_reserve, second_reserve, w_sum_squares))
+    }
+
+    /// Returns the amount of **quote_denom** tokens for one **base_denom** token. Like on chain,
+    /// it's the amount the CFMM gives for one scaled unit of **base_denom** without the swap fee,
+    /// multiplied by the ratio of the scaling factors. The chain computes the price with
+    /// [`BIG_DEC_PLACES`] decimal places, the result is truncated to [`Decimal256`] precision.
+    pub fn spot_price(&self, base_denom: &str, quote_denom: &str) -> NeutronResult<Decimal256> {
+        let (base_reserve, quote_reserve, w_sum_squares) =
+            self.scaled_reserves(base_denom, quote_denom, false)?;
+        let (_, base_scaling_factor) = self.liquidity_index(base_denom)?;
+        let (_, quote_scaling_factor) = self.liquidity_index(quote_denom)?;
+
+        let scaled_spot_price =
+            stableswap_solve_cfmm(quote_reserve, base_reserve, w_sum_squares, big_dec_one())?;
+        // the ratio of the scaling factors is an sdk.Dec, so it's truncated to 18 decimal places
+        let scaling_ratio = Decimal256::from_ratio(quote_scaling_factor, base_scaling_factor);
+        let spot_price = big_dec_mul(scaled_spot_price, big_dec_from_dec(scaling_ratio)?)?;
+
+        let price =
+            spot_price / Uint512::from(10u128.pow(BIG_DEC_PLACES - Decimal256::DECIMAL_PLACES));
+        Ok(Decimal256::new(
+            Uint256::try_from(price).map_err(StdError::from)?,
+        ))
+    }
+
+    /// Returns the amount of **token_out_denom** tokens the pool gives for **token_in** after
+    /// the swap fee is charged. Solves the CFMM for scaled reserves and amounts the same way as
+    /// on chain, the amount out is rounded down.
+    pub fn swap_exact_amount_in(
+        &self,
+        token_in: &Coin,
+        token_out_denom: &str,
+    ) -> NeutronResult<Uint256> {
+        let (reserve_in, reserve_out, w_sum_squares) =
+            self.scaled_reserves(&token_in.denom, token_out_denom, true)?;
+        let (_, scaling_factor_in) = self.liquidity_index(&token_in.denom)?;
+        let (_, scaling_factor_out) = self.liquidity_index(token_out_denom)?;
+
+        let amount_in = scale_amount(token_in.amount, scaling_factor_in, true)?;
+        let amount_in_after_fee = big_dec_mul(
+            amount_in,
+            big_dec_from_dec(Decimal256::one().checked_sub(self.swap_fee)?)?,
+        )?;
+        let scaled_amount_out =
+            stableswap_solve_cfmm(reserve_out, reserve_in, w_sum_squares, amount_in_after_fee)?;
+
+        // decimal part is ignored, token amount out is rounded down
+        let amount_out = scaled_amount_out
+            .checked_mul(Uint512::from(scaling_factor_out))?
+            .checked_div(big_dec_one())
+            .map_err(StdError::from)?;
+        Ok(Uint256::try_from(amount_out).map_err(StdError::from)?)
+    }
+}
+
+/// Divides **amount** by **scaling_factor** into `osmomath.BigDec` atomics, truncating the result
+/// if **round_down** is set and rounding it half to even otherwise
+fn scale_amount(amount: Uint256, scaling_factor: u64, round_down: bool) -> NeutronResult<Uint512> {
+    let atomics = Uint512::from(amount).checked_mul(big_dec_one())?;
+    let scaling_factor = Uint512::from(scaling_factor);
+    if round_down {
+        return Ok(atomics
+            .checked_div(scaling_factor)
+            .map_err(StdError::from)?);
+    }
+
+    chop_precision_and_round_512(atomics, scaling_factor)
+}
+
+/// Converts `sdk.Dec` into `osmomath.BigDec` atomics
+fn big_dec_from_dec(value: Decimal256) -> NeutronResult<Uint512> {
+    Ok(Uint512::from(value.atomics()).checked_mul(Uint512::from(
+        10u128.pow(BIG_DEC_PLACES - Decimal256::DECIMAL_PLACES),
+    ))?)
+}
+
+impl KVReconstruct for StableswapPool {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<StableswapPool> {
+        let pool: OsmosisStableswapPool =
+            decode_gamm_pool(pool_value(storage_values)?, STABLESWAP_POOL_TYPE_URL)?;
+        let pool_params = pool.pool_params.unwrap_or_default();
+
+        let mut pool_liquidity: Vec<Coin> = Vec::with_capacity(pool.pool_liquidity.len());
+        for coin in pool.pool_liquidity {
+            pool_liquidity.push(parse_coin(Some(coin))?)
+        }
+
+        Ok(StableswapPool {
+            id: pool.id,
+            address: pool.address,
+            swap_fee: parse_dec(&pool_params.swap_fee)?,
+            exit_fee: parse_dec(&pool_params.exit_fee)?,
+            total_shares: parse_coin(pool.total_shares)?,
+            pool_liquidity,
+            scaling_factors: pool.scaling_factors,
+        })
+    }
+}
+
+impl KVKeysCount for StableswapPool {
+    const KEYS_COUNT: usize = 1;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **Osmosis Concentrated Liquidity Pool Interchain Query**.
+/// Contains current state of a concentrated liquidity pool on remote chain.
+///
+/// Unlike gamm pools, it has no swap estimate: a swap may cross initialized ticks which liquidity
+/// is not stored in the pool and is not queried, so only the spot price is computed.
+pub struct ConcentratedLiquidityPool {
+    pub id: u64,
+    pub address: String,
+    pub token0: String,
+    pub token1: String,
+    /// liquidity of the current tick
+    pub current_tick_liquidity: Decimal256,
+    /// atomics of the square root of the current price (**token1** per **token0**)
+    /// with [`BIG_DEC_PLACES`] decimal places
+    pub current_sqrt_price: Uint256,
+    pub current_tick: i64,
+    pub tick_spacing: u64,
+    pub exponent_at_price_one: i64,
+    pub spread_factor: Decimal256,
+}
+
+impl ConcentratedLiquidityPool {
+    /// Returns the amount of **quote_denom** tokens for one **base_denom** token computed from
+    /// the current square root price the same way as on chain. The chain computes the price with
+    /// [`BIG_DEC_PLACES`] decimal places, the result is truncated to [`Decimal256`] precision.
+    pub fn spot_price(&self, base_denom: &str, quote_denom: &str) -> NeutronResult<Decimal256> {
+        let denoms = (base_denom, quote_denom);
+        let price_squared = mul_atomics(
+            self.current_sqrt_price,
+            self.current_sqrt_price,
+            BIG_DEC_PLACES,
+        )?;
+
+        let price = if denoms == (self.token0.as_str(), self.token1.as_str()) {
+            price_squared
+        } else if denoms == (self.token1.as_str(), self.token0.as_str()) {
+            let one = Uint256::from(10u128.pow(BIG_DEC_PLACES));
+            quo_atomics(one, price_squared, BIG_DEC_PLACES)?
+        } else {
+            return Err(NeutronError::InvalidQueryResultFormat(format!(
+                "pool {} has no {}/{} pair",
+                self.id, base_denom, quote_denom
+            )));
+        };
+
+        Ok(Decimal256::new(
+            price / Uint256::from(10u128.pow(BIG_DEC_PLACES - Decimal256::DECIMAL_PLACES)),
+        ))
+    }
+}
+
+impl KVReconstruct for ConcentratedLiquidityPool {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<ConcentratedLiquidityPool> {
+        let pool = OsmosisConcentratedLiquidityPool::decode(pool_value(storage_values)?)?;
+
+        Ok(ConcentratedLiquidityPool {
+            id: pool.id,
+            address: pool.address,
+            token0: pool.token0,
+            token1: pool.token1,
+            current_tick_liquidity: parse_dec(&pool.current_tick_liquidity)?,
+            current_sqrt_price: parse_atomics(&pool.current_sqrt_price)?,
+            current_tick: pool.current_tick,
+            tick_spacing: pool.tick_spacing,
+            exponent_at_price_one: pool.exponent_at_price_one,
+            spread_factor: parse_dec(&pool.spread_factor)?,
+        })
+    }
+}
+
+impl KVKeysCount for ConcentratedLiquidityPool {
+    const KEYS_COUNT: usize = 1;
+}
@@ -0,0 +1,128 @@
+//! A minimal reader of protobuf wire format which borrows strings and bytes right from the buffer.
+//!
+//! Used by zero-copy reconstructors (see `KVReconstructRef`) to read only the needed fields of
+//! **StorageValue**'s instead of decoding complete owned messages with `prost`.
+
+use crate::errors::error::{NeutronError, NeutronResult};
+
+const WIRE_TYPE_VARINT: u64 = 0;
+const WIRE_TYPE_FIXED64: u64 = 1;
+const WIRE_TYPE_LENGTH_DELIMITED: u64 = 2;
+const WIRE_TYPE_FIXED32: u64 = 5;
+
+/// Value of a single field read from protobuf wire format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Field<'a> {
+    Varint(u64),
+    LengthDelimited(&'a [u8]),
+    /// `fixed64`, `sfixed64` or `double` field, none of the read messages needs its value
+    Fixed64,
+    /// `fixed32`, `sfixed32` or `float` field, none of the read messages needs its value
+    Fixed32,
+}
+
+impl<'a> Field<'a> {
+    /// Returns a `string` or an embedded message field
+    pub(crate) fn bytes(self) -> NeutronResult<&'a [u8]> {
+        match self {
+            Field::LengthDelimited(bytes) => Ok(bytes),
+            _ => Err(wire_error("invalid wire type, expected length-delimited")),
+        }
+    }
+
+    /// Returns a `string` field checking it's UTF-8 encoded
+    pub(crate) fn str(self) -> NeutronResult<&'a str> {
+        Ok(std::str::from_utf8(self.bytes()?)?)
+    }
+
+    /// Returns an integer, `bool` or `enum` field as is, without any zigzag decoding
+    pub(crate) fn varint(self) -> NeutronResult<u64> {
+        match self {
+            Field::Varint(value) => Ok(value),
+            _ => Err(wire_error("invalid wire type, expected varint")),
+        }
+    }
+}
+
+/// Iterates over fields of a protobuf message. Unknown fields are to be skipped by the caller,
+/// a scalar field which occurs several times is to be overwritten by its last occurrence, and an
+/// embedded message one is to be merged with the previous occurrences like `prost` does.
+pub(crate) struct ProtoReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> ProtoReader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> ProtoReader<'a> {
+        ProtoReader { buf }
+    }
+
+    /// Reads the next field, returns its number and value or `None` at the end of the message
+    pub(crate) fn next_field(&mut self) -> NeutronResult<Option<(u32, Field<'a>)>> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+
+        let key = self.read_varint()?;
+        let number = u32::try_from(key >> 3)
+            .ok()
+            .filter(|number| *number != 0)
+            .ok_or_else(|| wire_error("invalid field number"))?;
+
+        let field = match key & 0b111 {
+            WIRE_TYPE_VARINT => Field::Varint(self.read_varint()?),
+            WIRE_TYPE_FIXED64 => {
+                self.read_bytes(8)?;
+                Field::Fixed64
+            }
+            WIRE_TYPE_LENGTH_DELIMITED => {
+                let len = usize::try_from(self.read_varint()?)
+                    .map_err(|_| wire_error("buffer underflow"))?;
+                Field::LengthDelimited(self.read_bytes(len)?)
+            }
+            WIRE_TYPE_FIXED32 => {
+                self.read_bytes(4)?;
+                Field::Fixed32
+            }
+            _ => return Err(wire_error("unsupported wire type")),
+        };
+
+        Ok(Some((number, field)))
+    }
+
+    fn read_varint(&mut self) -> NeutronResult<u64> {
+        let mut value: u64 = 0;
+
+        // a varint is at most 10 bytes long, 7 bits of value in each one
+        for i in 0..10 {
+            let (byte, rest) = self
+                .buf
+                .split_first()
+                .ok_or_else(|| wire_error("buffer underflow"))?;
+            self.buf = rest;
+
+            if i == 9 && *byte > 1 {
+                return Err(wire_error("invalid varint"));
+            }
+            value |= u64::from(byte & 0x7f) << (i * 7);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(wire_error("invalid varint"))
+    }
+
+    fn read_bytes(&mut self, len: usize) -> NeutronResult<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(wire_error("buffer underflow"));
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+
+        Ok(bytes)
+    }
+}
+
+fn wire_error(reason: &str) -> NeutronError {
+    NeutronError::ProtobufWireFormat(reason.to_string())
+}
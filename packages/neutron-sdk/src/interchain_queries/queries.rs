@@ -1,9 +1,10 @@
 use crate::errors::error::NeutronResult;
 use crate::interchain_queries::types::{KVReconstruct, QueryType};
 use crate::NeutronError;
-use cosmwasm_std::{Deps, StdError};
+use cosmwasm_std::{Addr, Deps, StdError};
+use neutron_std::types::cosmos::base::query::v1beta1::PageRequest;
 use neutron_std::types::neutron::interchainqueries::{
-    InterchainqueriesQuerier, QueryResult, RegisteredQuery,
+    InterchainqueriesQuerier, Params, QueryResult, RegisteredQuery,
 };
 
 /// Checks **actual** query type is **expected** query type
@@ -28,6 +29,63 @@ pub fn get_registered_query(
     Ok(res)
 }
 
+/// Queries all Interchain Queries owned by **owner**, going through all the pages of
+/// **page_limit** queries each
+///
+/// * **connection_id** is used to get only the queries on a particular IBC connection, e.g. a retired one.
+pub fn get_owned_registered_queries(
+    deps: Deps,
+    owner: &Addr,
+    connection_id: Option<String>,
+    page_limit: u64,
+) -> NeutronResult<Vec<RegisteredQuery>> {
+    let querier = InterchainqueriesQuerier::new(&deps.querier);
+    let connection_id = connection_id.unwrap_or_default();
+
+    let mut registered_queries: Vec<RegisteredQuery> = vec![];
+    let mut next_key: Vec<u8> = vec![];
+    loop {
+        let res = querier.registered_queries(
+            vec![owner.to_string()],
+            connection_id.clone(),
+            Some(PageRequest {
+                key: next_key,
+                offset: 0,
+                limit: page_limit,
+                count_total: false,
+                reverse: false,
+            }),
+        )?;
+        registered_queries.extend(res.registered_queries);
+
+        // an empty next key means there are no more pages
+        match res.pagination {
+            Some(pagination) if !pagination.next_key.is_empty() => next_key = pagination.next_key,
+            _ => return Ok(registered_queries),
+        }
+    }
+}
+
+/// Queries params of Interchain Queries Module, e.g. deposit which is charged from the contract
+/// balance on query registration and limits of KV keys and transactions filters of a query
+pub fn get_interchain_queries_params(deps: Deps) -> NeutronResult<Params> {
+    let querier = InterchainqueriesQuerier::new(&deps.querier);
+    let res = querier
+        .params()?
+        .params
+        .ok_or_else(|| StdError::msg("no interchainqueries params"))?;
+    Ok(res)
+}
+
+/// Returns local height after which a **registered_query** can be removed by anyone, not only
+/// by its owner, if no results are submitted for the query during its submit timeout
+pub fn get_query_removable_height(registered_query: &RegisteredQuery) -> u64 {
+    registered_query
+        .last_submitted_result_local_height
+        .max(registered_query.registered_at_height)
+        .saturating_add(registered_query.submit_timeout)
+}
+
 /// Reads submitted raw KV values for Interchain Query with **query_id** from the storage and reconstructs the result
 pub fn query_kv_result<T: KVReconstruct>(deps: Deps, query_id: u64) -> NeutronResult<T> {
     let registered_query_result = get_raw_interchain_query_result(deps, query_id)?;
@@ -0,0 +1,208 @@
+//! Storage-backed registry of Interchain Queries registered by a contract.
+//!
+//! Remembers which `query_id` is assigned by the Interchain Queries Module to a query registered
+//! under a contract defined key, e.g. a name of a pool or an address of a user:
+//! 1. `QueryRegistry::register` wraps a register message into a submessage;
+//! 2. `QueryRegistry::handle_reply` is called from the contract `reply` entry point for
+//!    `QueryRegistry::reply_id` and records `query_id` from `MsgRegisterInterchainQueryResponse`;
+//! 3. `QueryRegistry::remove` returns a message to remove the query and cleans up its entries.
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::helpers::remove_interchain_query;
+use crate::interchain_txs::helpers::decode_message_response;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, CosmosMsg, Order, Reply, StdError, StdResult, Storage, SubMsg,
+};
+use cw_storage_plus::Map;
+use neutron_std::types::neutron::interchainqueries::MsgRegisterInterchainQueryResponse;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Interchain Query recorded by `QueryRegistry`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct QueryRegistryEntry {
+    /// ID of the query assigned by the Interchain Queries Module
+    pub query_id: u64,
+    /// contract defined key the query is registered under
+    pub key: String,
+    /// contract defined kind of the query, e.g. `balances`, to know how to reconstruct its result
+    pub kind: String,
+}
+
+/// Key and kind of a query which is being registered, passed to `reply` in submessage payload
+#[derive(Serialize, Deserialize)]
+struct PendingQuery {
+    key: String,
+    kind: String,
+}
+
+/// Keeps `QueryRegistryEntry` of every registered query by its key and by its ID
+pub struct QueryRegistry {
+    entries: Map<String, QueryRegistryEntry>,
+    keys: Map<u64, String>,
+    reply_id: u64,
+}
+
+impl QueryRegistry {
+    /// Creates a registry which keeps its entries under **entries_namespace** and
+    /// **ids_namespace** and uses **reply_id** for register submessages
+    pub const fn new(
+        entries_namespace: &'static str,
+        ids_namespace: &'static str,
+        reply_id: u64,
+    ) -> Self {
+        QueryRegistry {
+            entries: Map::new(entries_namespace),
+            keys: Map::new(ids_namespace),
+            reply_id,
+        }
+    }
+
+    /// Returns reply ID of the register submessages to be handled with `handle_reply`
+    pub fn reply_id(&self) -> u64 {
+        self.reply_id
+    }
+
+    /// Wraps **register_msg** created by one of `new_register_*_msg` helpers into a submessage
+    /// which reply records the query under **key** with **kind**
+    pub fn register(
+        &self,
+        storage: &dyn Storage,
+        register_msg: CosmosMsg,
+        key: impl Into<String>,
+        kind: impl Into<String>,
+    ) -> NeutronResult<SubMsg> {
+        let key = key.into();
+        if self.entries.has(storage, key.clone()) {
+            return Err(NeutronError::QueryKeyAlreadyRegistered { key });
+        }
+
+        let payload = to_json_binary(&PendingQuery {
+            key,
+            kind: kind.into(),
+        })?;
+
+        Ok(SubMsg::reply_on_success(register_msg, self.reply_id).with_payload(payload))
+    }
+
+    /// Records ID of the registered query from a **reply** to a submessage created by `register`
+    pub fn handle_reply(
+        &self,
+        storage: &mut dyn Storage,
+        reply: Reply,
+    ) -> NeutronResult<QueryRegistryEntry> {
+        if reply.id != self.reply_id {
+            return Err(NeutronError::InvalidReplyID(reply.id));
+        }
+
+        let pending: PendingQuery = from_json(&reply.payload)?;
+        let response = reply.result.into_result().map_err(StdError::msg)?;
+        // register message is the only message of the submessage, so it has exactly one response
+        let msg_response = response
+            .msg_responses
+            .first()
+            .ok_or_else(|| StdError::msg("no register interchain query response"))?;
+        let register_response: MsgRegisterInterchainQueryResponse =
+            decode_message_response(&msg_response.value.to_vec())?;
+
+        // the key is checked again because several queries can be registered under the same key
+        // in one transaction before any of them is recorded
+        if self.entries.has(storage, pending.key.clone()) {
+            return Err(NeutronError::QueryKeyAlreadyRegistered { key: pending.key });
+        }
+
+        let entry = QueryRegistryEntry {
+            query_id: register_response.id,
+            key: pending.key,
+            kind: pending.kind,
+        };
+        self.entries.save(storage, entry.key.clone(), &entry)?;
+        self.keys.save(storage, entry.query_id, &entry.key)?;
+
+        Ok(entry)
+    }
+
+    /// Loads entry of the query registered under **key**
+    pub fn load(&self, storage: &dyn Storage, key: &str) -> StdResult<QueryRegistryEntry> {
+        self.entries.load(storage, key.to_string())
+    }
+
+    /// Loads entry of the query registered under **key** if there is one
+    pub fn may_load(
+        &self,
+        storage: &dyn Storage,
+        key: &str,
+    ) -> StdResult<Option<QueryRegistryEntry>> {
+        self.entries.may_load(storage, key.to_string())
+    }
+
+    /// Loads entry of the query with **query_id**
+    pub fn load_by_id(
+        &self,
+        storage: &dyn Storage,
+        query_id: u64,
+    ) -> StdResult<QueryRegistryEntry> {
+        let key = self.keys.load(storage, query_id)?;
+        self.entries.load(storage, key)
+    }
+
+    /// Loads entry of the query with **query_id** if there is one
+    pub fn may_load_by_id(
+        &self,
+        storage: &dyn Storage,
+        query_id: u64,
+    ) -> StdResult<Option<QueryRegistryEntry>> {
+        match self.keys.may_load(storage, query_id)? {
+            Some(key) => self.entries.may_load(storage, key),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns entries of all the registered queries ordered by their keys
+    pub fn entries(&self, storage: &dyn Storage) -> StdResult<Vec<QueryRegistryEntry>> {
+        self.entries
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, entry)| entry))
+            .collect()
+    }
+
+    /// Removes entries of the query registered under **key** and returns a message
+    /// to remove the query itself from the Interchain Queries Module
+    ///
+    /// * **contract** is an address of the contract that removes the query. Must be contract that sends the message.
+    pub fn remove(
+        &self,
+        storage: &mut dyn Storage,
+        contract: Addr,
+        key: &str,
+    ) -> NeutronResult<CosmosMsg> {
+        let entry = self.load(storage, key)?;
+        self.remove_entry(storage, contract, entry)
+    }
+
+    /// Removes entries of the query with **query_id** and returns a message
+    /// to remove the query itself from the Interchain Queries Module
+    ///
+    /// * **contract** is an address of the contract that removes the query. Must be contract that sends the message.
+    pub fn remove_by_id(
+        &self,
+        storage: &mut dyn Storage,
+        contract: Addr,
+        query_id: u64,
+    ) -> NeutronResult<CosmosMsg> {
+        let entry = self.load_by_id(storage, query_id)?;
+        self.remove_entry(storage, contract, entry)
+    }
+
+    fn remove_entry(
+        &self,
+        storage: &mut dyn Storage,
+        contract: Addr,
+        entry: QueryRegistryEntry,
+    ) -> NeutronResult<CosmosMsg> {
+        self.entries.remove(storage, entry.key);
+        self.keys.remove(storage, entry.query_id);
+
+        remove_interchain_query(contract, entry.query_id)
+    }
+}
@@ -0,0 +1,212 @@
+//! Storage-backed history of KV Interchain Query results.
+//!
+//! The Interchain Queries Module keeps only the latest result of a query. `QuerySnapshots` is an
+//! opt-in component for `sudo_kv_query_result` which reconstructs the submitted result and keeps it
+//! in the contract storage under `(query_id, revision, remote_height)`, so a contract can look up
+//! values at any recorded remote `Height`, e.g. a balance at every submitted height. Snapshots are
+//! ordered by `Height`, so the ones recorded after a chain upgrade to a new revision come after
+//! all the snapshots of the previous revision.
+//!
+//! The number of snapshots kept per query is limited with **retention**: recording a new snapshot
+//! prunes the oldest ones above the limit.
+
+use crate::interchain_queries::queries::get_raw_interchain_query_result;
+use crate::interchain_queries::types::KVReconstruct;
+use crate::sudo::msg::Height;
+use crate::{NeutronError, NeutronResult};
+use cosmwasm_std::{DepsMut, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Default number of snapshots returned by `QuerySnapshots::range`
+pub const DEFAULT_SNAPSHOTS_LIMIT: u32 = 10;
+
+/// Max number of snapshots returned by `QuerySnapshots::range`
+pub const MAX_SNAPSHOTS_LIMIT: u32 = 100;
+
+/// Reconstructed result of a KV Interchain Query at some remote height
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct QuerySnapshot<T> {
+    /// ID of the query the result was submitted for
+    pub query_id: u64,
+    /// height of the remote chain the result was read at
+    pub remote_height: u64,
+    /// revision number of the remote chain the result was read at
+    pub revision: u64,
+    pub value: T,
+}
+
+impl<T> QuerySnapshot<T> {
+    /// Returns the remote height the result was read at
+    pub fn height(&self) -> Height {
+        Height::new(self.revision, self.remote_height)
+    }
+}
+
+/// Keeps up to **retention** latest `QuerySnapshot`'s of every query
+pub struct QuerySnapshots<T> {
+    snapshots: Map<(u64, u64, u64), QuerySnapshot<T>>,
+    retention: u32,
+}
+
+impl<T> QuerySnapshots<T>
+where
+    T: KVReconstruct + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a history which keeps snapshots under **namespace**
+    /// and at most **retention** snapshots per query.
+    ///
+    /// **retention** must be positive, saving a snapshot fails otherwise.
+    pub const fn new(namespace: &'static str, retention: u32) -> Self {
+        QuerySnapshots {
+            snapshots: Map::new(namespace),
+            retention,
+        }
+    }
+
+    /// Returns max number of snapshots kept per query
+    pub fn retention(&self) -> u32 {
+        self.retention
+    }
+
+    /// Reconstructs the latest result of the query with **query_id** and records it as a snapshot
+    /// at the remote height of the result. Meant to be called from `sudo_kv_query_result`.
+    pub fn record(&self, deps: DepsMut, query_id: u64) -> NeutronResult<QuerySnapshot<T>> {
+        let result = get_raw_interchain_query_result(deps.as_ref(), query_id)?;
+        let snapshot = QuerySnapshot {
+            query_id,
+            remote_height: result.height,
+            revision: result.revision,
+            value: T::reconstruct(&result.kv_results)?,
+        };
+        self.save(deps.storage, &snapshot)?;
+
+        Ok(snapshot)
+    }
+
+    /// Saves **snapshot** and prunes the oldest snapshots of its query above the retention limit
+    pub fn save(
+        &self,
+        storage: &mut dyn Storage,
+        snapshot: &QuerySnapshot<T>,
+    ) -> NeutronResult<()> {
+        if self.retention == 0 {
+            return Err(NeutronError::ZeroSnapshotRetention {});
+        }
+
+        self.snapshots.save(
+            storage,
+            (snapshot.query_id, snapshot.revision, snapshot.remote_height),
+            snapshot,
+        )?;
+
+        let outdated = self
+            .snapshots
+            .sub_prefix(snapshot.query_id)
+            .keys(storage, None, None, Order::Descending)
+            .skip(self.retention as usize)
+            .collect::<StdResult<Vec<(u64, u64)>>>()?;
+        for (revision, remote_height) in outdated {
+            self.snapshots
+                .remove(storage, (snapshot.query_id, revision, remote_height));
+        }
+
+        Ok(())
+    }
+
+    /// Loads snapshot of the query with **query_id** at exactly **height** if there is one
+    pub fn may_load(
+        &self,
+        storage: &dyn Storage,
+        query_id: u64,
+        height: Height,
+    ) -> StdResult<Option<QuerySnapshot<T>>> {
+        self.snapshots.may_load(
+            storage,
+            (query_id, height.revision_number, height.revision_height),
+        )
+    }
+
+    /// Loads the latest snapshot of the query with **query_id** recorded at or before
+    /// **height**, i.e. the value the remote chain had at this height as far as we know
+    pub fn at_height(
+        &self,
+        storage: &dyn Storage,
+        query_id: u64,
+        height: Height,
+    ) -> StdResult<Option<QuerySnapshot<T>>> {
+        self.snapshots
+            .sub_prefix(query_id)
+            .range(
+                storage,
+                None,
+                Some(Bound::inclusive((
+                    height.revision_number,
+                    height.revision_height,
+                ))),
+                Order::Descending,
+            )
+            .next()
+            .transpose()
+            .map(|item| item.map(|(_, snapshot)| snapshot))
+    }
+
+    /// Loads the latest snapshot of the query with **query_id** if there is one
+    pub fn latest(
+        &self,
+        storage: &dyn Storage,
+        query_id: u64,
+    ) -> StdResult<Option<QuerySnapshot<T>>> {
+        self.snapshots
+            .sub_prefix(query_id)
+            .range(storage, None, None, Order::Descending)
+            .next()
+            .transpose()
+            .map(|item| item.map(|(_, snapshot)| snapshot))
+    }
+
+    /// Returns up to **limit** snapshots of the query with **query_id** ordered by `Height`
+    /// and starting after **start_after** height
+    pub fn range(
+        &self,
+        storage: &dyn Storage,
+        query_id: u64,
+        start_after: Option<Height>,
+        limit: Option<u32>,
+        order: Order,
+    ) -> StdResult<Vec<QuerySnapshot<T>>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_SNAPSHOTS_LIMIT)
+            .min(MAX_SNAPSHOTS_LIMIT) as usize;
+        let start_after = start_after
+            .map(|height| Bound::exclusive((height.revision_number, height.revision_height)));
+        let (min, max) = match order {
+            Order::Ascending => (start_after, None),
+            Order::Descending => (None, start_after),
+        };
+
+        self.snapshots
+            .sub_prefix(query_id)
+            .range(storage, min, max, order)
+            .take(limit)
+            .map(|item| item.map(|(_, snapshot)| snapshot))
+            .collect()
+    }
+
+    /// Removes all the snapshots of the query with **query_id**, e.g. when the query is removed
+    pub fn clear(&self, storage: &mut dyn Storage, query_id: u64) -> StdResult<()> {
+        let heights = self
+            .snapshots
+            .sub_prefix(query_id)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(u64, u64)>>>()?;
+        for (revision, remote_height) in heights {
+            self.snapshots
+                .remove(storage, (query_id, revision, remote_height));
+        }
+
+        Ok(())
+    }
+}
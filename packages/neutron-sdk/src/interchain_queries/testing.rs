@@ -0,0 +1,785 @@
+use crate::interchain_queries::address::{
+    convert_address, decode_address, decode_address_of_kind, encode_address, split_hrp, AddressKind,
+};
+use crate::interchain_queries::consensus_state::{
+    get_remote_block_time, ConnectionEnd, QueryConnectionRequest, QueryConnectionResponse,
+    QueryConsensusStateRequest, QueryConsensusStateResponse, TendermintConsensusState,
+    CONNECTION_QUERY_PATH, CONSENSUS_STATE_QUERY_PATH, TENDERMINT_CONSENSUS_STATE_TYPE_URL,
+};
+use crate::interchain_queries::helpers::{
+    check_query_deposit, check_query_payload, decode_and_convert, register_interchain_query,
+    remove_interchain_queries, remove_owned_interchain_queries, update_interchain_query,
+};
+use crate::interchain_queries::legacy_dec::LegacyDec;
+use crate::interchain_queries::queries::{
+    get_owned_registered_queries, get_query_removable_height,
+};
+use crate::interchain_queries::registry::{QueryRegistry, QueryRegistryEntry};
+use crate::interchain_queries::snapshots::{QuerySnapshot, QuerySnapshots};
+use crate::interchain_queries::types::{
+    QueryPayload, TransactionFilterItem, TransactionFilterOp, TransactionFilterValue,
+};
+use crate::interchain_queries::update_period::{
+    balances_change, relative_change, UpdatePeriodController,
+};
+use crate::interchain_queries::v045::helpers::{
+    create_balances_query_keys, create_delegations_query_keys,
+};
+use crate::interchain_queries::v045::types::{Balances, HEIGHT_FIELD};
+use crate::sudo::msg::Height;
+use crate::NeutronError;
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::testing::{
+    mock_dependencies_with_balance, MockApi, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    from_json, Addr, Coin as StdCoin, ContractResult, Decimal, Deps, GrpcQuery, MsgResponse, Order,
+    Querier, QuerierResult, QuerierWrapper, QueryRequest, Reply, SubMsg, SubMsgResponse,
+    SubMsgResult, SystemResult, Timestamp, Uint256,
+};
+use neutron_std::types::cosmos::base::query::v1beta1::PageResponse;
+use neutron_std::types::cosmos::base::v1beta1::Coin as NeutronCoin;
+use neutron_std::types::neutron::interchainqueries::{
+    MsgRegisterInterchainQueryResponse, MsgRemoveInterchainQueryRequest, Params,
+    QueryRegisteredQueriesRequest, QueryRegisteredQueriesResponse, QueryRegisteredQueryRequest,
+    QueryRegisteredQueryResponse, RegisteredQuery,
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[test]
+fn test_legacy_dec() {
+    // protobuf encoding is a string of atomics, empty string is a nil decimal
+    assert_eq!(
+        LegacyDec::from_atomics_str("50000000000000000").unwrap(),
+        LegacyDec::from_str("0.05").unwrap()
+    );
+    assert_eq!(LegacyDec::from_atomics_str("").unwrap(), LegacyDec::zero());
+    assert!(LegacyDec::from_atomics_str("0.05").is_err());
+    assert_eq!(
+        LegacyDec::from_str("0.05").unwrap().to_atomics_string(),
+        "50000000000000000"
+    );
+
+    // JSON encoding is a decimal string with exactly 18 decimal places
+    assert_eq!(LegacyDec::one().to_string(), "1.000000000000000000");
+    assert_eq!(
+        LegacyDec::from_str("123.45").unwrap().to_string(),
+        "123.450000000000000000"
+    );
+    for invalid in ["", "1.", ".5", "-1", "0.0000000000000000001", "1,5"] {
+        assert!(LegacyDec::from_str(invalid).is_err(), "{invalid}");
+    }
+
+    let value: LegacyDec = serde_json_wasm::from_str("\"0.010000000000000000\"").unwrap();
+    assert_eq!(value, LegacyDec::from_str("0.01").unwrap());
+    assert_eq!(
+        serde_json_wasm::to_string(&value).unwrap(),
+        "\"0.010000000000000000\""
+    );
+
+    // Mul and Quo round half to even, MulTruncate and QuoTruncate truncate
+    let one = LegacyDec::one();
+    let two = LegacyDec::from_int(Uint256::from(2u64)).unwrap();
+    let three = LegacyDec::from_int(Uint256::from(3u64)).unwrap();
+    assert_eq!(
+        two.checked_quo(three).unwrap().to_string(),
+        "0.666666666666666667"
+    );
+    assert_eq!(
+        two.checked_quo_truncate(three).unwrap().to_string(),
+        "0.666666666666666666"
+    );
+    assert_eq!(
+        one.checked_quo_int(Uint256::from(3u64))
+            .unwrap()
+            .to_string(),
+        "0.333333333333333333"
+    );
+    let tenth = LegacyDec::from_str("0.1").unwrap();
+    let atoms = |value: &str| LegacyDec::from_atomics_str(value).unwrap();
+    assert_eq!(atoms("5").checked_mul(tenth).unwrap(), LegacyDec::zero());
+    assert_eq!(atoms("15").checked_mul(tenth).unwrap(), atoms("2"));
+    assert_eq!(atoms("15").checked_mul_truncate(tenth).unwrap(), atoms("1"));
+    assert!(one.checked_quo(LegacyDec::zero()).is_err());
+
+    assert_eq!(
+        LegacyDec::from_str("2.999999999999999999")
+            .unwrap()
+            .truncate_int(),
+        Uint256::from(2u64)
+    );
+}
+
+#[test]
+fn test_address_toolkit() {
+    let account = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs";
+    let validator = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3";
+
+    assert_eq!(split_hrp("osmo"), ("osmo", AddressKind::Account));
+    assert_eq!(
+        split_hrp("cosmosvaloper"),
+        ("cosmos", AddressKind::ValidatorOperator)
+    );
+    assert_eq!(
+        split_hrp("cosmosvalcons"),
+        ("cosmos", AddressKind::ValidatorConsensus)
+    );
+
+    let bytes = decode_address(validator, "osmovaloper").unwrap();
+    assert_eq!(bytes, decode_and_convert(validator).unwrap());
+    assert_eq!(encode_address(&bytes, "osmovaloper").unwrap(), validator);
+    assert_eq!(
+        decode_address(validator, "cosmosvaloper")
+            .unwrap_err()
+            .to_string(),
+        "bech32 prefix should be \"cosmosvaloper\", got \"osmovaloper\""
+    );
+
+    assert_eq!(
+        decode_address_of_kind(validator, AddressKind::ValidatorOperator).unwrap(),
+        bytes
+    );
+    assert_eq!(
+        decode_address_of_kind(validator, AddressKind::Account)
+            .unwrap_err()
+            .to_string(),
+        format!("address {:?} should be an account address", validator)
+    );
+
+    // addresses are re-encoded keeping their kind
+    let converted = convert_address(validator, "cosmos").unwrap();
+    assert_eq!(
+        converted,
+        "cosmosvaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqnyzsav5h"
+    );
+    assert_eq!(convert_address(&converted, "osmo").unwrap(), validator);
+    assert_eq!(
+        convert_address(account, "cosmos").unwrap(),
+        "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z"
+    );
+    assert_eq!(
+        convert_address(validator, "cosmosvaloper")
+            .unwrap_err()
+            .to_string(),
+        "invalid bech32 chain prefix \"cosmosvaloper\""
+    );
+
+    assert_eq!(
+        encode_address(&[1; 19], "cosmos").unwrap_err().to_string(),
+        "address length should be 20 or 32 bytes, got 19"
+    );
+    assert_eq!(
+        decode_address_of_kind(
+            "cosmos1qqqsyqcyq5rqwzqfpg9scrgwpugpzysuumzx0",
+            AddressKind::Account
+        )
+        .unwrap_err()
+        .to_string(),
+        "address length should be 20 or 32 bytes, got 19"
+    );
+
+    // delegator and validator are swapped
+    assert_eq!(
+        create_delegations_query_keys(validator.to_string(), vec![account.to_string()])
+            .unwrap_err()
+            .to_string(),
+        format!("address {:?} should be an account address", validator)
+    );
+}
+
+#[test]
+fn test_interchain_queries_params_checks() {
+    let params = Params {
+        query_submit_timeout: 1036800,
+        query_deposit: vec![NeutronCoin {
+            denom: "untrn".to_string(),
+            amount: "1000000".to_string(),
+        }],
+        tx_query_removal_limit: 10000,
+        max_kv_query_keys_count: 2,
+        max_transactions_filters: 1,
+    };
+
+    let keys = create_balances_query_keys(
+        "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+        vec!["uosmo".to_string(), "uatom".to_string()],
+    )
+    .unwrap();
+    check_query_payload(&params, &QueryPayload::KV(keys.clone())).unwrap();
+    assert_eq!(
+        check_query_payload(&params, &QueryPayload::KV([keys.clone(), keys].concat()))
+            .unwrap_err()
+            .to_string(),
+        "interchain query can have at most 2 KV keys, got 4"
+    );
+    let filter = || TransactionFilterItem {
+        field: HEIGHT_FIELD.to_string(),
+        op: TransactionFilterOp::Gte,
+        value: TransactionFilterValue::Int(100),
+    };
+    check_query_payload(&params, &QueryPayload::TX(vec![filter()])).unwrap();
+    assert_eq!(
+        check_query_payload(&params, &QueryPayload::TX(vec![filter(), filter()]))
+            .unwrap_err()
+            .to_string(),
+        "interchain query can have at most 1 transactions filters, got 2"
+    );
+
+    let contract = Addr::unchecked(MOCK_CONTRACT_ADDR);
+    let deps = mock_dependencies_with_balance(&[StdCoin::new(1000000u128, "untrn")]);
+    check_query_deposit(deps.as_ref(), &contract, &params.query_deposit).unwrap();
+    let deps = mock_dependencies_with_balance(&[StdCoin::new(999999u128, "untrn")]);
+    assert_eq!(
+        check_query_deposit(deps.as_ref(), &contract, &params.query_deposit)
+            .unwrap_err()
+            .to_string(),
+        "contract balance 999999untrn is not enough to pay interchain query deposit 1000000untrn"
+    );
+
+    let mut registered_query = RegisteredQuery {
+        id: 1,
+        owner: MOCK_CONTRACT_ADDR.to_string(),
+        query_type: "kv".to_string(),
+        keys: vec![],
+        transactions_filter: "".to_string(),
+        connection_id: "connection-0".to_string(),
+        update_period: 10,
+        last_submitted_result_local_height: 0,
+        last_submitted_result_remote_height: None,
+        deposit: params.query_deposit.clone(),
+        submit_timeout: params.query_submit_timeout,
+        registered_at_height: 100,
+    };
+    assert_eq!(get_query_removable_height(&registered_query), 1036900);
+    registered_query.last_submitted_result_local_height = 200;
+    assert_eq!(get_query_removable_height(&registered_query), 1037000);
+}
+
+#[test]
+fn test_query_registry() {
+    const REGISTRY: QueryRegistry = QueryRegistry::new("icq_entries", "icq_keys", 7);
+
+    let mut storage = MockStorage::new();
+    let contract = Addr::unchecked(MOCK_CONTRACT_ADDR);
+    let register_msg = register_interchain_query(
+        contract.clone(),
+        QueryPayload::KV(vec![]),
+        "connection-0".to_string(),
+        10,
+    )
+    .unwrap();
+
+    let sub_msg = REGISTRY
+        .register(&storage, register_msg.clone(), "alice", "balances")
+        .unwrap();
+    assert_eq!(sub_msg.id, REGISTRY.reply_id());
+    assert_eq!(sub_msg.msg, register_msg);
+
+    #[allow(deprecated)]
+    let reply_to = |sub_msg: SubMsg, query_id: u64| Reply {
+        id: sub_msg.id,
+        payload: sub_msg.payload,
+        gas_used: 0,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+            msg_responses: vec![MsgResponse {
+                type_url: "/neutron.interchainqueries.MsgRegisterInterchainQueryResponse"
+                    .to_string(),
+                value: MsgRegisterInterchainQueryResponse { id: query_id }
+                    .encode_to_vec()
+                    .into(),
+            }],
+        }),
+    };
+    let reply = reply_to(sub_msg, 42);
+    let entry = QueryRegistryEntry {
+        query_id: 42,
+        key: "alice".to_string(),
+        kind: "balances".to_string(),
+    };
+    assert_eq!(
+        REGISTRY.handle_reply(&mut storage, reply.clone()).unwrap(),
+        entry
+    );
+    assert_eq!(REGISTRY.load(&storage, "alice").unwrap(), entry);
+    assert_eq!(REGISTRY.load_by_id(&storage, 42).unwrap(), entry);
+    assert_eq!(REGISTRY.entries(&storage).unwrap(), vec![entry]);
+    assert_eq!(REGISTRY.may_load(&storage, "bob").unwrap(), None);
+
+    // a key can't be registered twice
+    assert!(matches!(
+        REGISTRY.register(&storage, register_msg.clone(), "alice", "balances"),
+        Err(NeutronError::QueryKeyAlreadyRegistered { .. })
+    ));
+    // even in one transaction, before the first query is recorded
+    let first = REGISTRY
+        .register(&storage, register_msg.clone(), "bob", "balances")
+        .unwrap();
+    let second = REGISTRY
+        .register(&storage, register_msg, "bob", "balances")
+        .unwrap();
+    let bob = REGISTRY
+        .handle_reply(&mut storage, reply_to(first, 43))
+        .unwrap();
+    assert!(matches!(
+        REGISTRY.handle_reply(&mut storage, reply_to(second, 44)),
+        Err(NeutronError::QueryKeyAlreadyRegistered { key }) if key == "bob"
+    ));
+    assert_eq!(REGISTRY.load_by_id(&storage, 43).unwrap(), bob);
+    assert_eq!(REGISTRY.may_load_by_id(&storage, 44).unwrap(), None);
+    assert!(matches!(
+        REGISTRY.handle_reply(&mut storage, Reply { id: 8, ..reply }),
+        Err(NeutronError::InvalidReplyID(8))
+    ));
+
+    // removal of the query cleans up its entries
+    assert_eq!(
+        REGISTRY.remove(&mut storage, contract, "alice").unwrap(),
+        MsgRemoveInterchainQueryRequest {
+            sender: MOCK_CONTRACT_ADDR.to_string(),
+            query_id: 42,
+        }
+        .into()
+    );
+    assert_eq!(REGISTRY.may_load(&storage, "alice").unwrap(), None);
+    assert_eq!(REGISTRY.may_load_by_id(&storage, 42).unwrap(), None);
+}
+
+/// Serves registered queries page by page like Interchain Queries Module does,
+/// next key of a page is an ID of the first query of the next page
+struct RegisteredQueriesQuerier {
+    registered_queries: Vec<RegisteredQuery>,
+}
+
+impl Querier for RegisteredQueriesQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest = from_json(bin_request).unwrap();
+        let QueryRequest::Grpc(GrpcQuery { path, data }) = request else {
+            panic!("unexpected query request")
+        };
+        if path == "/neutron.interchainqueries.Query/RegisteredQuery" {
+            let request = QueryRegisteredQueryRequest::decode(data.as_slice()).unwrap();
+            let response = QueryRegisteredQueryResponse {
+                registered_query: self
+                    .registered_queries
+                    .iter()
+                    .find(|query| query.id == request.query_id)
+                    .cloned(),
+            };
+            return SystemResult::Ok(ContractResult::Ok(response.encode_to_vec().into()));
+        }
+        assert_eq!(path, "/neutron.interchainqueries.Query/RegisteredQueries");
+
+        let request = QueryRegisteredQueriesRequest::decode(data.as_slice()).unwrap();
+        let pagination = request.pagination.unwrap();
+        let start = if pagination.key.is_empty() {
+            0
+        } else {
+            u64::from_be_bytes(pagination.key.try_into().unwrap())
+        };
+
+        let mut queries = self.registered_queries.iter().filter(|query| {
+            query.id >= start
+                && request.owners.contains(&query.owner)
+                && (request.connection_id.is_empty()
+                    || query.connection_id == request.connection_id)
+        });
+        let page: Vec<RegisteredQuery> = queries
+            .by_ref()
+            .take(pagination.limit as usize)
+            .cloned()
+            .collect();
+        let next_key = queries
+            .next()
+            .map(|query| query.id.to_be_bytes().to_vec())
+            .unwrap_or_default();
+
+        let response = QueryRegisteredQueriesResponse {
+            registered_queries: page,
+            pagination: Some(PageResponse { next_key, total: 0 }),
+        };
+        SystemResult::Ok(ContractResult::Ok(response.encode_to_vec().into()))
+    }
+}
+
+#[test]
+fn test_owned_registered_queries() {
+    let registered_query = |id: u64, owner: &str, connection_id: &str| RegisteredQuery {
+        id,
+        owner: owner.to_string(),
+        query_type: "kv".to_string(),
+        keys: vec![],
+        transactions_filter: "".to_string(),
+        connection_id: connection_id.to_string(),
+        update_period: 10,
+        last_submitted_result_local_height: 0,
+        last_submitted_result_remote_height: None,
+        deposit: vec![],
+        submit_timeout: 0,
+        registered_at_height: 0,
+    };
+    let querier = RegisteredQueriesQuerier {
+        registered_queries: vec![
+            registered_query(1, MOCK_CONTRACT_ADDR, "connection-0"),
+            registered_query(2, "neutron1other", "connection-0"),
+            registered_query(3, MOCK_CONTRACT_ADDR, "connection-1"),
+            registered_query(4, MOCK_CONTRACT_ADDR, "connection-0"),
+            registered_query(5, MOCK_CONTRACT_ADDR, "connection-1"),
+            registered_query(6, MOCK_CONTRACT_ADDR, "connection-0"),
+        ],
+    };
+    let (storage, api) = (MockStorage::new(), MockApi::default());
+    let deps = Deps {
+        storage: &storage,
+        api: &api,
+        querier: QuerierWrapper::new(&querier),
+    };
+    let contract = Addr::unchecked(MOCK_CONTRACT_ADDR);
+    let ids =
+        |queries: Vec<RegisteredQuery>| -> Vec<u64> { queries.iter().map(|q| q.id).collect() };
+
+    // all the pages are read
+    assert_eq!(
+        ids(get_owned_registered_queries(deps, &contract, None, 2).unwrap()),
+        vec![1, 3, 4, 5, 6]
+    );
+    assert_eq!(
+        ids(
+            get_owned_registered_queries(deps, &contract, Some("connection-1".to_string()), 1)
+                .unwrap()
+        ),
+        vec![3, 5]
+    );
+
+    assert_eq!(
+        remove_owned_interchain_queries(deps, contract.clone(), Some("connection-0".to_string()))
+            .unwrap(),
+        remove_interchain_queries(contract.clone(), [1, 4, 6]).unwrap()
+    );
+    assert_eq!(
+        remove_interchain_queries(contract, [4]).unwrap(),
+        vec![MsgRemoveInterchainQueryRequest {
+            sender: MOCK_CONTRACT_ADDR.to_string(),
+            query_id: 4,
+        }
+        .into()]
+    );
+}
+
+/// Serves IBC connection and consensus state queries for a single light client,
+/// which has consensus states at the heights of **block_times**
+struct LightClientQuerier {
+    block_times: BTreeMap<u64, i64>,
+}
+
+impl Querier for LightClientQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest = from_json(bin_request).unwrap();
+        let QueryRequest::Grpc(GrpcQuery { path, data }) = request else {
+            panic!("unexpected query request")
+        };
+
+        let response = match path.as_str() {
+            CONNECTION_QUERY_PATH => {
+                let request = QueryConnectionRequest::decode(data.as_slice()).unwrap();
+                QueryConnectionResponse {
+                    connection: (request.connection_id == "connection-0").then(|| ConnectionEnd {
+                        client_id: "07-tendermint-0".to_string(),
+                    }),
+                }
+                .encode_to_vec()
+            }
+            CONSENSUS_STATE_QUERY_PATH => {
+                let request = QueryConsensusStateRequest::decode(data.as_slice()).unwrap();
+                assert_eq!(request.client_id, "07-tendermint-0");
+                assert_eq!(request.revision_number, 1);
+                QueryConsensusStateResponse {
+                    consensus_state: self.block_times.get(&request.revision_height).map(
+                        |seconds| prost_types::Any {
+                            type_url: TENDERMINT_CONSENSUS_STATE_TYPE_URL.to_string(),
+                            value: TendermintConsensusState {
+                                timestamp: Some(prost_types::Timestamp {
+                                    seconds: *seconds,
+                                    nanos: 500,
+                                }),
+                                next_validators_hash: vec![],
+                            }
+                            .encode_to_vec(),
+                        },
+                    ),
+                }
+                .encode_to_vec()
+            }
+            _ => panic!("unexpected grpc query {}", path),
+        };
+
+        SystemResult::Ok(ContractResult::Ok(response.into()))
+    }
+}
+
+#[test]
+fn test_remote_block_time() {
+    let querier = LightClientQuerier {
+        block_times: BTreeMap::from([(100, 1_700_000_000), (101, -1)]),
+    };
+    let (storage, api) = (MockStorage::new(), MockApi::default());
+    let deps = Deps {
+        storage: &storage,
+        api: &api,
+        querier: QuerierWrapper::new(&querier),
+    };
+
+    assert_eq!(
+        get_remote_block_time(deps, "connection-0", Height::new(1, 100)).unwrap(),
+        Timestamp::from_seconds(1_700_000_000).plus_nanos(500)
+    );
+    // the light client was not updated to the height
+    assert!(get_remote_block_time(deps, "connection-0", Height::new(1, 102)).is_err());
+    // timestamps before the Unix epoch are not supported
+    assert!(matches!(
+        get_remote_block_time(deps, "connection-0", Height::new(1, 101)),
+        Err(NeutronError::InvalidQueryResultFormat(_))
+    ));
+    assert!(get_remote_block_time(deps, "connection-1", Height::new(1, 100)).is_err());
+}
+
+#[test]
+fn test_query_snapshots() {
+    const SNAPSHOTS: QuerySnapshots<Balances> = QuerySnapshots::new("snapshots", 3);
+
+    let snapshot = |query_id: u64, revision: u64, remote_height: u64| QuerySnapshot {
+        query_id,
+        remote_height,
+        revision,
+        value: Balances {
+            coins: vec![StdCoin::new(remote_height as u128, "uosmo")],
+        },
+    };
+
+    let mut storage = MockStorage::new();
+    for (revision, remote_height) in [(1, 10), (1, 20), (1, 30), (1, 40)] {
+        SNAPSHOTS
+            .save(&mut storage, &snapshot(1, revision, remote_height))
+            .unwrap();
+    }
+    SNAPSHOTS.save(&mut storage, &snapshot(2, 1, 15)).unwrap();
+
+    // the oldest snapshot is pruned, snapshots of other queries are kept
+    assert_eq!(
+        SNAPSHOTS.may_load(&storage, 1, Height::new(1, 10)).unwrap(),
+        None
+    );
+    assert_eq!(
+        SNAPSHOTS.may_load(&storage, 1, Height::new(1, 20)).unwrap(),
+        Some(snapshot(1, 1, 20))
+    );
+    assert_eq!(
+        SNAPSHOTS.latest(&storage, 2).unwrap(),
+        Some(snapshot(2, 1, 15))
+    );
+    assert_eq!(SNAPSHOTS.latest(&storage, 3).unwrap(), None);
+
+    assert_eq!(
+        SNAPSHOTS
+            .at_height(&storage, 1, Height::new(1, 35))
+            .unwrap(),
+        Some(snapshot(1, 1, 30))
+    );
+    assert_eq!(
+        SNAPSHOTS
+            .at_height(&storage, 1, Height::new(1, 30))
+            .unwrap(),
+        Some(snapshot(1, 1, 30))
+    );
+    assert_eq!(
+        SNAPSHOTS
+            .at_height(&storage, 1, Height::new(1, 19))
+            .unwrap(),
+        None
+    );
+
+    assert_eq!(
+        SNAPSHOTS
+            .range(&storage, 1, None, None, Order::Ascending)
+            .unwrap(),
+        vec![snapshot(1, 1, 20), snapshot(1, 1, 30), snapshot(1, 1, 40)]
+    );
+    assert_eq!(
+        SNAPSHOTS
+            .range(
+                &storage,
+                1,
+                Some(Height::new(1, 20)),
+                Some(1),
+                Order::Ascending
+            )
+            .unwrap(),
+        vec![snapshot(1, 1, 30)]
+    );
+    assert_eq!(
+        SNAPSHOTS
+            .range(
+                &storage,
+                1,
+                Some(Height::new(1, 40)),
+                None,
+                Order::Descending
+            )
+            .unwrap(),
+        vec![snapshot(1, 1, 30), snapshot(1, 1, 20)]
+    );
+
+    // heights start over after an upgrade to a new revision, which is still the latest snapshot
+    SNAPSHOTS.save(&mut storage, &snapshot(1, 2, 5)).unwrap();
+    assert_eq!(
+        SNAPSHOTS.latest(&storage, 1).unwrap(),
+        Some(snapshot(1, 2, 5))
+    );
+    assert_eq!(snapshot(1, 2, 5).height(), Height::new(2, 5));
+    assert_eq!(
+        SNAPSHOTS.at_height(&storage, 1, Height::new(2, 4)).unwrap(),
+        Some(snapshot(1, 1, 40))
+    );
+    // the oldest snapshot of the previous revision is pruned
+    assert_eq!(
+        SNAPSHOTS
+            .range(&storage, 1, None, None, Order::Ascending)
+            .unwrap(),
+        vec![snapshot(1, 1, 30), snapshot(1, 1, 40), snapshot(1, 2, 5)]
+    );
+
+    SNAPSHOTS.clear(&mut storage, 1).unwrap();
+    assert_eq!(SNAPSHOTS.latest(&storage, 1).unwrap(), None);
+    assert_eq!(
+        SNAPSHOTS.latest(&storage, 2).unwrap(),
+        Some(snapshot(2, 1, 15))
+    );
+
+    const NO_SNAPSHOTS: QuerySnapshots<Balances> = QuerySnapshots::new("no_snapshots", 0);
+    assert!(matches!(
+        NO_SNAPSHOTS.save(&mut storage, &snapshot(1, 1, 10)),
+        Err(NeutronError::ZeroSnapshotRetention {})
+    ));
+    assert_eq!(NO_SNAPSHOTS.latest(&storage, 1).unwrap(), None);
+}
+
+#[test]
+fn test_update_period_controller() {
+    let controller = UpdatePeriodController {
+        min_update_period: 5,
+        max_update_period: 100,
+        change_threshold: Decimal::percent(1),
+        step_factor: 2,
+    };
+    controller.validate().unwrap();
+    assert!(matches!(
+        UpdatePeriodController {
+            min_update_period: 0,
+            ..controller.clone()
+        }
+        .validate(),
+        Err(NeutronError::InvalidUpdatePeriodController(_))
+    ));
+    assert!(matches!(
+        UpdatePeriodController {
+            max_update_period: 4,
+            ..controller.clone()
+        }
+        .validate(),
+        Err(NeutronError::InvalidUpdatePeriodController(_))
+    ));
+    assert!(matches!(
+        UpdatePeriodController {
+            step_factor: 1,
+            ..controller.clone()
+        }
+        .validate(),
+        Err(NeutronError::InvalidUpdatePeriodController(_))
+    ));
+
+    // a changing value is queried more often, a stable one less often, within the bounds
+    assert_eq!(controller.next_update_period(40, Decimal::percent(5)), 20);
+    assert_eq!(controller.next_update_period(8, Decimal::percent(5)), 5);
+    assert_eq!(controller.next_update_period(40, Decimal::percent(1)), 80);
+    assert_eq!(controller.next_update_period(80, Decimal::zero()), 100);
+    assert_eq!(
+        controller.next_update_period(u64::MAX, Decimal::zero()),
+        100
+    );
+
+    assert_eq!(
+        relative_change(Uint256::from(100u64), Uint256::from(150u64)),
+        Decimal::percent(50)
+    );
+    assert_eq!(
+        relative_change(Uint256::from(100u64), Uint256::from(50u64)),
+        Decimal::percent(50)
+    );
+    assert_eq!(
+        relative_change(Uint256::zero(), Uint256::from(50u64)),
+        Decimal::one()
+    );
+    assert_eq!(
+        relative_change(Uint256::from(1u64), Uint256::MAX),
+        Decimal::MAX
+    );
+    assert_eq!(
+        balances_change(
+            &[
+                StdCoin::new(100u128, "uosmo"),
+                StdCoin::new(100u128, "uatom")
+            ],
+            &[StdCoin::new(102u128, "uosmo")]
+        ),
+        Decimal::one()
+    );
+    assert_eq!(
+        balances_change(
+            &[StdCoin::new(100u128, "uosmo")],
+            &[StdCoin::new(102u128, "uosmo")]
+        ),
+        Decimal::percent(2)
+    );
+    assert_eq!(balances_change(&[], &[]), Decimal::zero());
+
+    let registered_query = |id: u64, update_period: u64| RegisteredQuery {
+        id,
+        owner: MOCK_CONTRACT_ADDR.to_string(),
+        query_type: "kv".to_string(),
+        keys: vec![],
+        transactions_filter: "".to_string(),
+        connection_id: "connection-0".to_string(),
+        update_period,
+        last_submitted_result_local_height: 0,
+        last_submitted_result_remote_height: None,
+        deposit: vec![],
+        submit_timeout: 0,
+        registered_at_height: 0,
+    };
+    let querier = RegisteredQueriesQuerier {
+        registered_queries: vec![registered_query(1, 40), registered_query(2, 100)],
+    };
+    let (storage, api) = (MockStorage::new(), MockApi::default());
+    let deps = Deps {
+        storage: &storage,
+        api: &api,
+        querier: QuerierWrapper::new(&querier),
+    };
+    let contract = Addr::unchecked(MOCK_CONTRACT_ADDR);
+
+    assert_eq!(
+        controller
+            .adjust_update_period(deps, contract.clone(), 1, Decimal::percent(5))
+            .unwrap(),
+        Some(update_interchain_query(contract.clone(), 1, vec![], 20, None).unwrap())
+    );
+    // the period is already at the max bound
+    assert_eq!(
+        controller
+            .adjust_update_period(deps, contract, 2, Decimal::zero())
+            .unwrap(),
+        None
+    );
+}
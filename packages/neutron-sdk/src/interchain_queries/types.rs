@@ -1,8 +1,9 @@
-use crate::errors::error::NeutronResult;
-use cosmwasm_std::{from_json, StdError, Uint128};
+use crate::errors::error::{NeutronError, NeutronResult};
+use cosmwasm_std::{from_json, Binary, StdError, Uint128};
 use neutron_std::types::neutron::interchainqueries::{KvKey, StorageValue};
 use schemars::{JsonSchema, _serde_json::Value};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Display;
 
 pub const QUERY_TYPE_KV_VALUE: &str = "kv";
 pub const QUERY_TYPE_TX_VALUE: &str = "tx";
@@ -120,6 +121,53 @@ pub trait KVReconstruct: Sized {
     fn reconstruct(kvs: &[StorageValue]) -> NeutronResult<Self>;
 }
 
+/// A zero-copy counterpart of `KVReconstruct`: a **data structure** which borrows strings and bytes
+/// right from the slice of **StorageValue** structures instead of copying them.
+///
+/// `KVReconstruct` decodes complete owned protobuf messages, so every string of every value is
+/// allocated, which makes reconstruction of big results (e.g. delegations to dozens of validators)
+/// expensive in gas. Borrowed structures read only the fields they need and usually allocate
+/// just the resulting `Vec`. They can't outlive the query result they are reconstructed from:
+/// ```rust ignore
+/// let query_result = get_raw_interchain_query_result(deps, query_id)?;
+/// let delegations = DelegationsRef::reconstruct_ref(&query_result.kv_results)?;
+/// ```
+pub trait KVReconstructRef<'a>: Sized {
+    /// Reconstructs this value borrowing data from the slice of **StorageValue**'s.
+    fn reconstruct_ref(kvs: &'a [StorageValue]) -> NeutronResult<Self>;
+}
+
+/// An inverse of `KVReconstruct`: a **data structure** that can be encoded back into the exact
+/// **StorageValue**'s (with keys and store prefixes) a remote chain would return for it.
+///
+/// Useful to mock results of Interchain Queries in tests without hand-crafting protobuf or hex:
+/// ```rust ignore
+/// let storage_values = balances.construct("cosmos1...")?;
+/// assert_eq!(Balances::reconstruct(&storage_values)?, balances);
+/// ```
+pub trait KVConstruct {
+    /// Data which is needed to build the keys but is not a part of the value itself,
+    /// e.g. an account address for balances. `()` if the value contains everything.
+    type Context: ?Sized;
+
+    /// Encodes this value into the slice of **StorageValue**'s in the order
+    /// it is reconstructed from.
+    fn construct(&self, context: &Self::Context) -> NeutronResult<Vec<StorageValue>>;
+}
+
+/// Creates an error describing malformed **StorageValue** at **index** of a KV query result,
+/// where **expected** is a shape of the value the reconstructor expects to find there
+pub(crate) fn invalid_storage_value<E: Display>(
+    index: usize,
+    expected: &str,
+    reason: E,
+) -> NeutronError {
+    NeutronError::InvalidQueryResultFormat(format!(
+        "storage value {}: expected {}: {}",
+        index, expected, reason
+    ))
+}
+
 impl KVReconstruct for Uint128 {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<Uint128> {
         let value = storage_values
@@ -129,3 +177,174 @@ impl KVReconstruct for Uint128 {
         Ok(balance)
     }
 }
+
+/// A **data structure** that is always reconstructed from the same number of **StorageValue**'s.
+///
+/// Allows to split results of a KV query which combines several parts,
+/// see `KVReconstruct` implementations for tuples and `Composite`.
+pub trait KVKeysCount {
+    /// Number of **StorageValue**'s this value is reconstructed from.
+    const KEYS_COUNT: usize;
+}
+
+/// A part of a composite KV query which is reconstructed from exactly **N** **StorageValue**'s.
+///
+/// Used to mark parts with a number of keys that is only known at query registration time, e.g.
+/// balances of two denoms bundled together with a fee pool into one query:
+/// ```rust ignore
+/// let (balances, fee_pool): (Composite<Balances, 2>, FeePool) = query_kv_result(deps, query_id)?;
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Composite<T, const N: usize>(pub T);
+
+impl<T, const N: usize> KVKeysCount for Composite<T, N> {
+    const KEYS_COUNT: usize = N;
+}
+
+impl<T: KVReconstruct, const N: usize> KVReconstruct for Composite<T, N> {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<Composite<T, N>> {
+        if storage_values.len() != N {
+            return Err(NeutronError::InvalidQueryResultFormat(format!(
+                "expected {} storage values for composite query part, got {}",
+                N,
+                storage_values.len()
+            )));
+        }
+
+        Ok(Composite(T::reconstruct(storage_values)?))
+    }
+}
+
+/// Splits off the first **count** storage values of a composite query part
+fn split_storage_values(
+    storage_values: &[StorageValue],
+    count: usize,
+) -> NeutronResult<(&[StorageValue], &[StorageValue])> {
+    if storage_values.len() < count {
+        return Err(NeutronError::InvalidQueryResultFormat(format!(
+            "expected at least {} storage values for composite query part, got {}",
+            count,
+            storage_values.len()
+        )));
+    }
+
+    Ok(storage_values.split_at(count))
+}
+
+/// Implements `KVReconstruct` for a tuple. Every part except the last one must have a known
+/// number of keys, the last part is reconstructed from the rest of storage values.
+macro_rules! impl_kv_reconstruct_for_tuple {
+    ($($part:ident $values:ident),+; $last:ident) => {
+        impl<$($part,)+ $last> KVReconstruct for ($($part,)+ $last)
+        where
+            $($part: KVReconstruct + KVKeysCount,)+
+            $last: KVReconstruct,
+        {
+            fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<Self> {
+                let rest = storage_values;
+                $(
+                    let ($values, rest) = split_storage_values(rest, $part::KEYS_COUNT)?;
+                    let $values = $part::reconstruct($values)?;
+                )+
+
+                Ok(($($values,)+ $last::reconstruct(rest)?))
+            }
+        }
+    };
+}
+
+impl_kv_reconstruct_for_tuple!(A a; B);
+impl_kv_reconstruct_for_tuple!(A a, B b; C);
+impl_kv_reconstruct_for_tuple!(A a, B b, C c; D);
+impl_kv_reconstruct_for_tuple!(A a, B b, C c, D d; E);
+
+/// Status of a single **StorageValue** of a KV query result
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KVStatus {
+    /// the value is present in remote chain storage and decoded successfully
+    Present,
+    /// the value is absent in remote chain storage, e.g. a zero balance or a misspelled denom
+    Absent,
+    /// the value is present in remote chain storage but can't be decoded
+    Malformed(String),
+}
+
+/// Status of a single key of a KV query result
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct KeyStatus {
+    pub storage_prefix: String,
+    pub key: Binary,
+    pub status: KVStatus,
+}
+
+impl KeyStatus {
+    pub fn new(storage_value: &StorageValue, status: KVStatus) -> KeyStatus {
+        KeyStatus {
+            storage_prefix: storage_value.storage_prefix.clone(),
+            key: Binary::new(storage_value.key.clone()),
+            status,
+        }
+    }
+}
+
+/// A **data structure** that can be reconstructed from slice of **StorageValue** structures
+/// reporting status of every key instead of failing on the first malformed value.
+///
+/// Usually used through `WithStatus` together with `query_kv_result` function:
+/// ```rust ignore
+/// let result: WithStatus<Balances> = query_kv_result(deps, query_id)?;
+/// ```
+pub trait KVReconstructWithStatus: Sized {
+    /// Reconstructs this value from the slice of **StorageValue**'s skipping malformed values.
+    /// Returns the value together with statuses of all the keys in the same order.
+    fn reconstruct_with_status(kvs: &[StorageValue]) -> NeutronResult<(Self, Vec<KeyStatus>)>;
+}
+
+/// Opt-in reconstruct mode which returns **result** together with per-key **statuses**,
+/// so a contract can tell an absent key apart from a zero value or detect registration mistakes.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct WithStatus<T> {
+    pub result: T,
+    pub statuses: Vec<KeyStatus>,
+}
+
+impl<T: KVReconstructWithStatus> KVReconstruct for WithStatus<T> {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<WithStatus<T>> {
+        let (result, statuses) = T::reconstruct_with_status(storage_values)?;
+
+        Ok(WithStatus { result, statuses })
+    }
+}
+
+/// Returns `KVStatus::Absent` for an empty **StorageValue** and `KVStatus::Present` otherwise
+pub(crate) fn presence_status(storage_value: &StorageValue) -> KVStatus {
+    if storage_value.value.is_empty() {
+        KVStatus::Absent
+    } else {
+        KVStatus::Present
+    }
+}
+
+/// Reconstructs every **StorageValue** independently with **reconstruct_value**.
+/// Values which failed to reconstruct are skipped and reported as `KVStatus::Malformed`.
+pub(crate) fn reconstruct_each_with_status<T>(
+    storage_values: &[StorageValue],
+    reconstruct_value: impl Fn(&StorageValue) -> NeutronResult<T>,
+) -> (Vec<T>, Vec<KeyStatus>) {
+    let mut values: Vec<T> = Vec::with_capacity(storage_values.len());
+    let mut statuses: Vec<KeyStatus> = Vec::with_capacity(storage_values.len());
+
+    for kv in storage_values {
+        let status = match reconstruct_value(kv) {
+            Ok(value) => {
+                values.push(value);
+                presence_status(kv)
+            }
+            Err(err) => KVStatus::Malformed(err.to_string()),
+        };
+        statuses.push(KeyStatus::new(kv, status));
+    }
+
+    (values, statuses)
+}
@@ -0,0 +1,127 @@
+//! Adaptive update period of Interchain Queries.
+//!
+//! `update_period` of a query is set at registration, so a query either gets updated too often
+//! while the remote value stays the same, paying relayers for nothing, or too rarely while the
+//! value changes fast. `UpdatePeriodController` compares successive results of a query and returns
+//! a message to shorten the update period of the query if the value changes more than a threshold,
+//! or to extend it if the value stays stable, always keeping the period within configured bounds.
+//!
+//! A contract calls it from `sudo_kv_query_result`, e.g. with snapshots kept by
+//! `QuerySnapshots`, or from a cron handler:
+//! ```rust ignore
+//! let change = balances_change(&previous.value.coins, &current.value.coins);
+//! let msg = CONTROLLER.adjust_update_period(deps.as_ref(), env.contract.address, query_id, change)?;
+//! ```
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::helpers::update_interchain_query;
+use crate::interchain_queries::queries::get_registered_query;
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, Decimal256, Deps, Uint256};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Adjusts update periods of Interchain Queries depending on how much their results change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UpdatePeriodController {
+    /// update period, in Neutron blocks, the controller never goes below
+    pub min_update_period: u64,
+    /// update period, in Neutron blocks, the controller never goes above
+    pub max_update_period: u64,
+    /// relative change of a result, e.g. `0.01` for 1%, above which the update period is
+    /// shortened; the update period is extended if a result changes less than that
+    pub change_threshold: Decimal,
+    /// factor the update period is multiplied or divided by on every adjustment, at least 2
+    pub step_factor: u64,
+}
+
+impl UpdatePeriodController {
+    /// Checks the bounds are not empty and the step factor changes the period
+    pub fn validate(&self) -> NeutronResult<()> {
+        if self.min_update_period == 0 || self.min_update_period > self.max_update_period {
+            return Err(NeutronError::InvalidUpdatePeriodController(format!(
+                "invalid update period bounds [{}, {}]",
+                self.min_update_period, self.max_update_period
+            )));
+        }
+        if self.step_factor < 2 {
+            return Err(NeutronError::InvalidUpdatePeriodController(format!(
+                "update period step factor should be at least 2, got {}",
+                self.step_factor
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the next update period for a query with **current_update_period**
+    /// which result has changed by **change** since the previous one
+    pub fn next_update_period(&self, current_update_period: u64, change: Decimal) -> u64 {
+        let next = if change > self.change_threshold {
+            current_update_period / self.step_factor
+        } else {
+            current_update_period.saturating_mul(self.step_factor)
+        };
+
+        next.clamp(self.min_update_period, self.max_update_period)
+    }
+
+    /// Returns a message to update the period of the query with **query_id** if a result
+    /// that has changed by **change** since the previous one requires a different update period.
+    /// Returns `None` if the period stays the same.
+    ///
+    /// * **contract** is an address of the contract that owns the query. Must be contract that sends the message.
+    pub fn adjust_update_period(
+        &self,
+        deps: Deps,
+        contract: Addr,
+        query_id: u64,
+        change: Decimal,
+    ) -> NeutronResult<Option<CosmosMsg>> {
+        self.validate()?;
+
+        let registered_query = get_registered_query(deps, query_id)?;
+        let next_update_period = self.next_update_period(registered_query.update_period, change);
+        if next_update_period == registered_query.update_period {
+            return Ok(None);
+        }
+
+        // keys and transactions filter are left as is when they are empty in the update message
+        update_interchain_query(contract, query_id, vec![], next_update_period, None).map(Some)
+    }
+}
+
+/// Returns relative change of **current** value from **previous** one, e.g. `0.5` if a value
+/// has changed from 100 to 150 or to 50. A value appearing from zero is a change of `1`.
+pub fn relative_change(previous: Uint256, current: Uint256) -> Decimal {
+    let diff = previous.abs_diff(current);
+    if diff.is_zero() {
+        return Decimal::zero();
+    }
+    if previous.is_zero() {
+        return Decimal::one();
+    }
+
+    Decimal256::checked_from_ratio(diff, previous)
+        .ok()
+        .and_then(|change| Decimal::try_from(change).ok())
+        .unwrap_or(Decimal::MAX)
+}
+
+/// Returns the largest relative change of an amount among all the denoms of **previous**
+/// and **current** balances, a denom missing in one of the balances has zero amount there
+pub fn balances_change(previous: &[Coin], current: &[Coin]) -> Decimal {
+    let amount = |balances: &[Coin], denom: &str| {
+        balances
+            .iter()
+            .find(|coin| coin.denom == denom)
+            .map(|coin| coin.amount)
+            .unwrap_or_default()
+    };
+
+    previous
+        .iter()
+        .chain(current)
+        .map(|coin| relative_change(amount(previous, &coin.denom), amount(current, &coin.denom)))
+        .max()
+        .unwrap_or_default()
+}
@@ -1,15 +1,21 @@
 use super::types::{GOV_STORE_KEY, VOTES_KEY_PREFIX};
 use crate::errors::error::NeutronResult;
-use crate::interchain_queries::helpers::{decode_and_convert, length_prefix};
-use crate::interchain_queries::types::AddressBytes;
+use crate::interchain_queries::address::{decode_address_of_kind, AddressKind};
+use crate::interchain_queries::helpers::{encode_hex, length_prefix, merge_kv_keys};
+use crate::interchain_queries::legacy_dec::LegacyDec;
+use crate::interchain_queries::types::{
+    AddressBytes, TransactionFilterItem, TransactionFilterOp, TransactionFilterValue,
+};
 use crate::interchain_queries::v045::types::{
-    BALANCES_PREFIX, BANK_STORE_KEY, DELEGATION_KEY, FEE_POOL_KEY, PARAMS_STORE_DELIMITER,
-    PROPOSALS_KEY_PREFIX, SUPPLY_PREFIX, UNBONDING_DELEGATION_KEY, VALIDATORS_KEY,
+    BALANCES_PREFIX, BANK_STORE_KEY, DELEGATION_KEY, DISTRIBUTION_STORE_KEY, FEE_POOL_KEY,
+    ICAHOST_PARAMS_KEY, ICAHOST_STORE_KEY, KEY_ALLOW_MESSAGES, KEY_BOND_DENOM, KEY_HOST_ENABLED,
+    PARAMS_STORE_DELIMITER, PARAMS_STORE_KEY, PROPOSALS_KEY_PREFIX, RECIPIENT_FIELD,
+    STAKING_STORE_KEY, SUPPLY_PREFIX, UNBONDING_DELEGATION_KEY, VALIDATORS_KEY,
     VALIDATOR_SIGNING_INFO_KEY, WASM_CONTRACT_STORE_PREFIX,
 };
 use crate::NeutronError;
 use cosmos_sdk_proto::cosmos::staking::v1beta1::Commission as ValidatorCommission;
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::Uint128;
 use neutron_std::types::neutron::interchainqueries::KvKey;
 use std::str::{from_utf8, FromStr};
 
@@ -23,6 +29,51 @@ pub fn create_params_store_key(module: &str, key: &str) -> Vec<u8> {
     s.into_bytes()
 }
 
+/// Creates keys for an Interchain Query to get params of legacy **params** **subspace** by **keys**
+pub fn create_params_store_keys(subspace: &str, keys: &[String]) -> Vec<KvKey> {
+    keys.iter()
+        .map(|key| KvKey {
+            path: PARAMS_STORE_KEY.to_string(),
+            key: create_params_store_key(subspace, key),
+        })
+        .collect()
+}
+
+/// Deconstructs legacy **params** storage key into subspace and param key
+pub fn deconstruct_params_store_key(key: &[u8]) -> NeutronResult<(String, String)> {
+    let key = from_utf8(key).map_err(|e| {
+        NeutronError::InvalidQueryResultFormat(format!("invalid params key: {}", e))
+    })?;
+
+    match key.split_once(PARAMS_STORE_DELIMITER) {
+        Some((subspace, param)) if !subspace.is_empty() && !param.is_empty() => {
+            Ok((subspace.to_string(), param.to_string()))
+        }
+        _ => Err(NeutronError::InvalidQueryResultFormat(format!(
+            "invalid params key: {}",
+            key
+        ))),
+    }
+}
+
+/// Creates keys for an Interchain Query to get ICA host params from legacy **params** subspace
+/// <https://github.com/cosmos/ibc-go/blob/v7.3.1/modules/apps/27-interchain-accounts/host/types/params.go>
+pub fn create_ica_host_params_keys() -> Vec<KvKey> {
+    create_params_store_keys(
+        ICAHOST_STORE_KEY,
+        &[KEY_HOST_ENABLED.to_string(), KEY_ALLOW_MESSAGES.to_string()],
+    )
+}
+
+/// Creates key for an Interchain Query to get ICA host params from **icahost** module store (ibc-go v8+)
+/// <https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/apps/27-interchain-accounts/host/types/keys.go>
+pub fn create_ica_host_module_params_key() -> KvKey {
+    KvKey {
+        path: ICAHOST_STORE_KEY.to_string(),
+        key: ICAHOST_PARAMS_KEY.as_bytes().to_vec(),
+    }
+}
+
 /// Creates balances Cosmos-SDK storage prefix for account with **addr**
 /// <https://github.com/cosmos/cosmos-sdk/blob/ad9e5620fb3445c716e9de45cfcdb56e8f1745bf/x/bank/types/key.go#L55>
 pub fn create_account_balances_prefix<AddrBytes: AsRef<[u8]>>(
@@ -34,6 +85,48 @@ pub fn create_account_balances_prefix<AddrBytes: AsRef<[u8]>>(
     Ok(prefix)
 }
 
+/// Deconstructs a storage key for an **account** balance of a particular **denom** without copying.
+/// Returns two values borrowed from the **key**: **address** of an account and **denom**
+pub fn deconstruct_account_denom_balance_key_ref(key: &[u8]) -> NeutronResult<(&[u8], &str)> {
+    // the first element must be BALANCES_PREFIX
+    let (prefix, key) =
+        key.split_first()
+            .ok_or(NeutronError::AccountDenomBalanceKeyDeconstructionError(
+                "invalid key length".to_string(),
+            ))?;
+    if *prefix != BALANCES_PREFIX {
+        return Err(NeutronError::AccountDenomBalanceKeyDeconstructionError(
+            format!(
+                "first element in key does not equal to BALANCES_PREFIX: {:?} != {:?}",
+                prefix, BALANCES_PREFIX
+            ),
+        ));
+    }
+
+    // next we try read address bytes
+    let (address_length, key) =
+        key.split_first()
+            .ok_or(NeutronError::AccountDenomBalanceKeyDeconstructionError(
+                "invalid key length".to_string(),
+            ))?;
+    if key.len() < *address_length as usize {
+        return Err(NeutronError::AccountDenomBalanceKeyDeconstructionError(
+            "address length in key is invalid".to_string(),
+        ));
+    }
+    let (address, denom) = key.split_at(*address_length as usize);
+
+    // and the rest should be denom
+    let denom = from_utf8(denom)?;
+    if denom.is_empty() {
+        return Err(NeutronError::AccountDenomBalanceKeyDeconstructionError(
+            "denom in key can't be empty".to_string(),
+        ));
+    }
+
+    Ok((address, denom))
+}
+
 /// Creates **denom** balance Cosmos-SDK storage key for account with **addr**
 pub fn create_account_denom_balance_key<AddrBytes: AsRef<[u8]>, S: AsRef<str>>(
     addr: AddrBytes,
@@ -50,7 +143,7 @@ pub fn create_account_denom_balance_key<AddrBytes: AsRef<[u8]>, S: AsRef<str>>(
 /// * **addr** address of an account on remote chain for which you want to get balances;
 /// * **denoms** denominations of the coins for which you want to get balance;
 pub fn create_balances_query_keys(addr: String, denoms: Vec<String>) -> NeutronResult<Vec<KvKey>> {
-    let converted_addr_bytes = decode_and_convert(addr.as_str())?;
+    let converted_addr_bytes = decode_address_of_kind(&addr, AddressKind::Account)?;
     let mut kv_keys: Vec<KvKey> = Vec::with_capacity(denoms.len());
 
     for denom in denoms {
@@ -66,6 +159,38 @@ pub fn create_balances_query_keys(addr: String, denoms: Vec<String>) -> NeutronR
     Ok(kv_keys)
 }
 
+/// Adds keys for **denoms** to **keys** of a registered Interchain Query to get balances of account on remote chain.
+/// Denoms which are already in the query are skipped.
+///
+/// * **keys** are keys of the registered query, all of them must be balance keys of **addr**;
+/// * **addr** address of an account on remote chain for which the query gets balances;
+/// * **denoms** denominations of the coins to add to the query;
+pub fn add_balances_query_denoms(
+    keys: Vec<KvKey>,
+    addr: String,
+    denoms: Vec<String>,
+) -> NeutronResult<Vec<KvKey>> {
+    let converted_addr_bytes = decode_address_of_kind(&addr, AddressKind::Account)?;
+    let balances_prefix = create_account_balances_prefix(&converted_addr_bytes)?;
+
+    if let Some(key) = keys
+        .iter()
+        .find(|key| key.path != BANK_STORE_KEY || !key.key.starts_with(&balances_prefix))
+    {
+        return Err(NeutronError::IncompatibleQueryKeys(format!(
+            "{}/{} is not a balance key of {}",
+            key.path,
+            encode_hex(&key.key),
+            addr
+        )));
+    }
+
+    Ok(merge_kv_keys(
+        keys,
+        create_balances_query_keys(addr, denoms)?,
+    ))
+}
+
 /// Deconstructs a storage key for an **account** balance of a particular **denom**.
 /// Returns two values: **address** of an account and **denom**
 pub fn deconstruct_account_denom_balance_key<Key: IntoIterator<Item = u8>>(
@@ -225,6 +350,129 @@ pub fn create_fee_pool_key() -> NeutronResult<Vec<u8>> {
     Ok(key)
 }
 
+/// Creates keys for an Interchain Query to get fee pool from distribution module
+pub fn create_fee_pool_query_keys() -> NeutronResult<Vec<KvKey>> {
+    Ok(vec![KvKey {
+        path: DISTRIBUTION_STORE_KEY.to_string(),
+        key: create_fee_pool_key()?,
+    }])
+}
+
+/// Creates keys for an Interchain Query to get delegations of particular **delegator** to **validators**
+///
+/// * **delegator** is an address of an account on remote chain for which you want to get list of delegations;
+/// * **validators** is a list of validators addresses for which you want to get delegations from particular **delegator**;
+pub fn create_delegations_query_keys(
+    delegator: String,
+    validators: Vec<String>,
+) -> NeutronResult<Vec<KvKey>> {
+    let delegator_addr = decode_address_of_kind(&delegator, AddressKind::Account)?;
+
+    // Allocate memory for such KV keys as:
+    // * staking module params to get staking denomination
+    // * validators structures to calculate amount of delegated tokens
+    // * delegations structures to get info about delegations itself
+    let mut keys: Vec<KvKey> = Vec::with_capacity(validators.len() * 2 + 1);
+
+    // create KV key to get BondDenom from staking module params
+    keys.push(KvKey {
+        path: PARAMS_STORE_KEY.to_string(),
+        key: create_params_store_key(STAKING_STORE_KEY, KEY_BOND_DENOM),
+    });
+
+    for v in validators {
+        let val_addr = decode_address_of_kind(&v, AddressKind::ValidatorOperator)?;
+
+        // create delegation key to get delegation structure
+        keys.push(KvKey {
+            path: STAKING_STORE_KEY.to_string(),
+            key: create_delegation_key(&delegator_addr, &val_addr)?,
+        });
+
+        // create validator key to get validator structure
+        keys.push(KvKey {
+            path: STAKING_STORE_KEY.to_string(),
+            key: create_validator_key(&val_addr)?,
+        })
+    }
+
+    Ok(keys)
+}
+
+/// Updates keys of a registered Interchain Query to get delegations of particular **delegator**
+/// adding **add_validators** to it and removing **remove_validators** from it.
+/// Validators which are already in the query are skipped, staking denom key is always kept first.
+///
+/// * **keys** are keys of the registered query created by `create_delegations_query_keys`;
+/// * **delegator** is an address of an account on remote chain for which the query gets delegations;
+/// * **add_validators** is a list of validators addresses to add to the query;
+/// * **remove_validators** is a list of validators addresses to remove from the query;
+pub fn update_delegations_query_keys(
+    keys: Vec<KvKey>,
+    delegator: String,
+    add_validators: Vec<String>,
+    remove_validators: Vec<String>,
+) -> NeutronResult<Vec<KvKey>> {
+    let new_keys = create_delegations_query_keys(delegator.clone(), add_validators)?;
+
+    merge_delegations_query_keys(&keys, &new_keys, &delegator, remove_validators)
+}
+
+/// Merges **keys** of a registered delegations query with **new_keys** of the same layout, i.e.
+/// staking denom key followed by pairs of delegation and validator keys, skipping pairs of
+/// **remove_validators** and the ones which are already there
+pub(crate) fn merge_delegations_query_keys(
+    keys: &[KvKey],
+    new_keys: &[KvKey],
+    delegator: &str,
+    remove_validators: Vec<String>,
+) -> NeutronResult<Vec<KvKey>> {
+    let delegator_addr = decode_address_of_kind(delegator, AddressKind::Account)?;
+    let delegations_prefix = create_delegations_key(&delegator_addr)?;
+
+    let mut removed_keys: Vec<Vec<u8>> = Vec::with_capacity(remove_validators.len());
+    for v in remove_validators {
+        let val_addr = decode_address_of_kind(&v, AddressKind::ValidatorOperator)?;
+        removed_keys.push(create_validator_key(&val_addr)?);
+    }
+
+    let (denom_key, new_pairs) = new_keys.split_first().ok_or_else(|| {
+        NeutronError::IncompatibleQueryKeys("staking denom key is missing".to_string())
+    })?;
+    let pairs = match keys.split_first() {
+        Some((key, pairs)) if key == denom_key && pairs.len() % 2 == 0 => pairs,
+        _ => {
+            return Err(NeutronError::IncompatibleQueryKeys(
+                "delegations query keys should be staking denom key followed by pairs of delegation and validator keys"
+                    .to_string(),
+            ))
+        }
+    };
+
+    let mut merged: Vec<KvKey> = Vec::with_capacity(keys.len() + new_pairs.len());
+    merged.push(denom_key.clone());
+
+    for pair in pairs.chunks_exact(2).chain(new_pairs.chunks_exact(2)) {
+        let (delegation, validator) = (&pair[0], &pair[1]);
+        if delegation.path != STAKING_STORE_KEY || !delegation.key.starts_with(&delegations_prefix)
+        {
+            return Err(NeutronError::IncompatibleQueryKeys(format!(
+                "{}/{} is not a delegation key of {}",
+                delegation.path,
+                encode_hex(&delegation.key),
+                delegator
+            )));
+        }
+
+        if removed_keys.contains(&validator.key) || merged.contains(delegation) {
+            continue;
+        }
+        merged.extend_from_slice(pair);
+    }
+
+    Ok(merged)
+}
+
 /// Creates Cosmos-SDK governance key for proposal with specific id
 /// <https://github.com/cosmos/cosmos-sdk/blob/35ae2c4c72d4aeb33447d5a7af23ca47f786606e/x/gov/types/keys.go#L41>
 pub fn create_gov_proposal_key(proposal_id: u64) -> NeutronResult<Vec<u8>> {
@@ -279,7 +527,7 @@ pub fn create_gov_proposals_voters_votes_keys(
     let mut kv_keys: Vec<KvKey> = Vec::with_capacity(voters.len() * proposals_ids.len());
 
     for voter in voters {
-        let voter_addr = decode_and_convert(&voter)?;
+        let voter_addr = decode_address_of_kind(&voter, AddressKind::Account)?;
 
         for proposal_id in proposals_ids.clone() {
             let kv_key = KvKey {
@@ -294,24 +542,33 @@ pub fn create_gov_proposals_voters_votes_keys(
     Ok(kv_keys)
 }
 
-/// Returns validator max change rate
-pub fn get_max_change_rate(commission: &Option<ValidatorCommission>) -> Option<Decimal> {
-    let commission_rates = commission.as_ref().map(|v| v.commission_rates.as_ref())?;
-    commission_rates
-        .map(|v| Decimal::new(Uint128::from_str(v.max_change_rate.as_str()).unwrap_or_default()))
+/// Returns validator max change rate, fails if the rate is not a valid `LegacyDec`
+pub fn get_max_change_rate(
+    commission: &Option<ValidatorCommission>,
+) -> NeutronResult<Option<LegacyDec>> {
+    commission
+        .as_ref()
+        .and_then(|v| v.commission_rates.as_ref())
+        .map(|v| LegacyDec::from_atomics_str(&v.max_change_rate))
+        .transpose()
 }
 
-/// Returns validator max rate
-pub fn get_max_rate(commission: &Option<ValidatorCommission>) -> Option<Decimal> {
-    let commission_rates = commission.as_ref().map(|v| v.commission_rates.as_ref())?;
-    commission_rates
-        .map(|v| Decimal::new(Uint128::from_str(v.max_rate.as_str()).unwrap_or_default()))
+/// Returns validator max rate, fails if the rate is not a valid `LegacyDec`
+pub fn get_max_rate(commission: &Option<ValidatorCommission>) -> NeutronResult<Option<LegacyDec>> {
+    commission
+        .as_ref()
+        .and_then(|v| v.commission_rates.as_ref())
+        .map(|v| LegacyDec::from_atomics_str(&v.max_rate))
+        .transpose()
 }
 
-/// Returns current validator rate
-pub fn get_rate(commission: &Option<ValidatorCommission>) -> Option<Decimal> {
-    let commission_rates = commission.as_ref().map(|v| v.commission_rates.as_ref())?;
-    commission_rates.map(|v| Decimal::new(Uint128::from_str(v.rate.as_str()).unwrap_or_default()))
+/// Returns current validator rate, fails if the rate is not a valid `LegacyDec`
+pub fn get_rate(commission: &Option<ValidatorCommission>) -> NeutronResult<Option<LegacyDec>> {
+    commission
+        .as_ref()
+        .and_then(|v| v.commission_rates.as_ref())
+        .map(|v| LegacyDec::from_atomics_str(&v.rate))
+        .transpose()
 }
 
 /// Returns current validator rate
@@ -339,3 +596,23 @@ pub fn get_total_supply_amount(amount: &Vec<u8>) -> Option<Uint128> {
         .ok()
         .map(|a| Uint128::from_str(a).ok())?
 }
+
+/// Sets **recipient** in transactions filter of a registered Interchain Query to get transfers,
+/// other filters (e.g. min height) are kept as is
+pub fn set_transfers_query_recipient(
+    transactions_filter: Vec<TransactionFilterItem>,
+    recipient: String,
+) -> Vec<TransactionFilterItem> {
+    let mut filter: Vec<TransactionFilterItem> = vec![TransactionFilterItem {
+        field: RECIPIENT_FIELD.to_string(),
+        op: TransactionFilterOp::Eq,
+        value: TransactionFilterValue::String(recipient),
+    }];
+    filter.extend(
+        transactions_filter
+            .into_iter()
+            .filter(|item| item.field != RECIPIENT_FIELD || item.op != TransactionFilterOp::Eq),
+    );
+
+    filter
+}
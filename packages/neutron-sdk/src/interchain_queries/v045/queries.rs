@@ -4,13 +4,15 @@ use crate::{
         types::QueryType,
         v045::types::{
             Balances, Delegations, FeePool, GovernmentProposal, GovernmentProposalVotes,
-            SigningInfo, StakingValidator, StdDelegation, TotalSupply, UnbondingDelegations,
+            IcaHostParams, LegacyParams, SigningInfo, StakingValidator, StdDelegation, TotalSupply,
+            UnbondingDelegations,
         },
     },
     NeutronResult,
 };
 use cosmwasm_std::{Deps, Env};
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -76,6 +78,20 @@ pub struct DelegatorUnbondingDelegationsResponse {
     pub last_submitted_local_height: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct IcaHostParamsResponse {
+    pub params: IcaHostParams,
+    pub last_submitted_local_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct LegacyParamsResponse<T> {
+    pub params: LegacyParams<T>,
+    pub last_submitted_local_height: u64,
+}
+
 /// Returns balance of account on remote chain for particular denom
 /// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
 pub fn query_balance(
@@ -246,3 +262,41 @@ pub fn query_unbonding_delegations(
         last_submitted_local_height: registered_query.last_submitted_result_local_height,
     })
 }
+
+/// Returns ICA host params of remote chain
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_ica_host_params(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<IcaHostParamsResponse> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let params: IcaHostParams = query_kv_result(deps, registered_query_id)?;
+
+    Ok(IcaHostParamsResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        params,
+    })
+}
+
+/// Returns params of legacy **params** subspace of remote chain decoded into **T**
+/// * ***registered_query_id*** is an identifier of the corresponding registered interchain query
+pub fn query_legacy_params<T: DeserializeOwned>(
+    deps: Deps,
+    _env: Env,
+    registered_query_id: u64,
+) -> NeutronResult<LegacyParamsResponse<T>> {
+    let registered_query = get_registered_query(deps, registered_query_id)?;
+
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let params: LegacyParams<T> = query_kv_result(deps, registered_query_id)?;
+
+    Ok(LegacyParamsResponse {
+        last_submitted_local_height: registered_query.last_submitted_result_local_height,
+        params,
+    })
+}
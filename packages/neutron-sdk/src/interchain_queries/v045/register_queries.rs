@@ -1,22 +1,26 @@
 use crate::interchain_queries::helpers::{register_interchain_query, update_interchain_query};
+use crate::interchain_queries::queries::{check_query_type, get_registered_query};
 use crate::interchain_queries::types::{
-    QueryPayload, TransactionFilterItem, TransactionFilterOp, TransactionFilterValue,
+    QueryPayload, QueryType, TransactionFilterItem, TransactionFilterOp, TransactionFilterValue,
 };
 use crate::interchain_queries::v045::types::{
-    BANK_STORE_KEY, DISTRIBUTION_STORE_KEY, HEIGHT_FIELD, KEY_BOND_DENOM, PARAMS_STORE_KEY,
-    RECIPIENT_FIELD, SLASHING_STORE_KEY, STAKING_STORE_KEY, WASM_STORE_KEY,
+    BANK_STORE_KEY, HEIGHT_FIELD, RECIPIENT_FIELD, SLASHING_STORE_KEY, STAKING_STORE_KEY,
+    WASM_STORE_KEY,
 };
 use crate::{
     errors::error::NeutronResult,
-    interchain_queries::helpers::decode_and_convert,
+    interchain_queries::address::{decode_address_of_kind, AddressKind},
     interchain_queries::v045::helpers::{
-        create_balances_query_keys, create_delegation_key, create_fee_pool_key,
-        create_gov_proposal_keys, create_gov_proposals_voters_votes_keys, create_params_store_key,
-        create_total_denom_key, create_unbonding_delegation_key, create_validator_key,
-        create_validator_signing_info_key, create_wasm_contract_store_key,
+        add_balances_query_denoms, create_balances_query_keys, create_delegations_query_keys,
+        create_fee_pool_query_keys, create_gov_proposal_keys,
+        create_gov_proposals_voters_votes_keys, create_ica_host_module_params_key,
+        create_ica_host_params_keys, create_params_store_keys, create_total_denom_key,
+        create_unbonding_delegation_key, create_validator_key, create_validator_signing_info_key,
+        create_wasm_contract_store_key, set_transfers_query_recipient,
+        update_delegations_query_keys,
     },
 };
-use cosmwasm_std::{Addr, CosmosMsg};
+use cosmwasm_std::{Addr, CosmosMsg, Deps};
 use neutron_std::types::neutron::interchainqueries::KvKey;
 
 /// Creates a message to register an Interchain Query to get balance of account on remote chain for list of denoms
@@ -41,6 +45,36 @@ pub fn new_register_balances_query_msg(
     )
 }
 
+/// Creates a message to update a registered Interchain Query to get balances of account on remote chain
+/// adding **denoms** to it. Keys of the query are fetched from the chain, denoms which are already
+/// in the query are skipped, the update period is kept.
+///
+/// * **deps** is used to fetch the registered query;
+/// * **contract** is an address of the contract that updates the query. Must be contract that sends this message.
+/// * **query_id** is an ID of the registered balances query;
+/// * **addr** address of an account on remote chain for which the query gets balances;
+/// * **denoms** denominations of the coins to add to the query.
+pub fn update_balances_query_add_denoms_msg(
+    deps: Deps,
+    contract: Addr,
+    query_id: u64,
+    addr: String,
+    denoms: Vec<String>,
+) -> NeutronResult<CosmosMsg> {
+    let registered_query = get_registered_query(deps, query_id)?;
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let kv_keys = add_balances_query_denoms(registered_query.keys, addr, denoms)?;
+
+    update_interchain_query(
+        contract,
+        query_id,
+        kv_keys,
+        registered_query.update_period,
+        None,
+    )
+}
+
 /// Creates a message to register an Interchain Query to get balance of account on remote chain for a particular denom
 ///
 /// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
@@ -102,14 +136,9 @@ pub fn new_register_distribution_fee_pool_query_msg(
     connection_id: String,
     update_period: u64,
 ) -> NeutronResult<CosmosMsg> {
-    let kv_key = KvKey {
-        path: DISTRIBUTION_STORE_KEY.to_string(),
-        key: create_fee_pool_key()?,
-    };
-
     register_interchain_query(
         contract,
-        QueryPayload::KV(vec![kv_key]),
+        QueryPayload::KV(create_fee_pool_query_keys()?),
         connection_id,
         update_period,
     )
@@ -212,7 +241,7 @@ pub fn new_register_staking_validators_query_msg(
     let mut kv_keys: Vec<KvKey> = Vec::with_capacity(validators.len());
 
     for validator in validators {
-        let val_addr = decode_and_convert(&validator)?;
+        let val_addr = decode_address_of_kind(&validator, AddressKind::ValidatorOperator)?;
 
         let kv_key = KvKey {
             path: STAKING_STORE_KEY.to_string(),
@@ -245,7 +274,7 @@ pub fn new_register_validators_signing_infos_query_msg(
     let mut kv_keys: Vec<KvKey> = Vec::with_capacity(validators.len());
 
     for validator in validators {
-        let valcons_addr = decode_and_convert(&validator)?;
+        let valcons_addr = decode_address_of_kind(&validator, AddressKind::ValidatorConsensus)?;
 
         let kv_key = KvKey {
             path: SLASHING_STORE_KEY.to_string(),
@@ -277,35 +306,7 @@ pub fn new_register_delegator_delegations_query_msg(
     validators: Vec<String>,
     update_period: u64,
 ) -> NeutronResult<CosmosMsg> {
-    let delegator_addr = decode_and_convert(&delegator)?;
-
-    // Allocate memory for such KV keys as:
-    // * staking module params to get staking denomination
-    // * validators structures to calculate amount of delegated tokens
-    // * delegations structures to get info about delegations itself
-    let mut keys: Vec<KvKey> = Vec::with_capacity(validators.len() * 2 + 1);
-
-    // create KV key to get BondDenom from staking module params
-    keys.push(KvKey {
-        path: PARAMS_STORE_KEY.to_string(),
-        key: create_params_store_key(STAKING_STORE_KEY, KEY_BOND_DENOM),
-    });
-
-    for v in validators {
-        let val_addr = decode_and_convert(&v)?;
-
-        // create delegation key to get delegation structure
-        keys.push(KvKey {
-            path: STAKING_STORE_KEY.to_string(),
-            key: create_delegation_key(&delegator_addr, &val_addr)?,
-        });
-
-        // create validator key to get validator structure
-        keys.push(KvKey {
-            path: STAKING_STORE_KEY.to_string(),
-            key: create_validator_key(&val_addr)?,
-        })
-    }
+    let keys = create_delegations_query_keys(delegator, validators)?;
 
     register_interchain_query(
         contract,
@@ -315,6 +316,43 @@ pub fn new_register_delegator_delegations_query_msg(
     )
 }
 
+/// Creates a message to update a registered Interchain Query to get delegations of particular delegator
+/// on remote chain adding and removing validators. Keys of the query are fetched from the chain,
+/// validators which are already in the query are skipped, the update period is kept.
+///
+/// * **deps** is used to fetch the registered query;
+/// * **contract** is an address of the contract that updates the query. Must be contract that sends this message.
+/// * **query_id** is an ID of the registered delegations query;
+/// * **delegator** is an address of an account on remote chain for which the query gets delegations;
+/// * **add_validators** is a list of validators addresses to add to the query;
+/// * **remove_validators** is a list of validators addresses to remove from the query.
+pub fn update_delegator_delegations_query_msg(
+    deps: Deps,
+    contract: Addr,
+    query_id: u64,
+    delegator: String,
+    add_validators: Vec<String>,
+    remove_validators: Vec<String>,
+) -> NeutronResult<CosmosMsg> {
+    let registered_query = get_registered_query(deps, query_id)?;
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let keys = update_delegations_query_keys(
+        registered_query.keys,
+        delegator,
+        add_validators,
+        remove_validators,
+    )?;
+
+    update_interchain_query(
+        contract,
+        query_id,
+        keys,
+        registered_query.update_period,
+        None,
+    )
+}
+
 /// Creates a message to register an Interchain Query to get unbonding delegations of particular delegator on remote chain.
 ///
 /// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
@@ -329,13 +367,13 @@ pub fn new_register_delegator_unbonding_delegations_query_msg(
     validators: Vec<String>,
     update_period: u64,
 ) -> NeutronResult<CosmosMsg> {
-    let delegator_addr = decode_and_convert(&delegator)?;
+    let delegator_addr = decode_address_of_kind(&delegator, AddressKind::Account)?;
 
     // Allocate memory, one KV key per validator
     let mut keys: Vec<KvKey> = Vec::with_capacity(validators.len());
 
     for v in validators {
-        let val_addr = decode_and_convert(&v)?;
+        let val_addr = decode_address_of_kind(&v, AddressKind::ValidatorOperator)?;
 
         // create unbonding delegation key to get unbonding delegation structure
         keys.push(KvKey {
@@ -373,7 +411,7 @@ pub fn new_register_wasm_contract_store_query_msg(
     key: impl AsRef<[u8]>,
     update_period: u64,
 ) -> NeutronResult<CosmosMsg> {
-    let converted_addr_bytes = decode_and_convert(contract_address.as_str())?;
+    let converted_addr_bytes = decode_address_of_kind(&contract_address, AddressKind::Account)?;
     let wasm_key = create_wasm_contract_store_key(converted_addr_bytes, key.as_ref())?;
 
     let kv_key = KvKey {
@@ -389,6 +427,68 @@ pub fn new_register_wasm_contract_store_query_msg(
     )
 }
 
+/// Creates a message to register an Interchain Query to get ICA host params on remote chain
+/// from legacy **params** subspace of **icahost** module
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_ica_host_params_query_msg(
+    contract: Addr,
+    connection_id: String,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(create_ica_host_params_keys()),
+        connection_id,
+        update_period,
+    )
+}
+
+/// Creates a message to register an Interchain Query to get ICA host params on remote chain
+/// from **icahost** module store. Should be used for chains with ibc-go v8+,
+/// which don't keep ICA host params in legacy **params** subspace anymore.
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_ica_host_module_params_query_msg(
+    contract: Addr,
+    connection_id: String,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(vec![create_ica_host_module_params_key()]),
+        connection_id,
+        update_period,
+    )
+}
+
+/// Creates a message to register an Interchain Query to get params of legacy **params** subspace
+/// on remote chain. The result can be reconstructed into a typed `LegacyParams`.
+///
+/// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
+/// * **connection_id** is an IBC connection identifier between Neutron and remote chain;
+/// * **subspace** is a name of the params subspace, usually the same as module name, e.g. `transfer`;
+/// * **keys** is a list of param keys in the subspace, e.g. `SendEnabled`;
+/// * **update_period** is used to say how often the query must be updated.
+pub fn new_register_params_query_msg(
+    contract: Addr,
+    connection_id: String,
+    subspace: String,
+    keys: Vec<String>,
+    update_period: u64,
+) -> NeutronResult<CosmosMsg> {
+    register_interchain_query(
+        contract,
+        QueryPayload::KV(create_params_store_keys(&subspace, &keys)),
+        connection_id,
+        update_period,
+    )
+}
+
 /// Creates a message to register an Interchain Query to get transfer events to a recipient on a remote chain.
 ///
 /// * **contract** is an address of the contract that registers the query. Must be contract that sends this message.
@@ -423,3 +523,35 @@ pub fn new_register_transfers_query_msg(
         update_period,
     )
 }
+
+/// Creates a message to update a registered Interchain Query to get transfer events to a recipient
+/// on a remote chain changing the recipient. The transactions filter of the query is fetched from
+/// the chain, its other filters (e.g. min height) and the update period are kept.
+///
+/// * **deps** is used to fetch the registered query;
+/// * **contract** is an address of the contract that updates the query. Must be contract that sends this message.
+/// * **query_id** is an ID of the registered transfers query;
+/// * **recipient** is a new address of an account on remote chain for which you want to get list of transfer transactions.
+pub fn update_transfers_query_recipient_msg(
+    deps: Deps,
+    contract: Addr,
+    query_id: u64,
+    recipient: String,
+) -> NeutronResult<CosmosMsg> {
+    let registered_query = get_registered_query(deps, query_id)?;
+    check_query_type(registered_query.query_type, QueryType::TX)?;
+
+    let transactions_filter: Vec<TransactionFilterItem> =
+        serde_json_wasm::from_str(&registered_query.transactions_filter)?;
+
+    update_interchain_query(
+        contract,
+        query_id,
+        vec![],
+        registered_query.update_period,
+        Some(set_transfers_query_recipient(
+            transactions_filter,
+            recipient,
+        )),
+    )
+}
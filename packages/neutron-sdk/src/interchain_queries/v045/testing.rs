@@ -1,17 +1,32 @@
+use crate::interchain_queries::helpers::composite_kv_query_payload;
 use crate::interchain_queries::helpers::decode_and_convert;
-use crate::interchain_queries::types::{AddressBytes, KVReconstruct};
+use crate::interchain_queries::legacy_dec::LegacyDec;
+use crate::interchain_queries::types::{
+    AddressBytes, Composite, KVConstruct, KVReconstruct, KVReconstructRef, KVStatus, QueryPayload,
+    TransactionFilterItem, TransactionFilterOp, TransactionFilterValue, WithStatus,
+};
 use crate::interchain_queries::v045::helpers::{
-    create_account_denom_balance_key, create_delegation_key, create_fee_pool_key,
-    create_gov_proposal_key, create_gov_proposal_votes_key, create_params_store_key,
+    add_balances_query_denoms, create_account_denom_balance_key, create_balances_query_keys,
+    create_delegation_key, create_delegations_query_keys, create_fee_pool_key,
+    create_fee_pool_query_keys, create_gov_proposal_key, create_gov_proposal_votes_key,
+    create_ica_host_module_params_key, create_params_store_key, create_params_store_keys,
     create_total_denom_key, create_validator_key, create_validator_signing_info_key,
-    deconstruct_account_denom_balance_key,
+    deconstruct_account_denom_balance_key, deconstruct_params_store_key,
+    set_transfers_query_recipient, update_delegations_query_keys,
 };
-use crate::interchain_queries::v045::types::BALANCES_PREFIX;
 use crate::interchain_queries::v045::types::{
-    Balances, Delegations, FeePool, GovernmentProposal, GovernmentProposalVotes, Proposal,
-    ProposalVote, SigningInfo, StakingValidator, StdDelegation, TallyResult, TotalSupply,
-    UnbondingDelegations, UnbondingEntry, UnbondingResponse, Validator as ContractValidator,
-    ValidatorSigningInfo, WeightedVoteOption, DECIMAL_PLACES, KEY_BOND_DENOM, STAKING_STORE_KEY,
+    Balances, BalancesRef, Delegations, DelegationsContext, DelegationsRef, FeePool,
+    GovernmentProposal, GovernmentProposalVotes, IcaHostParams, LegacyParams, Proposal,
+    ProposalVote, SigningInfo, StakingValidator, StakingValidatorRef, StdDelegation, TallyResult,
+    TotalSupply, UnbondingDelegations, UnbondingEntry, UnbondingResponse,
+    Validator as ContractValidator, ValidatorSigningInfo, WeightedVoteOption, DECIMAL_PLACES,
+    ICAHOST_STORE_KEY, KEY_ALLOW_MESSAGES, KEY_BOND_DENOM, KEY_HOST_ENABLED, PARAMS_STORE_KEY,
+    STAKING_STORE_KEY,
+};
+use crate::interchain_queries::v045::types::{BALANCES_PREFIX, HEIGHT_FIELD, RECIPIENT_FIELD};
+use crate::interchain_queries::v047::types::{
+    Balances as V047Balances, BalancesRef as V047BalancesRef, Delegations as V047Delegations,
+    DelegationsRef as V047DelegationsRef, StakingValidator as V047StakingValidator,
 };
 use crate::{NeutronError, NeutronResult};
 use base64::prelude::*;
@@ -23,15 +38,22 @@ use cosmos_sdk_proto::cosmos::gov::v1beta1::{
     WeightedVoteOption as CosmosWeightedVoteOption,
 };
 use cosmos_sdk_proto::cosmos::slashing::v1beta1::ValidatorSigningInfo as CosmosValidatorSigningInfo;
+use cosmos_sdk_proto::cosmos::staking::v1beta1::Params as CosmosStakingParams;
 use cosmos_sdk_proto::cosmos::staking::v1beta1::{
-    Commission, CommissionRates, Delegation, Description, Validator,
+    Commission, CommissionRates, Delegation, Description, UnbondingDelegation,
+    UnbondingDelegationEntry, Validator,
 };
 use cosmos_sdk_proto::traits::Message;
 use cosmwasm_std::{
     to_json_binary, Addr, Binary, Coin as StdCoin, Decimal, Timestamp, Uint128, Uint256,
 };
 use hex;
+use neutron_std::shim::Any;
+use neutron_std::types::ibc::applications::interchain_accounts::host::v1::Params as IcaHostModuleParams;
 use neutron_std::types::neutron::interchainqueries::StorageValue;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::ops::Mul;
 use std::str::FromStr;
 
@@ -48,6 +70,22 @@ pub const DELEGATOR_DELEGATIONS_HEX_RESPONSE: &str = "0a2d636f736d6f73313566716a
 pub const DELEGATOR_UNBONDING_DELEGATIONS_HEX_RESPONSE: &str = "0a2d636f736d6f73316d396c33353878756e6868776473303536387a6134396d7a68767578783975787265357475641234636f736d6f7376616c6f7065723138686c356339786e35647a6532673530756177306c326d723032657735377a6b3061756b746e1a2108ed02120c08ba97f9ac0610f6abf18f021a0531303030302205313030303028011a2008f902120b08c797f9ac0610e59a89011a053230303030220532303030302802";
 pub const VALIDATOR_SIGNING_INFO_HEX_RESPONSE: &str = "0a34636f736d6f7376616c636f6e73313966353366717132387636706d7a383737646e653735643464376c307236356432373530707718102200";
 
+/// Context to construct delegations to **validators** which have 1.5 shares per token
+fn delegations_context(denom: &str, validators: &[&str]) -> DelegationsContext {
+    DelegationsContext {
+        denom: denom.to_string(),
+        validators: validators
+            .iter()
+            .map(|operator_address| Validator {
+                operator_address: operator_address.to_string(),
+                tokens: "2000000".to_string(),
+                delegator_shares: "3000000000000000000000000".to_string(),
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
 #[test]
 fn test_balance_reconstruct() {
     struct TestCase {
@@ -258,9 +296,9 @@ fn test_staking_validators_reconstruct() {
                     details: Some("Validator details".to_string()),
                     unbonding_height: 0,
                     unbonding_time: Some(1203981203),
-                    rate: Some(Decimal::from_str("5").unwrap()),
-                    max_rate: Some(Decimal::from_str("20").unwrap()),
-                    max_change_rate: Some(Decimal::from_str("1").unwrap()),
+                    rate: Some(LegacyDec::from_str("5").unwrap()),
+                    max_rate: Some(LegacyDec::from_str("20").unwrap()),
+                    max_change_rate: Some(LegacyDec::from_str("1").unwrap()),
                     update_time: Some(56324234),
                     min_self_delegation: Decimal::from_str("0").unwrap(),
                     jailed: false,
@@ -951,7 +989,7 @@ fn test_delegations_reconstruct() {
             delegations: vec![],
             validators: vec![],
             expected_result: Err(NeutronError::InvalidQueryResultFormat(
-                "denom is empty".into(),
+                "storage value 0: expected JSON encoded bond denom: value is empty".into(),
             )),
         },
         TestCase {
@@ -963,7 +1001,7 @@ fn test_delegations_reconstruct() {
             }],
             validators: vec![],
             expected_result: Err(NeutronError::InvalidQueryResultFormat(
-                "validator is empty".into(),
+                "storage value 2: expected cosmos.staking.v1beta1.Validator: value is empty".into(),
             )),
         },
     ];
@@ -975,9 +1013,10 @@ fn test_delegations_reconstruct() {
             key: create_params_store_key(STAKING_STORE_KEY, KEY_BOND_DENOM),
             value: {
                 if ts.stake_denom.is_empty() {
-                    return Default::default();
+                    vec![]
+                } else {
+                    to_json_binary(&ts.stake_denom).unwrap().to_vec()
                 }
-                to_json_binary(&ts.stake_denom).unwrap().to_vec()
             },
             proof: None,
         }];
@@ -993,23 +1032,34 @@ fn test_delegations_reconstruct() {
                 proof: None,
             });
 
-            if let Some(v) = ts.validators.get(i) {
-                st_values.push(StorageValue {
-                    storage_prefix: STAKING_STORE_KEY.to_string(),
-                    key: create_validator_key(&val_addr).unwrap(),
-                    value: v.encode_to_vec(),
-                    proof: None,
-                });
-            }
+            // absent validator is represented by an empty value
+            st_values.push(StorageValue {
+                storage_prefix: STAKING_STORE_KEY.to_string(),
+                key: create_validator_key(&val_addr).unwrap(),
+                value: ts
+                    .validators
+                    .get(i)
+                    .map(|v| v.encode_to_vec())
+                    .unwrap_or_default(),
+                proof: None,
+            });
         }
 
         // test reconstruction
         let delegations = Delegations::reconstruct(&st_values);
 
-        assert_eq!(
-            delegations.as_ref().unwrap(),
-            ts.expected_result.as_ref().unwrap()
-        )
+        match (&delegations, &ts.expected_result) {
+            (Ok(val), Ok(expected)) => assert_eq!(val, expected),
+            (Err(err), Err(expected_err)) => {
+                // Compare error messages since NeutronError doesn't implement PartialEq
+                assert_eq!(err.to_string(), expected_err.to_string())
+            }
+            _ => panic!(
+                "Result type mismatch: got {:?}, expected {:?}",
+                delegations.is_ok(),
+                ts.expected_result.is_ok()
+            ),
+        }
     }
 }
 
@@ -1120,9 +1170,9 @@ fn test_staking_validators_reconstruct_from_hex() {
                 details: Some(String::from("")),
                 unbonding_height: 0u64,
                 unbonding_time: Some(0u64),
-                rate: Some(Decimal::from_str("0.100000000000000000").unwrap()),
-                max_rate: Some(Decimal::from_str("0.200000000000000000").unwrap()),
-                max_change_rate: Some(Decimal::from_str("0.010000000000000000").unwrap()),
+                rate: Some(LegacyDec::from_str("0.100000000000000000").unwrap()),
+                max_rate: Some(LegacyDec::from_str("0.200000000000000000").unwrap()),
+                max_change_rate: Some(LegacyDec::from_str("0.010000000000000000").unwrap()),
                 update_time: Some(1683291676u64), // mutating
                 min_self_delegation: Decimal::one(),
             }]
@@ -1461,9 +1511,10 @@ fn test_delegations_reconstruct_overflow() {
             key: create_params_store_key(STAKING_STORE_KEY, KEY_BOND_DENOM),
             value: {
                 if ts.stake_denom.is_empty() {
-                    return Default::default();
+                    vec![]
+                } else {
+                    to_json_binary(&ts.stake_denom).unwrap().to_vec()
                 }
-                to_json_binary(&ts.stake_denom).unwrap().to_vec()
             },
             proof: None,
         }];
@@ -1479,14 +1530,17 @@ fn test_delegations_reconstruct_overflow() {
                 proof: None,
             });
 
-            if let Some(v) = ts.validators.get(i) {
-                st_values.push(StorageValue {
-                    storage_prefix: STAKING_STORE_KEY.to_string(),
-                    key: create_validator_key(&val_addr).unwrap(),
-                    value: v.encode_to_vec(),
-                    proof: None,
-                });
-            }
+            // absent validator is represented by an empty value
+            st_values.push(StorageValue {
+                storage_prefix: STAKING_STORE_KEY.to_string(),
+                key: create_validator_key(&val_addr).unwrap(),
+                value: ts
+                    .validators
+                    .get(i)
+                    .map(|v| v.encode_to_vec())
+                    .unwrap_or_default(),
+                proof: None,
+            });
         }
 
         // test reconstruction
@@ -1498,3 +1552,1368 @@ fn test_delegations_reconstruct_overflow() {
         )
     }
 }
+
+#[test]
+fn test_ica_host_params_reconstruct() {
+    // legacy params subspace values are JSON encoded
+    let legacy_values = vec![
+        StorageValue {
+            storage_prefix: PARAMS_STORE_KEY.to_string(),
+            key: create_params_store_key(ICAHOST_STORE_KEY, KEY_HOST_ENABLED),
+            value: to_json_binary(&true).unwrap().to_vec(),
+            proof: None,
+        },
+        StorageValue {
+            storage_prefix: PARAMS_STORE_KEY.to_string(),
+            key: create_params_store_key(ICAHOST_STORE_KEY, KEY_ALLOW_MESSAGES),
+            value: to_json_binary(&vec!["/cosmos.bank.v1beta1.MsgSend"])
+                .unwrap()
+                .to_vec(),
+            proof: None,
+        },
+    ];
+    assert_eq!(
+        IcaHostParams::reconstruct(&legacy_values).unwrap(),
+        IcaHostParams {
+            host_enabled: true,
+            allow_messages: vec!["/cosmos.bank.v1beta1.MsgSend".to_string()],
+        }
+    );
+
+    // icahost module store value is protobuf encoded
+    let module_values = vec![StorageValue {
+        storage_prefix: ICAHOST_STORE_KEY.to_string(),
+        key: create_ica_host_module_params_key().key,
+        value: IcaHostModuleParams {
+            host_enabled: false,
+            allow_messages: vec!["*".to_string()],
+        }
+        .encode_to_vec(),
+        proof: None,
+    }];
+    assert_eq!(
+        IcaHostParams::reconstruct(&module_values).unwrap(),
+        IcaHostParams {
+            host_enabled: false,
+            allow_messages: vec!["*".to_string()],
+        }
+    );
+
+    assert_eq!(
+        IcaHostParams::reconstruct(&[]).unwrap_err().to_string(),
+        "Invalid query result format: expected 1 or 2 storage values for icahost params, got 0"
+    );
+}
+
+#[test]
+fn test_ica_host_params_check_msgs() {
+    let msg = |type_url: &str| Any {
+        type_url: type_url.to_string(),
+        value: vec![],
+    };
+
+    let params = IcaHostParams {
+        host_enabled: true,
+        allow_messages: vec!["/cosmos.staking.v1beta1.MsgDelegate".to_string()],
+    };
+    assert!(params
+        .check_msgs(&[msg("/cosmos.staking.v1beta1.MsgDelegate")])
+        .is_ok());
+    assert_eq!(
+        params
+            .check_msgs(&[
+                msg("/cosmos.staking.v1beta1.MsgDelegate"),
+                msg("/cosmos.bank.v1beta1.MsgSend"),
+            ])
+            .unwrap_err()
+            .to_string(),
+        "message \"/cosmos.bank.v1beta1.MsgSend\" is not allowed by ICA host on remote chain"
+    );
+
+    let params = IcaHostParams {
+        host_enabled: true,
+        allow_messages: vec!["*".to_string()],
+    };
+    assert!(params
+        .check_msgs(&[msg("/cosmos.bank.v1beta1.MsgSend")])
+        .is_ok());
+
+    let params = IcaHostParams {
+        host_enabled: false,
+        allow_messages: vec!["*".to_string()],
+    };
+    assert_eq!(
+        params
+            .check_msgs(&[msg("/cosmos.bank.v1beta1.MsgSend")])
+            .unwrap_err()
+            .to_string(),
+        "ICA host is disabled on remote chain"
+    );
+}
+
+#[test]
+fn test_legacy_params_reconstruct() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct TransferParams {
+        #[serde(rename = "SendEnabled")]
+        send_enabled: bool,
+        #[serde(rename = "ReceiveEnabled")]
+        receive_enabled: Option<bool>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct DistributionParams {
+        #[serde(rename = "communitytax")]
+        community_tax: Decimal,
+        #[serde(rename = "withdrawaddrenabled")]
+        withdraw_addr_enabled: bool,
+    }
+
+    let storage_value = |subspace: &str, key: &str, value: &[u8]| StorageValue {
+        storage_prefix: PARAMS_STORE_KEY.to_string(),
+        key: create_params_store_key(subspace, key),
+        value: value.to_vec(),
+        proof: None,
+    };
+
+    // absent params are skipped
+    let params: LegacyParams<TransferParams> = LegacyParams::reconstruct(&[
+        storage_value("transfer", "SendEnabled", b"true"),
+        storage_value("transfer", "ReceiveEnabled", b""),
+    ])
+    .unwrap();
+    assert_eq!(
+        params.params,
+        TransferParams {
+            send_enabled: true,
+            receive_enabled: None,
+        }
+    );
+
+    let params: LegacyParams<DistributionParams> = LegacyParams::reconstruct(&[
+        storage_value("distribution", "communitytax", b"\"0.020000000000000000\""),
+        storage_value("distribution", "withdrawaddrenabled", b"false"),
+    ])
+    .unwrap();
+    assert_eq!(
+        params.params,
+        DistributionParams {
+            community_tax: Decimal::percent(2),
+            withdraw_addr_enabled: false,
+        }
+    );
+
+    // a value can't break the object it is put into
+    let err = LegacyParams::<TransferParams>::reconstruct(&[storage_value(
+        "transfer",
+        "SendEnabled",
+        b"true,\"ReceiveEnabled\":true",
+    )])
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Invalid query result format: storage value 0: expected JSON encoded value of param SendEnabled"));
+
+    // required param is missing
+    assert!(LegacyParams::<TransferParams>::reconstruct(&[]).is_err());
+}
+
+#[test]
+fn test_params_store_keys() {
+    let keys = create_params_store_keys(
+        "transfer",
+        &["SendEnabled".to_string(), "ReceiveEnabled".to_string()],
+    );
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0].path, PARAMS_STORE_KEY);
+    assert_eq!(keys[0].key, b"transfer/SendEnabled".to_vec());
+    assert_eq!(keys[1].key, b"transfer/ReceiveEnabled".to_vec());
+
+    assert_eq!(
+        deconstruct_params_store_key(&keys[1].key).unwrap(),
+        ("transfer".to_string(), "ReceiveEnabled".to_string())
+    );
+    assert!(deconstruct_params_store_key(b"transfer").is_err());
+    assert!(deconstruct_params_store_key(b"/SendEnabled").is_err());
+}
+
+#[test]
+fn test_composite_reconstruct() {
+    let addr = decode_and_convert("osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs").unwrap();
+    let balance = |denom: &str, amount: &str| StorageValue {
+        storage_prefix: "".to_string(),
+        key: create_account_denom_balance_key(&addr, denom).unwrap(),
+        value: Coin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        }
+        .encode_to_vec(),
+        proof: None,
+    };
+    let fee_pool = StorageValue {
+        storage_prefix: "".to_string(),
+        key: create_fee_pool_key().unwrap(),
+        value: CosmosFeePool {
+            community_pool: vec![DecCoin {
+                denom: "uosmo".to_string(),
+                amount: "5000000000000000000000".to_string(),
+            }],
+        }
+        .encode_to_vec(),
+        proof: None,
+    };
+
+    let storage_values = vec![
+        balance("uosmo", "100"),
+        balance("uatom", "200"),
+        fee_pool,
+        balance("uluna", "300"),
+    ];
+
+    // the last part takes the rest of storage values
+    let (balances, fee_pool, rest): (Composite<Balances, 2>, FeePool, Balances) =
+        KVReconstruct::reconstruct(&storage_values).unwrap();
+    assert_eq!(
+        balances.0.coins,
+        vec![
+            StdCoin::new(100u128, "uosmo"),
+            StdCoin::new(200u128, "uatom")
+        ]
+    );
+    assert_eq!(fee_pool.coins, vec![StdCoin::new(5000u128, "uosmo")]);
+    assert_eq!(rest.coins, vec![StdCoin::new(300u128, "uluna")]);
+
+    // not enough storage values for a part with known keys count
+    let err = <(Composite<Balances, 2>, FeePool)>::reconstruct(&storage_values[..1]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid query result format: expected at least 2 storage values for composite query part, got 1"
+    );
+
+    // the last composite part must match its keys count exactly
+    let err = <(FeePool, Composite<Balances, 2>)>::reconstruct(&storage_values[2..]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid query result format: expected 2 storage values for composite query part, got 1"
+    );
+}
+
+#[test]
+fn test_composite_kv_query_payload() {
+    let balances_keys = create_balances_query_keys(
+        "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+        vec!["uosmo".to_string(), "uatom".to_string()],
+    )
+    .unwrap();
+    let fee_pool_keys = create_fee_pool_query_keys().unwrap();
+
+    let payload = composite_kv_query_payload(vec![balances_keys.clone(), fee_pool_keys.clone()]);
+    match payload {
+        QueryPayload::KV(keys) => assert_eq!(
+            keys,
+            balances_keys
+                .into_iter()
+                .chain(fee_pool_keys)
+                .collect::<Vec<_>>()
+        ),
+        QueryPayload::TX(_) => panic!("expected KV payload"),
+    }
+}
+
+#[test]
+fn test_balances_reconstruct_with_status() {
+    let addr = decode_and_convert("osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs").unwrap();
+    let storage_values = vec![
+        StorageValue {
+            storage_prefix: "bank".to_string(),
+            key: create_account_denom_balance_key(&addr, "uosmo").unwrap(),
+            value: Coin {
+                denom: "uosmo".to_string(),
+                amount: "100".to_string(),
+            }
+            .encode_to_vec(),
+            proof: None,
+        },
+        // absent balance, e.g. a misspelled denom
+        StorageValue {
+            storage_prefix: "bank".to_string(),
+            key: create_account_denom_balance_key(&addr, "uatom").unwrap(),
+            value: vec![],
+            proof: None,
+        },
+        // the key is not a balance key
+        StorageValue {
+            storage_prefix: "bank".to_string(),
+            key: vec![1, 2, 3],
+            value: vec![],
+            proof: None,
+        },
+        // the value is not a coin
+        StorageValue {
+            storage_prefix: "bank".to_string(),
+            key: create_account_denom_balance_key(&addr, "uluna").unwrap(),
+            value: vec![0xff, 0xff],
+            proof: None,
+        },
+    ];
+
+    let result = WithStatus::<Balances>::reconstruct(&storage_values).unwrap();
+    assert_eq!(
+        result.result.coins,
+        vec![StdCoin::new(100u128, "uosmo"), StdCoin::new(0u128, "uatom")]
+    );
+
+    assert_eq!(result.statuses.len(), storage_values.len());
+    for (status, kv) in result.statuses.iter().zip(storage_values.iter()) {
+        assert_eq!(status.storage_prefix, kv.storage_prefix);
+        assert_eq!(status.key.as_slice(), kv.key.as_slice());
+    }
+    assert_eq!(result.statuses[0].status, KVStatus::Present);
+    assert_eq!(result.statuses[1].status, KVStatus::Absent);
+    assert!(matches!(result.statuses[2].status, KVStatus::Malformed(_)));
+    assert!(matches!(result.statuses[3].status, KVStatus::Malformed(_)));
+
+    // plain reconstruct fails on the first malformed value
+    assert!(Balances::reconstruct(&storage_values).is_err());
+}
+
+#[test]
+fn test_delegations_reconstruct_with_status() {
+    let delegation = |validator: &str| Delegation {
+        delegator_address: "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+        validator_address: validator.to_string(),
+        shares: "1000000000000000000".to_string(),
+    };
+    let validator = |operator: &str| Validator {
+        operator_address: operator.to_string(),
+        consensus_pubkey: None,
+        jailed: false,
+        status: 0,
+        tokens: "1000000000000000000".to_string(),
+        delegator_shares: "1000000000000000000".to_string(),
+        description: None,
+        unbonding_height: 0,
+        unbonding_time: None,
+        commission: None,
+        min_self_delegation: "".to_string(),
+    };
+    let storage_value = |value: Vec<u8>| StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key: vec![],
+        value,
+        proof: None,
+    };
+
+    let validator1 = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3";
+    let validator2 = "osmovaloper1lzhlnpahvznwfv4jmay2tgaha5kmz5qxwmj9we";
+    let validator3 = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e4";
+    let storage_values = vec![
+        storage_value(to_json_binary("stake").unwrap().to_vec()),
+        storage_value(delegation(validator1).encode_to_vec()),
+        storage_value(validator(validator1).encode_to_vec()),
+        // delegation is absent
+        storage_value(vec![]),
+        storage_value(validator(validator2).encode_to_vec()),
+        // validator is absent
+        storage_value(delegation(validator3).encode_to_vec()),
+        storage_value(vec![]),
+    ];
+
+    let result = WithStatus::<Delegations>::reconstruct(&storage_values).unwrap();
+    assert_eq!(
+        result.result,
+        Delegations {
+            delegations: vec![StdDelegation {
+                delegator: Addr::unchecked("osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs"),
+                validator: validator1.to_string(),
+                amount: StdCoin::new(1000000000000000000u128, "stake"),
+            }],
+        }
+    );
+    assert_eq!(
+        result
+            .statuses
+            .into_iter()
+            .map(|s| s.status)
+            .collect::<Vec<_>>(),
+        vec![
+            KVStatus::Present,
+            KVStatus::Present,
+            KVStatus::Present,
+            KVStatus::Absent,
+            KVStatus::Present,
+            KVStatus::Present,
+            KVStatus::Absent,
+        ]
+    );
+
+    // without denom no delegation can be reconstructed, but statuses of the rest keys are reported
+    let mut without_denom = storage_values[..3].to_vec();
+    without_denom[0].value = vec![];
+    let result = WithStatus::<Delegations>::reconstruct(&without_denom).unwrap();
+    assert!(result.result.delegations.is_empty());
+    assert_eq!(
+        result
+            .statuses
+            .into_iter()
+            .map(|s| s.status)
+            .collect::<Vec<_>>(),
+        vec![KVStatus::Absent, KVStatus::Present, KVStatus::Present]
+    );
+}
+
+/// Deterministic xorshift generator, so failures of the property tests below are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn bytes(&mut self, max_len: usize) -> Vec<u8> {
+        let len = self.below(max_len + 1);
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+
+    fn amount(&mut self) -> String {
+        let amounts = [
+            "",
+            "0",
+            "1",
+            "-1",
+            "1.5",
+            "abc",
+            "1000000000000000000",
+            "340282366920938463463374607431768211455",
+            "340282366920938463463374607431768211456",
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+        ];
+        amounts[self.below(amounts.len())].to_string()
+    }
+
+    fn timestamp(&mut self) -> Option<prost_types::Timestamp> {
+        let seconds = [0, 1_700_000_000, -1, i64::MAX, i64::MIN];
+        let nanos = [0, 999_999_999, -1, i32::MAX];
+        match self.below(3) {
+            0 => None,
+            _ => Some(prost_types::Timestamp {
+                seconds: seconds[self.below(seconds.len())],
+                nanos: nanos[self.below(nanos.len())],
+            }),
+        }
+    }
+
+    fn key(&mut self) -> Vec<u8> {
+        let addr = decode_and_convert("osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs").unwrap();
+        match self.below(4) {
+            0 => create_account_denom_balance_key(&addr, "uosmo").unwrap(),
+            1 => create_params_store_key(STAKING_STORE_KEY, KEY_BOND_DENOM),
+            _ => self.bytes(24),
+        }
+    }
+
+    /// Returns a value which is either random or a valid encoding of some structure
+    /// stored on remote chain with random fields
+    fn value(&mut self) -> Vec<u8> {
+        let value = match self.below(14) {
+            0 => vec![],
+            1 => self.bytes(64),
+            2 => to_json_binary("stake").unwrap().to_vec(),
+            3 => b"true".to_vec(),
+            4 => Coin {
+                denom: "uosmo".to_string(),
+                amount: self.amount(),
+            }
+            .encode_to_vec(),
+            5 => CosmosFeePool {
+                community_pool: vec![DecCoin {
+                    denom: "uosmo".to_string(),
+                    amount: self.amount(),
+                }],
+            }
+            .encode_to_vec(),
+            6 => Delegation {
+                delegator_address: "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+                validator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+                shares: self.amount(),
+            }
+            .encode_to_vec(),
+            7 => Validator {
+                operator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+                tokens: self.amount(),
+                delegator_shares: self.amount(),
+                unbonding_time: self.timestamp(),
+                min_self_delegation: self.amount(),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+            8 => UnbondingDelegation {
+                delegator_address: "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+                validator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+                entries: vec![UnbondingDelegationEntry {
+                    creation_height: self.next() as i64,
+                    completion_time: self.timestamp(),
+                    initial_balance: self.amount(),
+                    balance: self.amount(),
+                }],
+            }
+            .encode_to_vec(),
+            9 => CosmosProposal {
+                total_deposit: vec![Coin {
+                    denom: "uosmo".to_string(),
+                    amount: self.amount(),
+                }],
+                submit_time: self.timestamp(),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+            10 => Vote {
+                proposal_id: self.next(),
+                voter: "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+                options: vec![CosmosWeightedVoteOption {
+                    option: self.next() as i32,
+                    weight: self.amount(),
+                }],
+                ..Default::default()
+            }
+            .encode_to_vec(),
+            11 => CosmosValidatorSigningInfo {
+                jailed_until: self.timestamp(),
+                index_offset: self.next() as i64,
+                ..Default::default()
+            }
+            .encode_to_vec(),
+            12 => IcaHostModuleParams {
+                host_enabled: true,
+                allow_messages: vec!["*".to_string()],
+            }
+            .encode_to_vec(),
+            _ => CosmosStakingParams {
+                bond_denom: "stake".to_string(),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        };
+
+        // sometimes truncate a valid encoding
+        if self.below(5) == 0 && !value.is_empty() {
+            let len = self.below(value.len());
+            return value[..len].to_vec();
+        }
+
+        value
+    }
+
+    fn storage_values(&mut self) -> Vec<StorageValue> {
+        let len = self.below(8);
+        (0..len)
+            .map(|_| StorageValue {
+                storage_prefix: STAKING_STORE_KEY.to_string(),
+                key: self.key(),
+                value: self.value(),
+                proof: None,
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_reconstruct_never_panics() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..5000 {
+        let storage_values = rng.storage_values();
+
+        // results don't matter, malformed input must be reported as an error instead of a panic
+        let _ = Balances::reconstruct(&storage_values);
+        let _ = TotalSupply::reconstruct(&storage_values);
+        let _ = FeePool::reconstruct(&storage_values);
+        let _ = StakingValidator::reconstruct(&storage_values);
+        let _ = SigningInfo::reconstruct(&storage_values);
+        let _ = GovernmentProposal::reconstruct(&storage_values);
+        let _ = GovernmentProposalVotes::reconstruct(&storage_values);
+        let _ = Delegations::reconstruct(&storage_values);
+        let _ = UnbondingDelegations::reconstruct(&storage_values);
+        let _ = IcaHostParams::reconstruct(&storage_values);
+        let _ = LegacyParams::<BTreeMap<String, String>>::reconstruct(&storage_values);
+        let _ = WithStatus::<Balances>::reconstruct(&storage_values);
+        let _ = WithStatus::<Delegations>::reconstruct(&storage_values);
+        let _ = <(FeePool, Balances)>::reconstruct(&storage_values);
+
+        let _ = V047Balances::reconstruct(&storage_values);
+        let _ = V047Delegations::reconstruct(&storage_values);
+        let _ = WithStatus::<V047Balances>::reconstruct(&storage_values);
+        let _ = WithStatus::<V047Delegations>::reconstruct(&storage_values);
+
+        // zero-copy reconstructors must agree with the owned ones whenever both succeed
+        assert_same_if_ok::<Balances, BalancesRef>(&storage_values);
+        assert_same_if_ok::<StakingValidator, StakingValidatorRef>(&storage_values);
+        assert_same_if_ok::<Delegations, DelegationsRef>(&storage_values);
+        assert_same_if_ok::<V047Balances, V047BalancesRef>(&storage_values);
+        assert_same_if_ok::<V047Delegations, V047DelegationsRef>(&storage_values);
+    }
+}
+
+/// Checks a zero-copy reconstructor **R** returns the same value as the owned one **T**
+/// if both of them succeed
+fn assert_same_if_ok<'a, T, R>(storage_values: &'a [StorageValue])
+where
+    T: KVReconstruct + PartialEq + std::fmt::Debug + From<R>,
+    R: KVReconstructRef<'a>,
+{
+    if let (Ok(owned), Ok(borrowed)) = (
+        T::reconstruct(storage_values),
+        R::reconstruct_ref(storage_values),
+    ) {
+        assert_eq!(owned, T::from(borrowed));
+    }
+}
+
+#[test]
+fn test_delegations_reconstruct_shape_errors() {
+    let storage_value = |value: Vec<u8>| StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key: vec![],
+        value,
+        proof: None,
+    };
+    let denom = storage_value(to_json_binary("stake").unwrap().to_vec());
+    let delegation = storage_value(
+        Delegation {
+            delegator_address: "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+            validator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+            shares: "1000000000000000000".to_string(),
+        }
+        .encode_to_vec(),
+    );
+    let validator = |delegator_shares: &str| {
+        storage_value(
+            Validator {
+                operator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+                tokens: "1000000000000000000".to_string(),
+                delegator_shares: delegator_shares.to_string(),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        )
+    };
+
+    // delegation without validator
+    assert_eq!(
+        Delegations::reconstruct(&[denom.clone(), delegation.clone()])
+            .unwrap_err()
+            .to_string(),
+        "Invalid query result format: expected denom followed by pairs of delegation and validator storage values, got 2 storage values"
+    );
+
+    // malformed validator is reported with its index
+    let err = Delegations::reconstruct(&[
+        denom.clone(),
+        delegation.clone(),
+        validator("1000000000000000000"),
+        delegation.clone(),
+        storage_value(vec![0xff, 0xff]),
+    ])
+    .unwrap_err();
+    assert!(err.to_string().starts_with(
+        "Invalid query result format: storage value 4: expected cosmos.staking.v1beta1.Validator: "
+    ));
+
+    // validator without shares
+    let err = Delegations::reconstruct(&[denom.clone(), delegation, validator("0")]).unwrap_err();
+    assert!(err.to_string().starts_with(
+        "Invalid query result format: storage value 2: expected cosmos.staking.v1beta1.Validator: can't compute delegated tokens of validator osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3: "
+    ));
+
+    // delegation with malformed shares
+    let err = Delegations::reconstruct(&[
+        denom,
+        storage_value(
+            Delegation {
+                delegator_address: "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+                validator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+                shares: "one".to_string(),
+            }
+            .encode_to_vec(),
+        ),
+        validator("1000000000000000000"),
+    ])
+    .unwrap_err();
+    assert!(err.to_string().starts_with(
+        "Invalid query result format: storage value 1: expected cosmos.staking.v1beta1.Delegation: invalid shares: "
+    ));
+}
+
+#[test]
+fn test_reconstruct_malformed_amounts() {
+    // total supply which is not an amount
+    let total_supply = |amount: &str| StorageValue {
+        storage_prefix: "bank".to_string(),
+        key: create_total_denom_key("uatom").unwrap(),
+        value: amount.as_bytes().to_vec(),
+        proof: None,
+    };
+    assert_eq!(
+        TotalSupply::reconstruct(&[total_supply(""), total_supply("100")]).unwrap(),
+        TotalSupply {
+            coins: vec![StdCoin::new(100u128, "uatom")]
+        }
+    );
+    assert_eq!(
+        TotalSupply::reconstruct(&[total_supply("100"), total_supply("-1")])
+            .unwrap_err()
+            .to_string(),
+        "Invalid query result format: storage value 1: expected decimal amount under a denom total supply key: value is not a decimal amount"
+    );
+
+    // tally count which is not an amount
+    let proposal = StorageValue {
+        storage_prefix: "gov".to_string(),
+        key: create_gov_proposal_key(1).unwrap(),
+        value: CosmosProposal {
+            proposal_id: 1,
+            final_tally_result: Some(CosmosTallyResult {
+                abstain: "1".to_string(),
+                no: "two".to_string(),
+                no_with_veto: "3".to_string(),
+                yes: "4".to_string(),
+            }),
+            ..Default::default()
+        }
+        .encode_to_vec(),
+        proof: None,
+    };
+    assert!(GovernmentProposal::reconstruct(&[proposal])
+        .unwrap_err()
+        .to_string()
+        .starts_with(
+            "Invalid query result format: storage value 0: expected cosmos.gov.v1beta1.Proposal: invalid tally count: "
+        ));
+
+    // min self delegation which is not an amount, an empty one is zero
+    let validator = |min_self_delegation: &str| StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key: vec![],
+        value: Validator {
+            operator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+            min_self_delegation: min_self_delegation.to_string(),
+            ..Default::default()
+        }
+        .encode_to_vec(),
+        proof: None,
+    };
+    let validators = [validator(""), validator("x")];
+    assert_eq!(
+        StakingValidator::reconstruct(&validators[..1])
+            .unwrap()
+            .validators[0]
+            .min_self_delegation,
+        Decimal::zero()
+    );
+    for err in [
+        StakingValidator::reconstruct(&validators).unwrap_err(),
+        StakingValidatorRef::reconstruct_ref(&validators).unwrap_err(),
+    ] {
+        assert!(err.to_string().starts_with(
+            "Invalid query result format: storage value 1: expected cosmos.staking.v1beta1.Validator: "
+        ));
+        assert!(err.to_string().contains("invalid min self delegation"));
+    }
+}
+
+#[test]
+fn test_staking_validators_reconstruct_invalid_commission_rates() {
+    let validator = |rate: &str| StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key: vec![],
+        value: Validator {
+            operator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+            commission: Some(Commission {
+                commission_rates: Some(CommissionRates {
+                    rate: rate.to_string(),
+                    max_rate: "200000000000000000".to_string(),
+                    max_change_rate: "10000000000000000".to_string(),
+                }),
+                update_time: None,
+            }),
+            ..Default::default()
+        }
+        .encode_to_vec(),
+        proof: None,
+    };
+    let validators = [validator("100000000000000000"), validator("0.1")];
+
+    // the validators are the same for Cosmos SDK 0.47
+    for err in [
+        StakingValidator::reconstruct(&validators).unwrap_err(),
+        V047StakingValidator::reconstruct(&validators).unwrap_err(),
+        StakingValidatorRef::reconstruct_ref(&validators).unwrap_err(),
+    ] {
+        assert!(err.to_string().starts_with(
+            "Invalid query result format: storage value 1: expected cosmos.staking.v1beta1.Validator: "
+        ));
+    }
+}
+
+#[test]
+fn test_staking_validators_reconstruct_merges_repeated_messages() {
+    let first = Validator {
+        operator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+        description: Some(Description {
+            moniker: "first".to_string(),
+            identity: "identity".to_string(),
+            ..Default::default()
+        }),
+        commission: Some(Commission {
+            commission_rates: Some(CommissionRates {
+                rate: "0.1".to_string(),
+                max_rate: "200000000000000000".to_string(),
+                max_change_rate: "10000000000000000".to_string(),
+            }),
+            update_time: Some(prost_types::Timestamp {
+                seconds: 1203981203,
+                nanos: 0,
+            }),
+        }),
+        ..Default::default()
+    };
+    let second = Validator {
+        description: Some(Description {
+            moniker: "second".to_string(),
+            website: "website".to_string(),
+            ..Default::default()
+        }),
+        commission: Some(Commission {
+            commission_rates: Some(CommissionRates {
+                rate: "100000000000000000".to_string(),
+                ..Default::default()
+            }),
+            update_time: None,
+        }),
+        ..Default::default()
+    };
+    // a concatenation of two encoded messages has every embedded message repeated, the invalid
+    // rate of the first commission is overwritten by the second one
+    let storage_values = vec![StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key: vec![],
+        value: [first.encode_to_vec(), second.encode_to_vec()].concat(),
+        proof: None,
+    }];
+
+    let owned = StakingValidator::reconstruct(&storage_values).unwrap();
+    let borrowed = StakingValidatorRef::reconstruct_ref(&storage_values).unwrap();
+    assert_eq!(owned, StakingValidator::from(borrowed));
+
+    let validator = &owned.validators[0];
+    assert_eq!(validator.moniker, Some("second".to_string()));
+    assert_eq!(validator.identity, Some("identity".to_string()));
+    assert_eq!(validator.website, Some("website".to_string()));
+    assert_eq!(validator.rate, Some(LegacyDec::from_str("0.1").unwrap()));
+    assert_eq!(
+        validator.max_rate,
+        Some(LegacyDec::from_str("0.2").unwrap())
+    );
+    assert_eq!(
+        validator.max_change_rate,
+        Some(LegacyDec::from_str("0.01").unwrap())
+    );
+    assert_eq!(validator.update_time, Some(1203981203));
+}
+
+#[test]
+fn test_unbonding_delegations_reconstruct_invalid_completion_time() {
+    for (seconds, nanos) in [(-1, 0), (0, -1), (i64::MAX, 0)] {
+        let storage_values = vec![StorageValue {
+            storage_prefix: STAKING_STORE_KEY.to_string(),
+            key: vec![],
+            value: UnbondingDelegation {
+                delegator_address: "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string(),
+                validator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+                entries: vec![UnbondingDelegationEntry {
+                    creation_height: 1,
+                    completion_time: Some(prost_types::Timestamp { seconds, nanos }),
+                    initial_balance: "100".to_string(),
+                    balance: "100".to_string(),
+                }],
+            }
+            .encode_to_vec(),
+            proof: None,
+        }];
+
+        let err = UnbondingDelegations::reconstruct(&storage_values).unwrap_err();
+        assert!(err.to_string().starts_with(
+            "Invalid query result format: storage value 0: expected cosmos.staking.v1beta1.UnbondingDelegation: "
+        ));
+    }
+}
+
+#[test]
+fn test_staking_validators_reconstruct_invalid_commission_rate() {
+    let storage_values = vec![StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key: vec![],
+        value: Validator {
+            operator_address: "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+            commission: Some(Commission {
+                commission_rates: Some(CommissionRates {
+                    rate: "0.05".to_string(),
+                    max_rate: "200000000000000000".to_string(),
+                    max_change_rate: "10000000000000000".to_string(),
+                }),
+                update_time: None,
+            }),
+            ..Default::default()
+        }
+        .encode_to_vec(),
+        proof: None,
+    }];
+
+    // malformed rates are not turned into zeros
+    assert!(StakingValidator::reconstruct(&storage_values).is_err());
+    assert!(StakingValidatorRef::reconstruct_ref(&storage_values).is_err());
+}
+
+#[test]
+fn test_kv_construct_round_trip() {
+    let delegator = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs";
+    let validator = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3";
+
+    let balances = Balances {
+        coins: vec![
+            StdCoin::new(100u128, "uosmo"),
+            // zero balance is constructed as an absent value
+            StdCoin::new(0u128, "uatom"),
+        ],
+    };
+    let storage_values = balances.construct(delegator).unwrap();
+    assert!(storage_values[1].value.is_empty());
+    assert_eq!(
+        storage_values
+            .iter()
+            .map(|kv| (kv.storage_prefix.clone(), kv.key.clone()))
+            .collect::<Vec<_>>(),
+        create_balances_query_keys(
+            delegator.to_string(),
+            vec!["uosmo".to_string(), "uatom".to_string()]
+        )
+        .unwrap()
+        .into_iter()
+        .map(|k| (k.path, k.key))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(Balances::reconstruct(&storage_values).unwrap(), balances);
+
+    let total_supply = TotalSupply {
+        coins: vec![StdCoin::new(300001098u128, "stake")],
+    };
+    assert_eq!(
+        TotalSupply::reconstruct(&total_supply.construct(&()).unwrap()).unwrap(),
+        total_supply
+    );
+
+    let fee_pool = FeePool {
+        coins: vec![StdCoin::new(21960u128, "stake")],
+    };
+    assert_eq!(
+        FeePool::reconstruct(&fee_pool.construct(&()).unwrap()).unwrap(),
+        fee_pool
+    );
+
+    let staking_validator = StakingValidator {
+        validators: vec![ContractValidator {
+            operator_address: validator.to_string(),
+            jailed: false,
+            status: 3,
+            tokens: "1000000".to_string(),
+            delegator_shares: "1000000000000000000000000".to_string(),
+            consensus_pubkey: Some(vec![1, 2, 3]),
+            moniker: Some("moniker".to_string()),
+            identity: Some("identity".to_string()),
+            website: Some("".to_string()),
+            security_contact: Some("".to_string()),
+            details: Some("details".to_string()),
+            unbonding_height: 10,
+            unbonding_time: Some(1203981203),
+            rate: Some(LegacyDec::from_str("0.05").unwrap()),
+            max_rate: Some(LegacyDec::from_str("0.2").unwrap()),
+            max_change_rate: Some(LegacyDec::from_str("0.01").unwrap()),
+            update_time: Some(56324234),
+            min_self_delegation: Decimal::one(),
+        }],
+    };
+    assert_eq!(
+        StakingValidator::reconstruct(&staking_validator.construct(&()).unwrap()).unwrap(),
+        staking_validator
+    );
+
+    let signing_info = SigningInfo {
+        signing_infos: vec![ValidatorSigningInfo {
+            address: "cosmosvalcons1yjf46k064988jdjje068zmrqg8xh4fqqe2wwnl".to_string(),
+            start_height: 1,
+            index_offset: 2,
+            jailed_until: Some(1203981203),
+            tombstoned: true,
+            missed_blocks_counter: 3,
+        }],
+    };
+    assert_eq!(
+        SigningInfo::reconstruct(&signing_info.construct(&()).unwrap()).unwrap(),
+        signing_info
+    );
+
+    let proposal = GovernmentProposal {
+        proposals: vec![Proposal {
+            proposal_id: 1,
+            proposal_type: Some("/cosmos.gov.v1beta1.TextProposal".to_string()),
+            total_deposit: vec![StdCoin::new(1000u128, "stake")],
+            status: 3,
+            submit_time: Some(1677741257),
+            deposit_end_time: Some(1677741257),
+            voting_start_time: Some(1677741257),
+            voting_end_time: Some(1677741257),
+            final_tally_result: Some(TallyResult {
+                yes: Uint128::new(4),
+                no: Uint128::new(3),
+                abstain: Uint128::new(2),
+                no_with_veto: Uint128::new(1),
+            }),
+        }],
+    };
+    assert_eq!(
+        GovernmentProposal::reconstruct(&proposal.construct(&()).unwrap()).unwrap(),
+        proposal
+    );
+
+    let votes = GovernmentProposalVotes {
+        proposal_votes: vec![ProposalVote {
+            proposal_id: 1,
+            voter: delegator.to_string(),
+            options: vec![WeightedVoteOption {
+                option: 1,
+                weight: "1000000000000000000".to_string(),
+            }],
+        }],
+    };
+    assert_eq!(
+        GovernmentProposalVotes::reconstruct(&votes.construct(&()).unwrap()).unwrap(),
+        votes
+    );
+
+    let delegations = Delegations {
+        delegations: vec![StdDelegation {
+            delegator: Addr::unchecked(delegator),
+            validator: validator.to_string(),
+            amount: StdCoin::new(340282366920938463463u128, "stake"),
+        }],
+    };
+    let storage_values = delegations
+        .construct(&delegations_context("stake", &[validator]))
+        .unwrap();
+    assert_eq!(
+        storage_values
+            .iter()
+            .map(|kv| (kv.storage_prefix.clone(), kv.key.clone()))
+            .collect::<Vec<_>>(),
+        create_delegations_query_keys(delegator.to_string(), vec![validator.to_string()])
+            .unwrap()
+            .into_iter()
+            .map(|k| (k.path, k.key))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Delegations::reconstruct(&storage_values).unwrap(),
+        delegations
+    );
+    // validators are stored as they are given, shares are converted from tokens
+    let context = delegations_context("stake", &[validator]);
+    assert_eq!(
+        Validator::decode(storage_values[2].value.as_slice()).unwrap(),
+        context.validators[0]
+    );
+    assert_eq!(
+        Delegation::decode(storage_values[1].value.as_slice())
+            .unwrap()
+            .shares,
+        "510423550381407695194500000000000000000"
+    );
+    assert!(matches!(
+        delegations.construct(&delegations_context("stake", &[])),
+        Err(NeutronError::InvalidKVConstructContext(_))
+    ));
+
+    let unbonding_delegations = UnbondingDelegations {
+        unbonding_responses: vec![UnbondingResponse {
+            delegator_address: Addr::unchecked(delegator),
+            validator_address: validator.to_string(),
+            entries: vec![UnbondingEntry {
+                balance: Uint128::new(10_000),
+                completion_time: Some(Timestamp::from_nanos(1704872890570185206)),
+                creation_height: 365,
+                initial_balance: Uint128::new(20_000),
+            }],
+        }],
+    };
+    assert_eq!(
+        UnbondingDelegations::reconstruct(&unbonding_delegations.construct(&()).unwrap()).unwrap(),
+        unbonding_delegations
+    );
+
+    let ica_host_params = IcaHostParams {
+        host_enabled: true,
+        allow_messages: vec!["/cosmos.staking.v1beta1.MsgDelegate".to_string()],
+    };
+    assert_eq!(
+        IcaHostParams::reconstruct(&ica_host_params.construct(&()).unwrap()).unwrap(),
+        ica_host_params
+    );
+}
+
+#[test]
+fn test_delegations_reconstruct_rounding() {
+    let storage_value = |value: Vec<u8>| StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key: vec![],
+        value,
+        proof: None,
+    };
+    let delegator = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs";
+    let validator = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3";
+
+    // shares * tokens / delegator_shares is 0.9999999999999999995 which is rounded
+    // to 1.000000000000000000 by Quo before TruncateInt, just like the remote chain does
+    let delegations = Delegations::reconstruct(&[
+        storage_value(to_json_binary("stake").unwrap().to_vec()),
+        storage_value(
+            Delegation {
+                delegator_address: delegator.to_string(),
+                validator_address: validator.to_string(),
+                shares: "1000000000000000000".to_string(),
+            }
+            .encode_to_vec(),
+        ),
+        storage_value(
+            Validator {
+                operator_address: validator.to_string(),
+                tokens: "2".to_string(),
+                delegator_shares: "2000000000000000001".to_string(),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        ),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        delegations.delegations,
+        vec![StdDelegation {
+            delegator: Addr::unchecked(delegator),
+            validator: validator.to_string(),
+            amount: StdCoin::new(1u128, "stake"),
+        }]
+    );
+}
+
+#[test]
+fn test_reconstruct_ref() {
+    let delegator = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs";
+    let validator = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3";
+
+    let balances = Balances {
+        coins: vec![StdCoin::new(100u128, "uosmo"), StdCoin::new(0u128, "uatom")],
+    };
+    let storage_values = balances.construct(delegator).unwrap();
+    let balances_ref = BalancesRef::reconstruct_ref(&storage_values).unwrap();
+    // denoms are borrowed right from the keys
+    assert_eq!(
+        balances_ref.coins[1].denom.as_ptr(),
+        storage_values[1].key[storage_values[1].key.len() - 5..].as_ptr()
+    );
+    assert_eq!(Balances::from(balances_ref), balances);
+
+    let mut storage_values = StakingValidator {
+        validators: vec![ContractValidator {
+            operator_address: validator.to_string(),
+            jailed: true,
+            status: 3,
+            tokens: "1000000".to_string(),
+            delegator_shares: "1000000000000000000000000".to_string(),
+            consensus_pubkey: Some(vec![1, 2, 3]),
+            moniker: Some("moniker".to_string()),
+            identity: Some("identity".to_string()),
+            website: Some("".to_string()),
+            security_contact: Some("".to_string()),
+            details: Some("details".to_string()),
+            unbonding_height: 10,
+            unbonding_time: Some(1203981203),
+            rate: Some(LegacyDec::from_str("0.05").unwrap()),
+            max_rate: Some(LegacyDec::from_str("0.2").unwrap()),
+            max_change_rate: Some(LegacyDec::from_str("0.01").unwrap()),
+            update_time: Some(56324234),
+            min_self_delegation: Decimal::one(),
+        }],
+    }
+    .construct(&())
+    .unwrap();
+    // a validator without description and commission
+    storage_values.push(StorageValue {
+        storage_prefix: STAKING_STORE_KEY.to_string(),
+        key: vec![],
+        value: Validator {
+            operator_address: validator.to_string(),
+            tokens: "1".to_string(),
+            ..Default::default()
+        }
+        .encode_to_vec(),
+        proof: None,
+    });
+    assert_eq!(
+        StakingValidator::from(StakingValidatorRef::reconstruct_ref(&storage_values).unwrap()),
+        StakingValidator::reconstruct(&storage_values).unwrap()
+    );
+
+    let delegations = Delegations {
+        delegations: vec![StdDelegation {
+            delegator: Addr::unchecked(delegator),
+            validator: validator.to_string(),
+            amount: StdCoin::new(1000u128, "stake"),
+        }],
+    };
+    let mut storage_values = delegations
+        .construct(&delegations_context("stake", &[validator]))
+        .unwrap();
+    let delegations_ref = DelegationsRef::reconstruct_ref(&storage_values).unwrap();
+    assert!(matches!(delegations_ref.denom, Cow::Borrowed("stake")));
+    assert_eq!(Delegations::from(delegations_ref), delegations);
+
+    // denom with escape sequences can't be borrowed
+    storage_values[0].value = br#""\u0073take""#.to_vec();
+    let delegations_ref = DelegationsRef::reconstruct_ref(&storage_values).unwrap();
+    assert!(matches!(delegations_ref.denom, Cow::Owned(ref denom) if denom == "stake"));
+    assert_eq!(Delegations::from(delegations_ref), delegations);
+
+    // errors are reported the same way as by the owned reconstructor
+    storage_values[2].value = vec![0x0a, 0x05, b'o'];
+    assert_eq!(
+        DelegationsRef::reconstruct_ref(&storage_values)
+            .unwrap_err()
+            .to_string(),
+        "Invalid query result format: storage value 2: expected cosmos.staking.v1beta1.Validator: Invalid protobuf wire format: buffer underflow"
+    );
+    assert_eq!(
+        DelegationsRef::reconstruct_ref(&storage_values[..2])
+            .unwrap_err()
+            .to_string(),
+        Delegations::reconstruct(&storage_values[..2])
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn test_update_query_keys() {
+    let account = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs";
+    let validators = [
+        "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+        "osmovaloper1lzhlnpahvznwfv4jmay2tgaha5kmz5qxwmj9we".to_string(),
+    ];
+    let denoms =
+        |denoms: &[&str]| -> Vec<String> { denoms.iter().map(|d| d.to_string()).collect() };
+
+    let keys =
+        create_balances_query_keys(account.to_string(), denoms(&["uosmo", "uatom"])).unwrap();
+    assert_eq!(
+        add_balances_query_denoms(
+            keys.clone(),
+            account.to_string(),
+            denoms(&["uatom", "uusdc", "uusdc"])
+        )
+        .unwrap(),
+        create_balances_query_keys(account.to_string(), denoms(&["uosmo", "uatom", "uusdc"]))
+            .unwrap()
+    );
+    // balances of another account can't be added to the query
+    assert!(matches!(
+        add_balances_query_denoms(
+            keys,
+            "osmo1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny0l6fwk".to_string(),
+            denoms(&["uusdc"])
+        ),
+        Err(NeutronError::IncompatibleQueryKeys(_))
+    ));
+
+    let keys =
+        create_delegations_query_keys(account.to_string(), vec![validators[0].clone()]).unwrap();
+    let updated = update_delegations_query_keys(
+        keys.clone(),
+        account.to_string(),
+        vec![validators[1].clone(), validators[0].clone()],
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(
+        updated,
+        create_delegations_query_keys(account.to_string(), validators.to_vec()).unwrap()
+    );
+    assert_eq!(
+        update_delegations_query_keys(
+            updated,
+            account.to_string(),
+            vec![],
+            vec![validators[0].clone()]
+        )
+        .unwrap(),
+        create_delegations_query_keys(account.to_string(), vec![validators[1].clone()]).unwrap()
+    );
+    // staking denom key is kept even if there are no validators left
+    assert_eq!(
+        update_delegations_query_keys(
+            keys.clone(),
+            account.to_string(),
+            vec![],
+            vec![validators[0].clone()]
+        )
+        .unwrap(),
+        create_delegations_query_keys(account.to_string(), vec![]).unwrap()
+    );
+    // a query without staking denom key is not a delegations query
+    assert!(matches!(
+        update_delegations_query_keys(
+            keys[1..].to_vec(),
+            account.to_string(),
+            vec![validators[1].clone()],
+            vec![]
+        ),
+        Err(NeutronError::IncompatibleQueryKeys(_))
+    ));
+
+    let transactions_filter = vec![
+        TransactionFilterItem {
+            field: RECIPIENT_FIELD.to_string(),
+            op: TransactionFilterOp::Eq,
+            value: TransactionFilterValue::String(
+                "osmo1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny0l6fwk".to_string(),
+            ),
+        },
+        TransactionFilterItem {
+            field: HEIGHT_FIELD.to_string(),
+            op: TransactionFilterOp::Gte,
+            value: TransactionFilterValue::Int(100),
+        },
+    ];
+    assert_eq!(
+        serde_json_wasm::to_string(&set_transfers_query_recipient(
+            transactions_filter,
+            account.to_string()
+        ))
+        .unwrap(),
+        r#"[{"field":"transfer.recipient","op":"Eq","value":"osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs"},{"field":"tx.height","op":"Gte","value":100}]"#
+    );
+}
@@ -3,22 +3,57 @@ use super::helpers::{
     get_update_time,
 };
 use crate::errors::error::{NeutronError, NeutronResult};
-use crate::interchain_queries::types::KVReconstruct;
-use crate::interchain_queries::v045::helpers::deconstruct_account_denom_balance_key;
+use crate::interchain_queries::address::{decode_address_of_kind, AddressKind};
+use crate::interchain_queries::legacy_dec::LegacyDec;
+use crate::interchain_queries::proto_reader::ProtoReader;
+use crate::interchain_queries::types::{
+    invalid_storage_value, presence_status, reconstruct_each_with_status, KVConstruct, KVKeysCount,
+    KVReconstruct, KVReconstructRef, KVReconstructWithStatus, KVStatus, KeyStatus,
+};
+use crate::interchain_queries::v045::helpers::{
+    create_account_denom_balance_key, create_delegation_key, create_fee_pool_key,
+    create_gov_proposal_key, create_gov_proposal_voter_votes_key, create_params_store_key,
+    create_total_denom_key, create_unbonding_delegation_key, create_validator_key,
+    create_validator_signing_info_key, deconstruct_account_denom_balance_key_ref,
+    deconstruct_params_store_key,
+};
 use cosmos_sdk_proto::cosmos::gov::v1beta1::Vote;
 use cosmos_sdk_proto::cosmos::{
-    base::v1beta1::Coin as CosmosCoin,
+    base::v1beta1::{Coin as CosmosCoin, DecCoin},
     distribution::v1beta1::FeePool as CosmosFeePool,
-    gov::v1beta1::Proposal as CosmosProposal,
+    gov::v1beta1::{
+        Proposal as CosmosProposal, TallyResult as CosmosTallyResult,
+        WeightedVoteOption as CosmosWeightedVoteOption,
+    },
     slashing::v1beta1::ValidatorSigningInfo as CosmosValidatorSigningInfo,
-    staking::v1beta1::{Delegation, UnbondingDelegation, Validator as CosmosValidator},
+    staking::v1beta1::{
+        Commission, CommissionRates, Delegation, Description, UnbondingDelegation,
+        UnbondingDelegationEntry, Validator as CosmosValidator,
+    },
 };
 use cosmos_sdk_proto::traits::Message;
-use cosmwasm_std::{from_json, Addr, Coin, Decimal, Decimal256, Timestamp, Uint128, Uint256};
+use cosmwasm_std::{
+    from_json, to_json_vec, Addr, Coin, Decimal, StdError, Timestamp, Uint128, Uint256, Uint64,
+};
+use neutron_std::shim::Any;
+use neutron_std::types::ibc::applications::interchain_accounts::host::v1::Params as IcaHostModuleParams;
 use neutron_std::types::neutron::interchainqueries::StorageValue;
 use schemars::JsonSchema;
+use serde::de::{DeserializeOwned, IgnoredAny};
 use serde::{Deserialize, Serialize};
-use std::{ops::Div, str::FromStr};
+use std::borrow::Cow;
+use std::str::{from_utf8, FromStr};
+
+/// Shapes of **StorageValue**'s expected by reconstructors, used in errors about malformed values
+const BALANCE_SHAPE: &str = "cosmos.base.v1beta1.Coin under an account denom balance key";
+const FEE_POOL_SHAPE: &str = "cosmos.distribution.v1beta1.FeePool";
+const VALIDATOR_SHAPE: &str = "cosmos.staking.v1beta1.Validator";
+const SIGNING_INFO_SHAPE: &str = "cosmos.slashing.v1beta1.ValidatorSigningInfo";
+const PROPOSAL_SHAPE: &str = "cosmos.gov.v1beta1.Proposal";
+const VOTE_SHAPE: &str = "cosmos.gov.v1beta1.Vote";
+const DELEGATION_SHAPE: &str = "cosmos.staking.v1beta1.Delegation";
+const UNBONDING_DELEGATION_SHAPE: &str = "cosmos.staking.v1beta1.UnbondingDelegation";
+const TOTAL_SUPPLY_SHAPE: &str = "decimal amount under a denom total supply key";
 
 pub const DECIMAL_PLACES: u32 = 18;
 pub const DECIMAL_FRACTIONAL: u128 = 10u128.pow(DECIMAL_PLACES);
@@ -94,6 +129,24 @@ pub const PARAMS_STORE_DELIMITER: &str = "/";
 /// Name of the **wasm** Cosmos module
 pub const WASM_STORE_KEY: &str = "wasm";
 
+/// Name of the IBC **icahost** module
+pub const ICAHOST_STORE_KEY: &str = "icahost";
+
+/// Key for host enabled param of IBC icahost module
+/// <https://github.com/cosmos/ibc-go/blob/v7.3.1/modules/apps/27-interchain-accounts/host/types/params.go>
+pub const KEY_HOST_ENABLED: &str = "HostEnabled";
+
+/// Key for allow messages param of IBC icahost module
+/// <https://github.com/cosmos/ibc-go/blob/v7.3.1/modules/apps/27-interchain-accounts/host/types/params.go>
+pub const KEY_ALLOW_MESSAGES: &str = "AllowMessages";
+
+/// Key for params in the **icahost** module's storage, used since ibc-go v8 instead of legacy **params** subspace
+/// <https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/apps/27-interchain-accounts/host/types/keys.go>
+pub const ICAHOST_PARAMS_KEY: &str = "params";
+
+/// Wildcard which allows all messages to be executed by ICA host
+pub const ICAHOST_ALLOW_ALL_MESSAGES: &str = "*";
+
 pub const RECIPIENT_FIELD: &str = "transfer.recipient";
 pub const HEIGHT_FIELD: &str = "tx.height";
 
@@ -104,26 +157,150 @@ pub struct Balances {
     pub coins: Vec<Coin>,
 }
 
+/// Reconstructs balance of a single denom, an empty value means zero balance
+fn reconstruct_balance(kv: &StorageValue) -> NeutronResult<Coin> {
+    let (_, denom) = deconstruct_account_denom_balance_key_ref(&kv.key)?;
+    let amount = if kv.value.is_empty() {
+        0u128
+    } else {
+        let balance: CosmosCoin = CosmosCoin::decode(kv.value.as_slice())?;
+        Uint128::from_str(balance.amount.as_str())?.u128()
+    };
+
+    Ok(Coin::new(amount, denom))
+}
+
 impl KVReconstruct for Balances {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<Balances> {
         let mut coins: Vec<Coin> = Vec::with_capacity(storage_values.len());
 
-        for kv in storage_values {
-            let (_, denom) = deconstruct_account_denom_balance_key(kv.key.to_vec())?;
-            let amount = if kv.value.is_empty() {
-                0u128
-            } else {
-                let balance: CosmosCoin = CosmosCoin::decode(kv.value.as_slice())?;
-                Uint128::from_str(balance.amount.as_str())?.u128()
-            };
-
-            coins.push(Coin::new(amount, denom))
+        for (i, kv) in storage_values.iter().enumerate() {
+            coins.push(
+                reconstruct_balance(kv).map_err(|e| invalid_storage_value(i, BALANCE_SHAPE, e))?,
+            )
         }
 
         Ok(Balances { coins })
     }
 }
 
+impl KVConstruct for Balances {
+    /// Bech32 address of the account which holds the balances
+    type Context = str;
+
+    fn construct(&self, address: &str) -> NeutronResult<Vec<StorageValue>> {
+        let address = decode_address_of_kind(address, AddressKind::Account)?;
+
+        self.coins
+            .iter()
+            .map(|coin| {
+                // zero balances are not stored on chain
+                let value = if coin.amount.is_zero() {
+                    vec![]
+                } else {
+                    CosmosCoin {
+                        denom: coin.denom.clone(),
+                        amount: coin.amount.to_string(),
+                    }
+                    .encode_to_vec()
+                };
+
+                Ok(new_storage_value(
+                    BANK_STORE_KEY,
+                    create_account_denom_balance_key(&address, &coin.denom)?,
+                    value,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl KVReconstructWithStatus for Balances {
+    fn reconstruct_with_status(
+        storage_values: &[StorageValue],
+    ) -> NeutronResult<(Balances, Vec<KeyStatus>)> {
+        let (coins, statuses) = reconstruct_each_with_status(storage_values, reconstruct_balance);
+
+        Ok((Balances { coins }, statuses))
+    }
+}
+
+/// A coin which borrows its denom from a **StorageValue**
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoinRef<'a> {
+    pub denom: &'a str,
+    pub amount: Uint256,
+}
+
+impl From<CoinRef<'_>> for Coin {
+    fn from(coin: CoinRef) -> Coin {
+        Coin::new(coin.amount, coin.denom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A zero-copy counterpart of `Balances` for the **Balance Interchain Query**.
+/// Denoms are borrowed from storage keys.
+pub struct BalancesRef<'a> {
+    pub coins: Vec<CoinRef<'a>>,
+}
+
+impl From<BalancesRef<'_>> for Balances {
+    fn from(balances: BalancesRef) -> Balances {
+        Balances {
+            coins: balances.coins.into_iter().map(Coin::from).collect(),
+        }
+    }
+}
+
+/// Reconstructs balance of a single denom without copying, an empty value means zero balance
+fn reconstruct_balance_ref(kv: &StorageValue) -> NeutronResult<CoinRef<'_>> {
+    let (_, denom) = deconstruct_account_denom_balance_key_ref(&kv.key)?;
+    let amount = if kv.value.is_empty() {
+        Uint128::zero()
+    } else {
+        Uint128::from_str(read_coin_amount(&kv.value)?)?
+    };
+
+    Ok(CoinRef {
+        denom,
+        amount: amount.into(),
+    })
+}
+
+/// Reads amount of `cosmos.base.v1beta1.Coin`, the denom is only checked to be a valid string
+fn read_coin_amount(value: &[u8]) -> NeutronResult<&str> {
+    let mut amount = "";
+
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        match number {
+            1 => {
+                field.str()?;
+            }
+            2 => amount = field.str()?,
+            _ => {}
+        }
+    }
+
+    Ok(amount)
+}
+
+impl<'a> KVReconstructRef<'a> for BalancesRef<'a> {
+    fn reconstruct_ref(storage_values: &'a [StorageValue]) -> NeutronResult<BalancesRef<'a>> {
+        let mut coins: Vec<CoinRef> = Vec::with_capacity(storage_values.len());
+
+        for (i, kv) in storage_values.iter().enumerate() {
+            coins.push(
+                reconstruct_balance_ref(kv)
+                    .map_err(|e| invalid_storage_value(i, BALANCE_SHAPE, e))?,
+            )
+        }
+
+        Ok(BalancesRef { coins })
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 /// A structure that can be reconstructed from **StorageValues**'s for the **Bank Total Interchain Query**.
 /// Contains total supply for specific denom that are held on remote chain.
@@ -135,17 +312,40 @@ impl KVReconstruct for TotalSupply {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<TotalSupply> {
         let mut coins: Vec<Coin> = Vec::with_capacity(storage_values.len());
 
-        for kv in storage_values {
-            let denom = get_total_supply_denom(&kv.key);
-            let amount = get_total_supply_amount(&kv.value);
-            if let (Some(denom), Some(amount)) = (denom, amount) {
-                coins.push(Coin::new(amount.u128(), denom));
+        for (i, kv) in storage_values.iter().enumerate() {
+            // supply of a denom is absent on remote chain if no one holds the denom
+            if kv.value.is_empty() {
+                continue;
             }
+            let denom = get_total_supply_denom(&kv.key).ok_or_else(|| {
+                invalid_storage_value(i, TOTAL_SUPPLY_SHAPE, "key is not a total supply key")
+            })?;
+            let amount = get_total_supply_amount(&kv.value).ok_or_else(|| {
+                invalid_storage_value(i, TOTAL_SUPPLY_SHAPE, "value is not a decimal amount")
+            })?;
+            coins.push(Coin::new(amount.u128(), denom));
         }
         Ok(TotalSupply { coins })
     }
 }
 
+impl KVConstruct for TotalSupply {
+    type Context = ();
+
+    fn construct(&self, _: &()) -> NeutronResult<Vec<StorageValue>> {
+        self.coins
+            .iter()
+            .map(|coin| {
+                Ok(new_storage_value(
+                    BANK_STORE_KEY,
+                    create_total_denom_key(&coin.denom)?,
+                    coin.amount.to_string().into_bytes(),
+                ))
+            })
+            .collect()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 /// A structure that can be reconstructed from **StorageValues**'s for the **Fee Pool Interchain Query**.
 /// Contains coins that are held by some account on remote chain.
@@ -157,22 +357,18 @@ impl KVReconstruct for FeePool {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<FeePool> {
         let mut coins: Vec<Coin> = Vec::with_capacity(storage_values.len());
 
-        for kv in storage_values {
-            let cosmos_pool: CosmosFeePool = CosmosFeePool::decode(kv.value.as_slice())?;
+        for (i, kv) in storage_values.iter().enumerate() {
+            let cosmos_pool: CosmosFeePool = CosmosFeePool::decode(kv.value.as_slice())
+                .map_err(|e| invalid_storage_value(i, FEE_POOL_SHAPE, e))?;
 
             for pool_coin in cosmos_pool.community_pool {
                 // amount is stored as Dec which is gogo proto encoded to string without a decimal point.
                 // e.g. Dec(1) is 1 + 18 zeros
                 // https://github.com/cosmos/cosmos-sdk/blob/9c145c827001222df2e3e1101010874aeac20997/types/decimal_test.go#L498
-                let amount: Uint128 = Uint128::from_str(pool_coin.amount.as_str())
-                    .unwrap()
-                    .checked_div_floor(
-                        Decimal::one()
-                            .checked_mul(Decimal::from_ratio(10u64, 1u64).pow(DECIMAL_PLACES))
-                            .unwrap(),
-                    )
-                    .unwrap();
-                coins.push(Coin::new(amount.u128(), pool_coin.denom));
+                let amount = LegacyDec::from_atomics_str(&pool_coin.amount)
+                    .map_err(|e| invalid_storage_value(i, FEE_POOL_SHAPE, e))?
+                    .truncate_int();
+                coins.push(Coin::new(amount, pool_coin.denom));
             }
         }
 
@@ -180,6 +376,30 @@ impl KVReconstruct for FeePool {
     }
 }
 
+impl KVConstruct for FeePool {
+    type Context = ();
+
+    fn construct(&self, _: &()) -> NeutronResult<Vec<StorageValue>> {
+        let mut community_pool: Vec<DecCoin> = Vec::with_capacity(self.coins.len());
+        for coin in &self.coins {
+            community_pool.push(DecCoin {
+                denom: coin.denom.clone(),
+                amount: LegacyDec::from_int(coin.amount)?.to_atomics_string(),
+            });
+        }
+
+        Ok(vec![new_storage_value(
+            DISTRIBUTION_STORE_KEY,
+            create_fee_pool_key()?,
+            CosmosFeePool { community_pool }.encode_to_vec(),
+        )])
+    }
+}
+
+impl KVKeysCount for FeePool {
+    const KEYS_COUNT: usize = 1;
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 /// Validator structure for the querier. Contains validator from staking module
 pub struct Validator {
@@ -209,11 +429,11 @@ pub struct Validator {
     /// unbonding_time defines, if unbonding, the min time for the validator to complete unbonding.
     pub unbonding_time: Option<u64>,
     /// rate is the commission rate charged to delegators, as a fraction.
-    pub rate: Option<Decimal>,
+    pub rate: Option<LegacyDec>,
     /// max_rate defines the maximum commission rate which validator can ever charge, as a fraction.
-    pub max_rate: Option<Decimal>,
+    pub max_rate: Option<LegacyDec>,
     /// max_change_rate defines the maximum daily increase of the validator commission, as a fraction.
-    pub max_change_rate: Option<Decimal>,
+    pub max_change_rate: Option<LegacyDec>,
     /// update_time is the last time the commission rate was changed.
     pub update_time: Option<u64>,
     /// min_self_delegation is the validator's self declared minimum self delegation.
@@ -231,8 +451,9 @@ impl KVReconstruct for StakingValidator {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<StakingValidator> {
         let mut validators = Vec::with_capacity(storage_values.len());
 
-        for kv in storage_values {
-            let validator: CosmosValidator = CosmosValidator::decode(kv.value.as_slice())?;
+        for (i, kv) in storage_values.iter().enumerate() {
+            let validator: CosmosValidator = CosmosValidator::decode(kv.value.as_slice())
+                .map_err(|e| invalid_storage_value(i, VALIDATOR_SHAPE, e))?;
             let description = &validator.description;
             let commission = &validator.commission;
             let consensus_pubkey = &validator.consensus_pubkey;
@@ -251,12 +472,15 @@ impl KVReconstruct for StakingValidator {
                 website: description.as_ref().map(|v| v.website.to_string()),
                 security_contact: description.as_ref().map(|v| v.security_contact.to_string()),
                 details: description.as_ref().map(|v| v.details.to_string()),
-                max_change_rate: get_max_change_rate(commission),
-                max_rate: get_max_rate(commission),
-                rate: get_rate(commission),
+                max_change_rate: get_max_change_rate(commission)
+                    .map_err(|e| invalid_storage_value(i, VALIDATOR_SHAPE, e))?,
+                max_rate: get_max_rate(commission)
+                    .map_err(|e| invalid_storage_value(i, VALIDATOR_SHAPE, e))?,
+                rate: get_rate(commission)
+                    .map_err(|e| invalid_storage_value(i, VALIDATOR_SHAPE, e))?,
                 update_time: get_update_time(commission),
-                min_self_delegation: Decimal::from_str(validator.min_self_delegation.as_str())
-                    .unwrap_or_default(),
+                min_self_delegation: parse_min_self_delegation(&validator.min_self_delegation)
+                    .map_err(|e| invalid_storage_value(i, VALIDATOR_SHAPE, e))?,
             };
 
             validators.push(validator)
@@ -266,6 +490,323 @@ impl KVReconstruct for StakingValidator {
     }
 }
 
+impl KVConstruct for StakingValidator {
+    type Context = ();
+
+    fn construct(&self, _: &()) -> NeutronResult<Vec<StorageValue>> {
+        self.validators
+            .iter()
+            .map(|validator| {
+                Ok(new_storage_value(
+                    STAKING_STORE_KEY,
+                    create_validator_key(decode_address_of_kind(
+                        &validator.operator_address,
+                        AddressKind::ValidatorOperator,
+                    )?)?,
+                    cosmos_validator(validator).encode_to_vec(),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// A zero-copy counterpart of `Validator`, strings and bytes are borrowed from a **StorageValue**
+pub struct ValidatorRef<'a> {
+    pub operator_address: &'a str,
+    pub jailed: bool,
+    pub status: i32,
+    pub tokens: &'a str,
+    pub delegator_shares: &'a str,
+    pub consensus_pubkey: Option<&'a [u8]>,
+    pub moniker: Option<&'a str>,
+    pub identity: Option<&'a str>,
+    pub website: Option<&'a str>,
+    pub security_contact: Option<&'a str>,
+    pub details: Option<&'a str>,
+    pub unbonding_height: u64,
+    pub unbonding_time: Option<u64>,
+    pub rate: Option<LegacyDec>,
+    pub max_rate: Option<LegacyDec>,
+    pub max_change_rate: Option<LegacyDec>,
+    pub update_time: Option<u64>,
+    pub min_self_delegation: Decimal,
+}
+
+impl From<ValidatorRef<'_>> for Validator {
+    fn from(validator: ValidatorRef) -> Validator {
+        Validator {
+            operator_address: validator.operator_address.to_string(),
+            jailed: validator.jailed,
+            status: validator.status,
+            tokens: validator.tokens.to_string(),
+            delegator_shares: validator.delegator_shares.to_string(),
+            consensus_pubkey: validator.consensus_pubkey.map(<[u8]>::to_vec),
+            moniker: validator.moniker.map(str::to_string),
+            identity: validator.identity.map(str::to_string),
+            website: validator.website.map(str::to_string),
+            security_contact: validator.security_contact.map(str::to_string),
+            details: validator.details.map(str::to_string),
+            unbonding_height: validator.unbonding_height,
+            unbonding_time: validator.unbonding_time,
+            rate: validator.rate,
+            max_rate: validator.max_rate,
+            max_change_rate: validator.max_change_rate,
+            update_time: validator.update_time,
+            min_self_delegation: validator.min_self_delegation,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A zero-copy counterpart of `StakingValidator` for the **Staking Validator Interchain Query**.
+pub struct StakingValidatorRef<'a> {
+    pub validators: Vec<ValidatorRef<'a>>,
+}
+
+impl From<StakingValidatorRef<'_>> for StakingValidator {
+    fn from(validators: StakingValidatorRef) -> StakingValidator {
+        StakingValidator {
+            validators: validators
+                .validators
+                .into_iter()
+                .map(Validator::from)
+                .collect(),
+        }
+    }
+}
+
+impl<'a> KVReconstructRef<'a> for StakingValidatorRef<'a> {
+    fn reconstruct_ref(
+        storage_values: &'a [StorageValue],
+    ) -> NeutronResult<StakingValidatorRef<'a>> {
+        let mut validators: Vec<ValidatorRef> = Vec::with_capacity(storage_values.len());
+
+        for (i, kv) in storage_values.iter().enumerate() {
+            validators.push(
+                read_validator(&kv.value)
+                    .map_err(|e| invalid_storage_value(i, VALIDATOR_SHAPE, e))?,
+            );
+        }
+
+        Ok(StakingValidatorRef { validators })
+    }
+}
+
+/// Reads `cosmos.staking.v1beta1.Validator` the same way `StakingValidator` reconstructs it.
+/// Like prost does, an embedded message which occurs several times is merged with its previous
+/// occurrences, so the commission rates are parsed only after the whole validator is read.
+fn read_validator(value: &[u8]) -> NeutronResult<ValidatorRef<'_>> {
+    let mut validator = ValidatorRef::default();
+    let mut min_self_delegation = "";
+    let mut commission_rates: Option<[&str; 3]> = None;
+
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        match number {
+            1 => validator.operator_address = field.str()?,
+            2 => {
+                let previous = validator.consensus_pubkey.unwrap_or_default();
+                validator.consensus_pubkey = Some(read_any_value(field.bytes()?, previous)?)
+            }
+            3 => validator.jailed = field.varint()? != 0,
+            // protobuf enums are int32, so the value is truncated like prost does
+            4 => validator.status = field.varint()? as i32,
+            5 => validator.tokens = field.str()?,
+            6 => validator.delegator_shares = field.str()?,
+            7 => read_description(field.bytes()?, &mut validator)?,
+            8 => validator.unbonding_height = field.varint()?,
+            9 => {
+                let previous = validator.unbonding_time.unwrap_or_default();
+                validator.unbonding_time = Some(read_timestamp_seconds(field.bytes()?, previous)?)
+            }
+            10 => read_commission(field.bytes()?, &mut validator, &mut commission_rates)?,
+            11 => min_self_delegation = field.str()?,
+            _ => {}
+        }
+    }
+    if let Some([rate, max_rate, max_change_rate]) = commission_rates {
+        validator.rate = Some(LegacyDec::from_atomics_str(rate)?);
+        validator.max_rate = Some(LegacyDec::from_atomics_str(max_rate)?);
+        validator.max_change_rate = Some(LegacyDec::from_atomics_str(max_change_rate)?);
+    }
+    validator.min_self_delegation = parse_min_self_delegation(min_self_delegation)?;
+
+    Ok(validator)
+}
+
+/// Parses `min_self_delegation` of a validator, an empty value is the proto3 default of zero
+fn parse_min_self_delegation(value: &str) -> NeutronResult<Decimal> {
+    if value.is_empty() {
+        return Ok(Decimal::zero());
+    }
+
+    Decimal::from_str(value).map_err(|e| {
+        NeutronError::InvalidQueryResultFormat(format!("invalid min self delegation: {}", e))
+    })
+}
+
+/// Reads value of `google.protobuf.Any` merging it into **any_value** of the previous
+/// occurrences, the type url is only checked to be a valid string
+fn read_any_value<'a>(value: &'a [u8], mut any_value: &'a [u8]) -> NeutronResult<&'a [u8]> {
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        match number {
+            1 => {
+                field.str()?;
+            }
+            2 => any_value = field.bytes()?,
+            _ => {}
+        }
+    }
+
+    Ok(any_value)
+}
+
+/// Reads seconds of `google.protobuf.Timestamp` merging them into **seconds** of the previous
+/// occurrences, the nanos are only checked to be a varint
+fn read_timestamp_seconds(value: &[u8], mut seconds: u64) -> NeutronResult<u64> {
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        match number {
+            1 => seconds = field.varint()?,
+            2 => {
+                field.varint()?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(seconds)
+}
+
+/// Reads `cosmos.staking.v1beta1.Description` into **validator**, fields missing from **value**
+/// keep values of the previous occurrences
+fn read_description<'a>(value: &'a [u8], validator: &mut ValidatorRef<'a>) -> NeutronResult<()> {
+    let (mut moniker, mut identity, mut website, mut security_contact, mut details) = (
+        validator.moniker.unwrap_or_default(),
+        validator.identity.unwrap_or_default(),
+        validator.website.unwrap_or_default(),
+        validator.security_contact.unwrap_or_default(),
+        validator.details.unwrap_or_default(),
+    );
+
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        match number {
+            1 => moniker = field.str()?,
+            2 => identity = field.str()?,
+            3 => website = field.str()?,
+            4 => security_contact = field.str()?,
+            5 => details = field.str()?,
+            _ => {}
+        }
+    }
+
+    validator.moniker = Some(moniker);
+    validator.identity = Some(identity);
+    validator.website = Some(website);
+    validator.security_contact = Some(security_contact);
+    validator.details = Some(details);
+
+    Ok(())
+}
+
+/// Reads `cosmos.staking.v1beta1.Commission` into **validator** and unparsed rate, max rate and
+/// max change rate into **commission_rates**, fields missing from **value** keep values of the
+/// previous occurrences
+fn read_commission<'a>(
+    value: &'a [u8],
+    validator: &mut ValidatorRef<'a>,
+    commission_rates: &mut Option<[&'a str; 3]>,
+) -> NeutronResult<()> {
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        match number {
+            1 => {
+                let [rate, max_rate, max_change_rate] = commission_rates.get_or_insert([""; 3]);
+
+                let mut rates_reader = ProtoReader::new(field.bytes()?);
+                while let Some((number, field)) = rates_reader.next_field()? {
+                    match number {
+                        1 => *rate = field.str()?,
+                        2 => *max_rate = field.str()?,
+                        3 => *max_change_rate = field.str()?,
+                        _ => {}
+                    }
+                }
+            }
+            2 => {
+                let previous = validator.update_time.unwrap_or_default();
+                validator.update_time = Some(read_timestamp_seconds(field.bytes()?, previous)?)
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds validator structure of remote chain from `Validator`.
+/// Rates are set only if all of them are present, otherwise they are not stored at all.
+fn cosmos_validator(validator: &Validator) -> CosmosValidator {
+    let commission_rates = match (
+        validator.rate,
+        validator.max_rate,
+        validator.max_change_rate,
+    ) {
+        (Some(rate), Some(max_rate), Some(max_change_rate)) => Some(CommissionRates {
+            rate: rate.to_atomics_string(),
+            max_rate: max_rate.to_atomics_string(),
+            max_change_rate: max_change_rate.to_atomics_string(),
+        }),
+        _ => None,
+    };
+    let commission = if commission_rates.is_some() || validator.update_time.is_some() {
+        Some(Commission {
+            commission_rates,
+            update_time: validator.update_time.map(proto_timestamp),
+        })
+    } else {
+        None
+    };
+
+    CosmosValidator {
+        operator_address: validator.operator_address.clone(),
+        // key type is not a part of `Validator`, it is a usual consensus key type of Cosmos chains
+        consensus_pubkey: validator
+            .consensus_pubkey
+            .as_ref()
+            .map(|value| prost_types::Any {
+                type_url: "/cosmos.crypto.ed25519.PubKey".to_string(),
+                value: value.clone(),
+            }),
+        jailed: validator.jailed,
+        status: validator.status,
+        tokens: validator.tokens.clone(),
+        delegator_shares: validator.delegator_shares.clone(),
+        description: validator.moniker.as_ref().map(|moniker| Description {
+            moniker: moniker.clone(),
+            identity: validator.identity.clone().unwrap_or_default(),
+            website: validator.website.clone().unwrap_or_default(),
+            security_contact: validator.security_contact.clone().unwrap_or_default(),
+            details: validator.details.clone().unwrap_or_default(),
+        }),
+        unbonding_height: validator.unbonding_height as i64,
+        unbonding_time: validator.unbonding_time.map(proto_timestamp),
+        commission,
+        min_self_delegation: validator.min_self_delegation.to_string(),
+    }
+}
+
+/// Converts **seconds** since epoch into a protobuf timestamp
+fn proto_timestamp(seconds: u64) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: seconds as i64,
+        nanos: 0,
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 /// Validator structure for the querier. Contains validator signing info from `slashing` module
 pub struct ValidatorSigningInfo {
@@ -297,9 +838,10 @@ impl KVReconstruct for SigningInfo {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<SigningInfo> {
         let mut signing_infos = Vec::with_capacity(storage_values.len());
 
-        for kv in storage_values {
+        for (i, kv) in storage_values.iter().enumerate() {
             let signing_info: CosmosValidatorSigningInfo =
-                CosmosValidatorSigningInfo::decode(kv.value.as_slice())?;
+                CosmosValidatorSigningInfo::decode(kv.value.as_slice())
+                    .map_err(|e| invalid_storage_value(i, SIGNING_INFO_SHAPE, e))?;
 
             let validator = ValidatorSigningInfo {
                 address: signing_info.address,
@@ -317,6 +859,35 @@ impl KVReconstruct for SigningInfo {
     }
 }
 
+impl KVConstruct for SigningInfo {
+    type Context = ();
+
+    fn construct(&self, _: &()) -> NeutronResult<Vec<StorageValue>> {
+        self.signing_infos
+            .iter()
+            .map(|signing_info| {
+                let value = CosmosValidatorSigningInfo {
+                    address: signing_info.address.clone(),
+                    start_height: signing_info.start_height as i64,
+                    index_offset: signing_info.index_offset as i64,
+                    jailed_until: signing_info.jailed_until.map(proto_timestamp),
+                    tombstoned: signing_info.tombstoned,
+                    missed_blocks_counter: signing_info.missed_blocks_counter as i64,
+                };
+
+                Ok(new_storage_value(
+                    SLASHING_STORE_KEY,
+                    create_validator_signing_info_key(decode_address_of_kind(
+                        &signing_info.address,
+                        AddressKind::ValidatorConsensus,
+                    )?)?,
+                    value.encode_to_vec(),
+                ))
+            })
+            .collect()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 /// TallyResult defines a standard tally for a governance proposal.
 pub struct TallyResult {
@@ -351,17 +922,35 @@ impl KVReconstruct for GovernmentProposal {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<GovernmentProposal> {
         let mut proposals = Vec::with_capacity(storage_values.len());
 
-        for kv in storage_values {
-            let proposal: CosmosProposal = CosmosProposal::decode(kv.value.as_slice())?;
+        for (i, kv) in storage_values.iter().enumerate() {
+            let proposal: CosmosProposal = CosmosProposal::decode(kv.value.as_slice())
+                .map_err(|e| invalid_storage_value(i, PROPOSAL_SHAPE, e))?;
 
             let mut coins: Vec<Coin> = Vec::with_capacity(proposal.total_deposit.len());
 
             for coin in proposal.total_deposit {
-                let amount = Uint128::from_str(coin.amount.as_str())?;
+                let amount = Uint128::from_str(coin.amount.as_str())
+                    .map_err(|e| invalid_storage_value(i, PROPOSAL_SHAPE, e))?;
                 coins.push(Coin::new(amount.u128(), coin.denom));
             }
 
-            let final_tally_result = &proposal.final_tally_result;
+            let tally_count = |count: &str| {
+                Uint128::from_str(count).map_err(|e| {
+                    invalid_storage_value(i, PROPOSAL_SHAPE, format!("invalid tally count: {}", e))
+                })
+            };
+            let final_tally_result = proposal
+                .final_tally_result
+                .as_ref()
+                .map(|v| -> NeutronResult<TallyResult> {
+                    Ok(TallyResult {
+                        abstain: tally_count(&v.abstain)?,
+                        no: tally_count(&v.no)?,
+                        no_with_veto: tally_count(&v.no_with_veto)?,
+                        yes: tally_count(&v.yes)?,
+                    })
+                })
+                .transpose()?;
 
             let proposal = Proposal {
                 proposal_id: proposal.proposal_id,
@@ -372,13 +961,7 @@ impl KVReconstruct for GovernmentProposal {
                 deposit_end_time: proposal.deposit_end_time.map(|v| v.seconds as u64),
                 voting_end_time: proposal.voting_end_time.map(|v| v.seconds as u64),
                 voting_start_time: proposal.voting_start_time.map(|v| v.seconds as u64),
-                final_tally_result: final_tally_result.as_ref().map(|v| TallyResult {
-                    abstain: Uint128::from_str(v.abstain.as_str()).unwrap_or(Uint128::zero()),
-                    no: Uint128::from_str(v.no.as_str()).unwrap_or(Uint128::zero()),
-                    no_with_veto: Uint128::from_str(v.no_with_veto.as_str())
-                        .unwrap_or(Uint128::zero()),
-                    yes: Uint128::from_str(v.yes.as_str()).unwrap_or(Uint128::zero()),
-                }),
+                final_tally_result,
             };
 
             proposals.push(proposal);
@@ -388,6 +971,56 @@ impl KVReconstruct for GovernmentProposal {
     }
 }
 
+impl KVConstruct for GovernmentProposal {
+    type Context = ();
+
+    fn construct(&self, _: &()) -> NeutronResult<Vec<StorageValue>> {
+        self.proposals
+            .iter()
+            .map(|proposal| {
+                let value = CosmosProposal {
+                    proposal_id: proposal.proposal_id,
+                    // only type of the content is a part of `Proposal`
+                    content: proposal
+                        .proposal_type
+                        .as_ref()
+                        .map(|type_url| prost_types::Any {
+                            type_url: type_url.clone(),
+                            value: vec![],
+                        }),
+                    status: proposal.status,
+                    final_tally_result: proposal.final_tally_result.as_ref().map(|v| {
+                        CosmosTallyResult {
+                            yes: v.yes.to_string(),
+                            abstain: v.abstain.to_string(),
+                            no: v.no.to_string(),
+                            no_with_veto: v.no_with_veto.to_string(),
+                        }
+                    }),
+                    submit_time: proposal.submit_time.map(proto_timestamp),
+                    deposit_end_time: proposal.deposit_end_time.map(proto_timestamp),
+                    total_deposit: proposal
+                        .total_deposit
+                        .iter()
+                        .map(|coin| CosmosCoin {
+                            denom: coin.denom.clone(),
+                            amount: coin.amount.to_string(),
+                        })
+                        .collect(),
+                    voting_start_time: proposal.voting_start_time.map(proto_timestamp),
+                    voting_end_time: proposal.voting_end_time.map(proto_timestamp),
+                };
+
+                Ok(new_storage_value(
+                    GOV_STORE_KEY,
+                    create_gov_proposal_key(proposal.proposal_id)?,
+                    value.encode_to_vec(),
+                ))
+            })
+            .collect()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 /// Proposal vote option defines the members of a governance proposal vote option.
 pub struct WeightedVoteOption {
@@ -413,8 +1046,9 @@ impl KVReconstruct for GovernmentProposalVotes {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<GovernmentProposalVotes> {
         let mut proposal_votes = Vec::with_capacity(storage_values.len());
 
-        for kv in storage_values {
-            let voter_vote: Vote = Vote::decode(kv.value.as_slice())?;
+        for (i, kv) in storage_values.iter().enumerate() {
+            let voter_vote: Vote = Vote::decode(kv.value.as_slice())
+                .map_err(|e| invalid_storage_value(i, VOTE_SHAPE, e))?;
 
             let vote = ProposalVote {
                 proposal_id: voter_vote.proposal_id,
@@ -436,6 +1070,41 @@ impl KVReconstruct for GovernmentProposalVotes {
     }
 }
 
+impl KVConstruct for GovernmentProposalVotes {
+    type Context = ();
+
+    fn construct(&self, _: &()) -> NeutronResult<Vec<StorageValue>> {
+        self.proposal_votes
+            .iter()
+            .map(|vote| {
+                let value = Vote {
+                    proposal_id: vote.proposal_id,
+                    voter: vote.voter.clone(),
+                    // deprecated field, weighted options are used instead
+                    option: 0,
+                    options: vote
+                        .options
+                        .iter()
+                        .map(|v| CosmosWeightedVoteOption {
+                            option: v.option,
+                            weight: v.weight.clone(),
+                        })
+                        .collect(),
+                };
+
+                Ok(new_storage_value(
+                    GOV_STORE_KEY,
+                    create_gov_proposal_voter_votes_key(
+                        vote.proposal_id,
+                        decode_address_of_kind(&vote.voter, AddressKind::Account)?,
+                    )?,
+                    value.encode_to_vec(),
+                ))
+            })
+            .collect()
+    }
+}
+
 /// Delegation is basic (cheap to query) data about a delegation.
 ///
 /// Instances are created in the querier.
@@ -457,81 +1126,492 @@ pub struct Delegations {
 
 impl KVReconstruct for Delegations {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<Delegations> {
-        // We are taking 2 items chunks from starage_value to calculate one delegation
-        let mut delegations: Vec<StdDelegation> = Vec::with_capacity(storage_values.len() / 2);
+        // first StorageValue is denom, the rest are delegations and validators alternately
+        let (denom_kv, pairs) = split_delegations_storage_values(storage_values, "denom")?;
 
-        if storage_values.is_empty() {
-            return Err(NeutronError::InvalidQueryResultFormat(
-                "storage_values length is 0".into(),
-            ));
-        }
-        // first StorageValue is denom
-        if storage_values[0].value.is_empty() {
+        if denom_kv.value.is_empty() {
             // Incoming denom cannot be empty, it should always be configured on chain.
             // If we receive empty denom, that means incoming data structure is corrupted
             // and we cannot build `cosmwasm_std::Delegation`'s using this data.
-            return Err(NeutronError::InvalidQueryResultFormat(
-                "denom is empty".into(),
+            return Err(invalid_storage_value(
+                0,
+                "JSON encoded bond denom",
+                "value is empty",
             ));
         }
-        let denom: String = from_json(&storage_values[0].value)?;
+        let denom: String = from_json(&denom_kv.value)
+            .map_err(|e| invalid_storage_value(0, "JSON encoded bond denom", e))?;
 
-        // the rest are delegations and validators alternately
-        for chunk in storage_values[1..].chunks(2) {
-            if chunk[0].value.is_empty() {
-                // Incoming delegation can actually be empty, this just means that delegation
-                // is not present on remote chain, which is to be expected. So, if it doesn't
-                // exist, we can safely skip this and following chunk.
-                continue;
+        Ok(Delegations {
+            delegations: reconstruct_delegation_pairs(pairs, &denom)?,
+        })
+    }
+}
+
+/// Splits **storage_values** of the **Delegator Delegation Interchain Query** into the first value
+/// (**first_value** describes what it is) and pairs of delegation and validator values
+pub(crate) fn split_delegations_storage_values<'a>(
+    storage_values: &'a [StorageValue],
+    first_value: &str,
+) -> NeutronResult<(&'a StorageValue, &'a [StorageValue])> {
+    match storage_values.split_first() {
+        Some((first, pairs)) if pairs.len() % 2 == 0 => Ok((first, pairs)),
+        _ => Err(NeutronError::InvalidQueryResultFormat(format!(
+            "expected {} followed by pairs of delegation and validator storage values, got {} storage values",
+            first_value,
+            storage_values.len()
+        ))),
+    }
+}
+
+/// Reconstructs delegations of **denom** from pairs of delegation and validator **StorageValue**'s.
+/// Indexes in errors are counted from 1 since the pairs follow the denom (or params) value.
+pub(crate) fn reconstruct_delegation_pairs(
+    pairs: &[StorageValue],
+    denom: &str,
+) -> NeutronResult<Vec<StdDelegation>> {
+    // We are taking 2 items chunks from starage_value to calculate one delegation
+    let mut delegations: Vec<StdDelegation> = Vec::with_capacity(pairs.len() / 2);
+
+    for (i, pair) in pairs.chunks_exact(2).enumerate() {
+        let (delegation_kv, validator_kv) = (&pair[0], &pair[1]);
+        let (delegation_index, validator_index) = (2 * i + 1, 2 * i + 2);
+
+        if delegation_kv.value.is_empty() {
+            // Incoming delegation can actually be empty, this just means that delegation
+            // is not present on remote chain, which is to be expected. So, if it doesn't
+            // exist, we can safely skip this and following chunk.
+            continue;
+        }
+        let delegation_sdk: Delegation = Delegation::decode(delegation_kv.value.as_slice())
+            .map_err(|e| invalid_storage_value(delegation_index, DELEGATION_SHAPE, e))?;
+
+        if validator_kv.value.is_empty() {
+            // At this point, incoming validator cannot be empty, that would be invalid,
+            // because delegation is already defined, so, building `cosmwasm_std::Delegation`
+            // from this data is impossible, incoming data is corrupted.
+            return Err(invalid_storage_value(
+                validator_index,
+                VALIDATOR_SHAPE,
+                "value is empty",
+            ));
+        }
+        let validator: CosmosValidator = CosmosValidator::decode(validator_kv.value.as_slice())
+            .map_err(|e| invalid_storage_value(validator_index, VALIDATOR_SHAPE, e))?;
+
+        delegations.push(
+            delegation_from_parts(delegation_sdk, validator, denom)
+                .map_err(|e| e.into_error(delegation_index))?,
+        );
+    }
+
+    Ok(delegations)
+}
+
+/// Context of `KVConstruct` for delegations
+#[derive(Clone, Debug, PartialEq)]
+pub struct DelegationsContext {
+    /// bond denom of remote chain, it's queried along with delegations
+    pub denom: String,
+    /// validators of the delegations as they are stored on remote chain, their tokens and
+    /// delegator shares are used to convert delegated amounts into delegation shares
+    pub validators: Vec<CosmosValidator>,
+}
+
+impl KVConstruct for Delegations {
+    type Context = DelegationsContext;
+
+    fn construct(&self, context: &DelegationsContext) -> NeutronResult<Vec<StorageValue>> {
+        let mut storage_values = vec![new_storage_value(
+            PARAMS_STORE_KEY,
+            create_params_store_key(STAKING_STORE_KEY, KEY_BOND_DENOM),
+            to_json_vec(&context.denom)?,
+        )];
+        storage_values.extend(construct_delegation_pairs(
+            &self.delegations,
+            &context.validators,
+        )?);
+
+        Ok(storage_values)
+    }
+}
+
+/// Encodes **delegations** into pairs of delegation and validator **StorageValue**'s, where
+/// validators are taken from **validators** by operator address. Delegation shares are computed
+/// from delegated amounts the same way `SharesFromTokens` of the staking module does.
+pub(crate) fn construct_delegation_pairs(
+    delegations: &[StdDelegation],
+    validators: &[CosmosValidator],
+) -> NeutronResult<Vec<StorageValue>> {
+    let mut storage_values: Vec<StorageValue> = Vec::with_capacity(delegations.len() * 2);
+
+    for delegation in delegations {
+        let delegator_addr =
+            decode_address_of_kind(delegation.delegator.as_str(), AddressKind::Account)?;
+        let validator_addr =
+            decode_address_of_kind(&delegation.validator, AddressKind::ValidatorOperator)?;
+        let validator = validators
+            .iter()
+            .find(|validator| validator.operator_address == delegation.validator)
+            .ok_or_else(|| {
+                NeutronError::InvalidKVConstructContext(format!(
+                    "validator {} of the delegation is not provided",
+                    delegation.validator
+                ))
+            })?;
+
+        // https://github.com/cosmos/cosmos-sdk/blob/35ae2c4c72d4aeb33447d5a7af23ca47f786606e/x/staking/types/validator.go#L322
+        // shares = validator.delegator_shares.MulInt(amount).QuoInt(validator.tokens)
+        let shares = LegacyDec::from_atomics_str(&validator.delegator_shares)?
+            .checked_mul_int(delegation.amount.amount)?
+            .checked_quo_int(Uint256::from_str(&validator.tokens)?)?;
+        let delegation_sdk = Delegation {
+            delegator_address: delegation.delegator.to_string(),
+            validator_address: delegation.validator.clone(),
+            shares: shares.to_atomics_string(),
+        };
+
+        storage_values.push(new_storage_value(
+            STAKING_STORE_KEY,
+            create_delegation_key(&delegator_addr, &validator_addr)?,
+            delegation_sdk.encode_to_vec(),
+        ));
+        storage_values.push(new_storage_value(
+            STAKING_STORE_KEY,
+            create_validator_key(&validator_addr)?,
+            validator.encode_to_vec(),
+        ));
+    }
+
+    Ok(storage_values)
+}
+
+impl KVReconstructWithStatus for Delegations {
+    fn reconstruct_with_status(
+        storage_values: &[StorageValue],
+    ) -> NeutronResult<(Delegations, Vec<KeyStatus>)> {
+        let (denom_kv, rest) = storage_values.split_first().ok_or_else(|| {
+            NeutronError::InvalidQueryResultFormat("storage_values length is 0".into())
+        })?;
+
+        // first StorageValue is denom
+        let denom = if denom_kv.value.is_empty() {
+            Err(KVStatus::Absent)
+        } else {
+            from_json::<String>(&denom_kv.value).map_err(|e| KVStatus::Malformed(e.to_string()))
+        };
+
+        Ok(reconstruct_delegations_with_status(denom_kv, denom, rest))
+    }
+}
+
+/// Builds `StdDelegation` of **denom** from delegation and validator structures of remote chain
+fn delegation_from_parts(
+    delegation_sdk: Delegation,
+    validator: CosmosValidator,
+    denom: &str,
+) -> Result<StdDelegation, MalformedDelegationPart> {
+    let delegated_tokens = delegated_tokens(
+        &delegation_sdk.shares,
+        &validator.tokens,
+        &validator.delegator_shares,
+        &validator.operator_address,
+    )?;
+
+    Ok(StdDelegation {
+        delegator: Addr::unchecked(delegation_sdk.delegator_address.as_str()),
+        validator: delegation_sdk.validator_address,
+        amount: Coin::new(delegated_tokens, denom),
+    })
+}
+
+/// Part of a pair of delegation and validator storage values delegated tokens can't be computed
+/// from, the validator value follows the delegation value
+enum MalformedDelegationPart {
+    Delegation(String),
+    Validator(String),
+}
+
+impl MalformedDelegationPart {
+    /// Converts into an error of the pair which delegation value is at **delegation_index**
+    fn into_error(self, delegation_index: usize) -> NeutronError {
+        match self {
+            MalformedDelegationPart::Delegation(reason) => {
+                invalid_storage_value(delegation_index, DELEGATION_SHAPE, reason)
+            }
+            MalformedDelegationPart::Validator(reason) => {
+                invalid_storage_value(delegation_index + 1, VALIDATOR_SHAPE, reason)
             }
-            let delegation_sdk: Delegation = Delegation::decode(chunk[0].value.as_slice())?;
+        }
+    }
 
-            let mut delegation_std = StdDelegation {
-                delegator: Addr::unchecked(delegation_sdk.delegator_address.as_str()),
-                validator: delegation_sdk.validator_address,
-                amount: Default::default(),
-            };
+    /// Converts into statuses of the delegation and the validator keys
+    fn into_statuses(self) -> (KVStatus, KVStatus) {
+        match self {
+            MalformedDelegationPart::Delegation(reason) => {
+                (KVStatus::Malformed(reason), KVStatus::Present)
+            }
+            MalformedDelegationPart::Validator(reason) => {
+                (KVStatus::Present, KVStatus::Malformed(reason))
+            }
+        }
+    }
+}
 
-            if chunk[1].value.is_empty() {
-                // At this point, incoming validator cannot be empty, that would be invalid,
-                // because delegation is already defined, so, building `cosmwasm_std::Delegation`
-                // from this data is impossible, incoming data is corrupted.post
-                return Err(NeutronError::InvalidQueryResultFormat(
-                    "validator is empty".into(),
-                ));
+/// Computes amount of tokens delegated with **shares** to the validator with **operator_address**
+/// which has **tokens** and **delegator_shares** in total
+fn delegated_tokens(
+    shares: &str,
+    tokens: &str,
+    delegator_shares: &str,
+    operator_address: &str,
+) -> Result<Uint256, MalformedDelegationPart> {
+    let delegation_shares = LegacyDec::from_atomics_str(shares)
+        .map_err(|e| MalformedDelegationPart::Delegation(format!("invalid shares: {}", e)))?;
+    let delegator_shares = LegacyDec::from_atomics_str(delegator_shares).map_err(|e| {
+        MalformedDelegationPart::Validator(format!("invalid delegator shares: {}", e))
+    })?;
+    let validator_tokens = Uint256::from_str(tokens)
+        .map_err(|e| MalformedDelegationPart::Validator(format!("invalid tokens: {}", e)))?;
+
+    // https://github.com/cosmos/cosmos-sdk/blob/35ae2c4c72d4aeb33447d5a7af23ca47f786606e/x/staking/keeper/querier.go#L463
+    // delegated_tokens = TruncateInt(delegation.shares.MulInt(validator.tokens).Quo(validator.total_shares))
+    Ok(delegation_shares
+        .checked_mul_int(validator_tokens)
+        .and_then(|amount| amount.checked_quo(delegator_shares))
+        .map_err(|e| {
+            MalformedDelegationPart::Validator(format!(
+                "can't compute delegated tokens of validator {}: {}",
+                operator_address, e
+            ))
+        })?
+        .truncate_int())
+}
+
+/// Reconstructs delegations from pairs of delegation and validator **StorageValue**'s
+/// reporting status of every key. **denom** is a bond denom or a status of its key
+/// if it can't be read, delegations are not built in that case.
+pub(crate) fn reconstruct_delegations_with_status(
+    denom_kv: &StorageValue,
+    denom: Result<String, KVStatus>,
+    storage_values: &[StorageValue],
+) -> (Delegations, Vec<KeyStatus>) {
+    let mut delegations: Vec<StdDelegation> = Vec::with_capacity(storage_values.len() / 2);
+    let mut statuses: Vec<KeyStatus> = Vec::with_capacity(storage_values.len() + 1);
+
+    statuses.push(KeyStatus::new(
+        denom_kv,
+        denom.clone().err().unwrap_or(KVStatus::Present),
+    ));
+
+    for chunk in storage_values.chunks(2) {
+        let [delegation_kv, validator_kv] = chunk else {
+            statuses.push(KeyStatus::new(
+                &chunk[0],
+                KVStatus::Malformed("delegation without validator".into()),
+            ));
+            continue;
+        };
+
+        let (delegation_status, validator_status) = match (&denom, delegation_kv.value.is_empty()) {
+            // delegation is not present on remote chain
+            (_, true) => (KVStatus::Absent, presence_status(validator_kv)),
+            // delegation amount can't be computed without denom, so only check the values
+            (Err(_), false) => (
+                presence_status(delegation_kv),
+                presence_status(validator_kv),
+            ),
+            (Ok(denom), false) => {
+                match reconstruct_delegation_with_status(delegation_kv, validator_kv, denom) {
+                    Ok(delegation) => {
+                        delegations.push(delegation);
+                        (KVStatus::Present, KVStatus::Present)
+                    }
+                    Err(key_statuses) => key_statuses,
+                }
             }
-            let validator: CosmosValidator = CosmosValidator::decode(chunk[1].value.as_slice())?;
+        };
 
-            let delegation_shares = Decimal256::from_atomics(
-                Uint256::from_str(&delegation_sdk.shares)?,
-                DECIMAL_PLACES,
-            )?;
+        statuses.push(KeyStatus::new(delegation_kv, delegation_status));
+        statuses.push(KeyStatus::new(validator_kv, validator_status));
+    }
 
-            let delegator_shares = Decimal256::from_atomics(
-                Uint256::from_str(&validator.delegator_shares)?,
-                DECIMAL_PLACES,
-            )?;
+    (Delegations { delegations }, statuses)
+}
 
-            let validator_tokens =
-                Decimal256::from_atomics(Uint128::from_str(&validator.tokens)?, 0)?;
+/// Reconstructs a delegation from non-empty **delegation_kv** and **validator_kv**.
+/// Returns statuses of both keys if it's impossible.
+fn reconstruct_delegation_with_status(
+    delegation_kv: &StorageValue,
+    validator_kv: &StorageValue,
+    denom: &str,
+) -> Result<StdDelegation, (KVStatus, KVStatus)> {
+    let delegation_sdk = Delegation::decode(delegation_kv.value.as_slice()).map_err(|e| {
+        (
+            KVStatus::Malformed(e.to_string()),
+            presence_status(validator_kv),
+        )
+    })?;
+
+    if validator_kv.value.is_empty() {
+        return Err((KVStatus::Present, KVStatus::Absent));
+    }
+    let validator = CosmosValidator::decode(validator_kv.value.as_slice())
+        .map_err(|e| (KVStatus::Present, KVStatus::Malformed(e.to_string())))?;
 
-            // https://github.com/cosmos/cosmos-sdk/blob/35ae2c4c72d4aeb33447d5a7af23ca47f786606e/x/staking/keeper/querier.go#L463
-            // delegated_tokens = quotient(delegation.shares * validator.tokens / validator.total_shares);
-            let delegated_tokens = delegation_shares
-                .checked_mul(validator_tokens)?
-                .div(delegator_shares)
-                .atomics()
-                .div(Uint256::new(DECIMAL_FRACTIONAL));
+    delegation_from_parts(delegation_sdk, validator, denom)
+        .map_err(MalformedDelegationPart::into_statuses)
+}
 
-            delegation_std.amount = Coin::new(delegated_tokens, &denom);
+/// A delegation which borrows addresses from **StorageValue**'s, its denom is kept by `DelegationsRef`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelegationRef<'a> {
+    pub delegator: &'a str,
+    pub validator: &'a str,
+    pub amount: Uint256,
+}
 
-            delegations.push(delegation_std);
+impl DelegationRef<'_> {
+    /// Copies the delegation into `StdDelegation` of **denom**
+    pub fn to_std_delegation(&self, denom: &str) -> StdDelegation {
+        StdDelegation {
+            delegator: Addr::unchecked(self.delegator),
+            validator: self.validator.to_string(),
+            amount: Coin::new(self.amount, denom),
         }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A zero-copy counterpart of `Delegations` for the **Delegator Delegation Interchain Query**.
+/// The bond denom is stored once instead of being copied into every delegation.
+pub struct DelegationsRef<'a> {
+    pub denom: Cow<'a, str>,
+    pub delegations: Vec<DelegationRef<'a>>,
+}
+
+impl From<DelegationsRef<'_>> for Delegations {
+    fn from(delegations: DelegationsRef) -> Delegations {
+        Delegations {
+            delegations: delegations
+                .delegations
+                .iter()
+                .map(|delegation| delegation.to_std_delegation(&delegations.denom))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> KVReconstructRef<'a> for DelegationsRef<'a> {
+    fn reconstruct_ref(storage_values: &'a [StorageValue]) -> NeutronResult<DelegationsRef<'a>> {
+        // first StorageValue is denom, the rest are delegations and validators alternately
+        let (denom_kv, pairs) = split_delegations_storage_values(storage_values, "denom")?;
+
+        if denom_kv.value.is_empty() {
+            return Err(invalid_storage_value(
+                0,
+                "JSON encoded bond denom",
+                "value is empty",
+            ));
+        }
+        let denom = read_json_str(&denom_kv.value)
+            .map_err(|e| invalid_storage_value(0, "JSON encoded bond denom", e))?;
 
-        Ok(Delegations { delegations })
+        Ok(DelegationsRef {
+            denom,
+            delegations: reconstruct_delegation_pairs_ref(pairs)?,
+        })
     }
 }
 
+/// Reads a JSON encoded string, it's borrowed from **value** unless it has escape sequences
+fn read_json_str(value: &[u8]) -> NeutronResult<Cow<'_, str>> {
+    if let [b'"', string @ .., b'"'] = value {
+        if !string
+            .iter()
+            .any(|&c| matches!(c, b'"' | b'\\' | 0x00..=0x1f))
+        {
+            return Ok(Cow::Borrowed(from_utf8(string)?));
+        }
+    }
+
+    Ok(Cow::Owned(from_json(value)?))
+}
+
+/// Zero-copy counterpart of `reconstruct_delegation_pairs`, reports errors the same way
+pub(crate) fn reconstruct_delegation_pairs_ref(
+    pairs: &[StorageValue],
+) -> NeutronResult<Vec<DelegationRef<'_>>> {
+    let mut delegations: Vec<DelegationRef> = Vec::with_capacity(pairs.len() / 2);
+
+    for (i, pair) in pairs.chunks_exact(2).enumerate() {
+        let (delegation_kv, validator_kv) = (&pair[0], &pair[1]);
+        let (delegation_index, validator_index) = (2 * i + 1, 2 * i + 2);
+
+        if delegation_kv.value.is_empty() {
+            // delegation is not present on remote chain
+            continue;
+        }
+        let (delegator, validator, shares) = read_delegation(&delegation_kv.value)
+            .map_err(|e| invalid_storage_value(delegation_index, DELEGATION_SHAPE, e))?;
+
+        if validator_kv.value.is_empty() {
+            return Err(invalid_storage_value(
+                validator_index,
+                VALIDATOR_SHAPE,
+                "value is empty",
+            ));
+        }
+        let (operator_address, tokens, delegator_shares) =
+            read_validator_shares(&validator_kv.value)
+                .map_err(|e| invalid_storage_value(validator_index, VALIDATOR_SHAPE, e))?;
+
+        delegations.push(DelegationRef {
+            delegator,
+            validator,
+            amount: delegated_tokens(shares, tokens, delegator_shares, operator_address)
+                .map_err(|e| e.into_error(delegation_index))?,
+        });
+    }
+
+    Ok(delegations)
+}
+
+/// Reads delegator address, validator address and shares of `cosmos.staking.v1beta1.Delegation`
+fn read_delegation(value: &[u8]) -> NeutronResult<(&str, &str, &str)> {
+    let (mut delegator, mut validator, mut shares) = ("", "", "");
+
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        match number {
+            1 => delegator = field.str()?,
+            2 => validator = field.str()?,
+            3 => shares = field.str()?,
+            _ => {}
+        }
+    }
+
+    Ok((delegator, validator, shares))
+}
+
+/// Reads operator address, tokens and delegator shares of `cosmos.staking.v1beta1.Validator`.
+/// The rest of the fields are skipped without decoding.
+fn read_validator_shares(value: &[u8]) -> NeutronResult<(&str, &str, &str)> {
+    let (mut operator_address, mut tokens, mut delegator_shares) = ("", "", "");
+
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        match number {
+            1 => operator_address = field.str()?,
+            5 => tokens = field.str()?,
+            6 => delegator_shares = field.str()?,
+            _ => {}
+        }
+    }
+
+    Ok((operator_address, tokens, delegator_shares))
+}
+
 /// Represents a single unbonding delegation from some validator to some delegator on remote chain
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct UnbondingEntry {
@@ -566,9 +1646,10 @@ impl KVReconstruct for UnbondingDelegations {
         let mut unbonding_responses: Vec<UnbondingResponse> =
             Vec::with_capacity(storage_values.len());
 
-        for storage_value in storage_values {
+        for (i, storage_value) in storage_values.iter().enumerate() {
             let unbonding_delegation_sdk: UnbondingDelegation =
-                UnbondingDelegation::decode(storage_value.value.as_slice())?;
+                UnbondingDelegation::decode(storage_value.value.as_slice())
+                    .map_err(|e| invalid_storage_value(i, UNBONDING_DELEGATION_SHAPE, e))?;
 
             let mut unbonding_response = UnbondingResponse {
                 delegator_address: Addr::unchecked(unbonding_delegation_sdk.delegator_address),
@@ -576,14 +1657,8 @@ impl KVReconstruct for UnbondingDelegations {
                 entries: Vec::with_capacity(unbonding_delegation_sdk.entries.len()),
             };
             for entry in unbonding_delegation_sdk.entries {
-                let unbonding_entry = UnbondingEntry {
-                    balance: Uint128::from_str(&entry.balance)?,
-                    completion_time: entry.completion_time.map(|t| {
-                        Timestamp::from_seconds(t.seconds as u64).plus_nanos(t.nanos as u64)
-                    }),
-                    creation_height: entry.creation_height as u64,
-                    initial_balance: Uint128::from_str(&entry.initial_balance)?,
-                };
+                let unbonding_entry = unbonding_entry_from_sdk(entry)
+                    .map_err(|e| invalid_storage_value(i, UNBONDING_DELEGATION_SHAPE, e))?;
                 unbonding_response.entries.push(unbonding_entry);
             }
 
@@ -595,3 +1670,243 @@ impl KVReconstruct for UnbondingDelegations {
         })
     }
 }
+
+impl KVConstruct for UnbondingDelegations {
+    type Context = ();
+
+    fn construct(&self, _: &()) -> NeutronResult<Vec<StorageValue>> {
+        self.unbonding_responses
+            .iter()
+            .map(|response| {
+                let value = UnbondingDelegation {
+                    delegator_address: response.delegator_address.to_string(),
+                    validator_address: response.validator_address.clone(),
+                    entries: response
+                        .entries
+                        .iter()
+                        .map(|entry| UnbondingDelegationEntry {
+                            creation_height: entry.creation_height as i64,
+                            completion_time: entry.completion_time.map(|t| {
+                                prost_types::Timestamp {
+                                    seconds: t.seconds() as i64,
+                                    nanos: t.subsec_nanos() as i32,
+                                }
+                            }),
+                            initial_balance: entry.initial_balance.to_string(),
+                            balance: entry.balance.to_string(),
+                        })
+                        .collect(),
+                };
+
+                Ok(new_storage_value(
+                    STAKING_STORE_KEY,
+                    create_unbonding_delegation_key(
+                        decode_address_of_kind(
+                            response.delegator_address.as_str(),
+                            AddressKind::Account,
+                        )?,
+                        decode_address_of_kind(
+                            &response.validator_address,
+                            AddressKind::ValidatorOperator,
+                        )?,
+                    )?,
+                    value.encode_to_vec(),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Builds `UnbondingEntry` from unbonding delegation entry structure of remote chain
+fn unbonding_entry_from_sdk(entry: UnbondingDelegationEntry) -> NeutronResult<UnbondingEntry> {
+    Ok(UnbondingEntry {
+        balance: Uint128::from_str(&entry.balance)?,
+        completion_time: entry
+            .completion_time
+            .map(|t| checked_timestamp(t.seconds, t.nanos))
+            .transpose()?,
+        creation_height: entry.creation_height as u64,
+        initial_balance: Uint128::from_str(&entry.initial_balance)?,
+    })
+}
+
+/// Builds `Timestamp` from **seconds** and **nanos** of a protobuf timestamp,
+/// fails if any of them is negative or the result overflows
+pub(crate) fn checked_timestamp(seconds: i64, nanos: i32) -> NeutronResult<Timestamp> {
+    let seconds = u64::try_from(seconds).map_err(StdError::from)?;
+    let nanos = u64::try_from(nanos).map_err(StdError::from)?;
+
+    let nanos_since_epoch = Uint64::new(seconds)
+        .checked_mul(Uint64::new(1_000_000_000))?
+        .checked_add(Uint64::new(nanos))?;
+
+    Ok(Timestamp::from_nanos(nanos_since_epoch.u64()))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **ICA Host Params Interchain Query**.
+/// Contains params of the interchain accounts host module on remote chain.
+///
+/// Can be reconstructed from two legacy **params** subspace values (`HostEnabled`, `AllowMessages`)
+/// or from a single **icahost** module store value (ibc-go v8+).
+pub struct IcaHostParams {
+    /// host_enabled enables or disables the host submodule.
+    pub host_enabled: bool,
+    /// allow_messages defines a list of sdk message typeURLs allowed to be executed on a host chain.
+    pub allow_messages: Vec<String>,
+}
+
+impl IcaHostParams {
+    /// Checks whether a message with **type_url** is allowed to be executed by ICA host
+    pub fn is_message_allowed(&self, type_url: &str) -> bool {
+        self.allow_messages
+            .iter()
+            .any(|m| m == ICAHOST_ALLOW_ALL_MESSAGES || m == type_url)
+    }
+
+    /// Checks that ICA host is enabled and all the **msgs** are allowed to be executed by it.
+    /// Useful to check messages before sending them with `submit_tx`,
+    /// since a rejected message is only noticed after a round trip and closes an ordered channel.
+    pub fn check_msgs(&self, msgs: &[Any]) -> NeutronResult<()> {
+        if !self.host_enabled {
+            return Err(NeutronError::IcaHostDisabled {});
+        }
+
+        for msg in msgs {
+            if !self.is_message_allowed(&msg.type_url) {
+                return Err(NeutronError::IcaHostMessageNotAllowed {
+                    type_url: msg.type_url.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl KVReconstruct for IcaHostParams {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<IcaHostParams> {
+        match storage_values {
+            // params are stored in the icahost module store
+            [params] => {
+                let params: IcaHostModuleParams =
+                    IcaHostModuleParams::decode(params.value.as_slice()).map_err(|e| {
+                        invalid_storage_value(
+                            0,
+                            "ibc.applications.interchain_accounts.host.v1.Params",
+                            e,
+                        )
+                    })?;
+
+                Ok(IcaHostParams {
+                    host_enabled: params.host_enabled,
+                    allow_messages: params.allow_messages,
+                })
+            }
+            // params are stored in the legacy params subspace, every value is JSON encoded
+            [host_enabled, allow_messages] => {
+                if host_enabled.value.is_empty() {
+                    return Err(invalid_storage_value(
+                        0,
+                        "JSON encoded HostEnabled param",
+                        "value is empty",
+                    ));
+                }
+
+                Ok(IcaHostParams {
+                    host_enabled: from_json(&host_enabled.value).map_err(|e| {
+                        invalid_storage_value(0, "JSON encoded HostEnabled param", e)
+                    })?,
+                    allow_messages: if allow_messages.value.is_empty() {
+                        vec![]
+                    } else {
+                        from_json(&allow_messages.value).map_err(|e| {
+                            invalid_storage_value(1, "JSON encoded AllowMessages param", e)
+                        })?
+                    },
+                })
+            }
+            _ => Err(NeutronError::InvalidQueryResultFormat(format!(
+                "expected 1 or 2 storage values for icahost params, got {}",
+                storage_values.len()
+            ))),
+        }
+    }
+}
+
+impl KVConstruct for IcaHostParams {
+    type Context = ();
+
+    /// Encodes params the way they are stored in the legacy **params** subspace
+    fn construct(&self, _: &()) -> NeutronResult<Vec<StorageValue>> {
+        Ok(vec![
+            new_storage_value(
+                PARAMS_STORE_KEY,
+                create_params_store_key(ICAHOST_STORE_KEY, KEY_HOST_ENABLED),
+                to_json_vec(&self.host_enabled)?,
+            ),
+            new_storage_value(
+                PARAMS_STORE_KEY,
+                create_params_store_key(ICAHOST_STORE_KEY, KEY_ALLOW_MESSAGES),
+                to_json_vec(&self.allow_messages)?,
+            ),
+        ])
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// A structure that can be reconstructed from **StorageValues**'s for the **Legacy Params Interchain Query**.
+/// Contains params of a legacy **params** subspace on remote chain decoded into **T**.
+///
+/// Every param value is JSON encoded by the subspace, so **T** is deserialized from a JSON object
+/// where param keys are field names, e.g. `#[serde(rename = "SendEnabled")] send_enabled: bool`.
+/// Params which are not set on remote chain are omitted from the object, so **T** can declare
+/// them as `Option`al fields.
+pub struct LegacyParams<T> {
+    pub params: T,
+}
+
+impl<T: DeserializeOwned> KVReconstruct for LegacyParams<T> {
+    fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<LegacyParams<T>> {
+        let mut object: Vec<u8> = vec![b'{'];
+
+        for (i, kv) in storage_values.iter().enumerate() {
+            if kv.value.is_empty() {
+                continue;
+            }
+
+            let (_, key) = deconstruct_params_store_key(&kv.key)
+                .map_err(|e| invalid_storage_value(i, "param under a params store key", e))?;
+            // every value must be a complete JSON value to be safely put into the object
+            from_json::<IgnoredAny>(&kv.value).map_err(|e| {
+                invalid_storage_value(i, &format!("JSON encoded value of param {}", key), e)
+            })?;
+
+            if object.len() > 1 {
+                object.push(b',');
+            }
+            object.extend_from_slice(to_json_vec(&key)?.as_slice());
+            object.push(b':');
+            object.extend_from_slice(kv.value.as_slice());
+        }
+        object.push(b'}');
+
+        Ok(LegacyParams {
+            params: from_json(&object)?,
+        })
+    }
+}
+
+/// Creates **StorageValue** of **key** in **storage_prefix** store the way remote chain returns it
+pub(crate) fn new_storage_value(
+    storage_prefix: &str,
+    key: Vec<u8>,
+    value: Vec<u8>,
+) -> StorageValue {
+    StorageValue {
+        storage_prefix: storage_prefix.to_string(),
+        key,
+        value,
+        proof: None,
+    }
+}
@@ -0,0 +1,73 @@
+// import all helpers from v045 package
+// to make it available from v047 package (kinda proxy) since they work with Cosmos SDK 0.47 as usual
+pub use crate::interchain_queries::v045::helpers::*;
+
+// But at the same time we redefine some helpers from v045 with helpers below to create keys
+// compatible with Cosmos SDK 0.47
+
+use crate::errors::error::NeutronResult;
+use crate::interchain_queries::address::{decode_address_of_kind, AddressKind};
+use crate::interchain_queries::v045::helpers::merge_delegations_query_keys;
+use crate::interchain_queries::v045::types::STAKING_STORE_KEY;
+use crate::interchain_queries::v047::types::STAKING_PARAMS_KEY;
+use neutron_std::types::neutron::interchainqueries::KvKey;
+
+/// Creates keys for an Interchain Query to get delegations of particular **delegator** to **validators**
+///
+/// * **delegator** is an address of an account on remote chain for which you want to get list of delegations;
+/// * **validators** is a list of validators addresses for which you want to get delegations from particular **delegator**;
+pub fn create_delegations_query_keys(
+    delegator: String,
+    validators: Vec<String>,
+) -> NeutronResult<Vec<KvKey>> {
+    let delegator_addr = decode_address_of_kind(&delegator, AddressKind::Account)?;
+
+    // Allocate memory for such KV keys as:
+    // * staking module params to get staking denomination
+    // * validators structures to calculate amount of delegated tokens
+    // * delegations structures to get info about delegations itself
+    let mut keys: Vec<KvKey> = Vec::with_capacity(validators.len() * 2 + 1);
+
+    // create KV key to get Staking Params from staking module
+    keys.push(KvKey {
+        path: STAKING_STORE_KEY.to_string(),
+        key: vec![STAKING_PARAMS_KEY],
+    });
+
+    for v in validators {
+        let val_addr = decode_address_of_kind(&v, AddressKind::ValidatorOperator)?;
+
+        // create delegation key to get delegation structure
+        keys.push(KvKey {
+            path: STAKING_STORE_KEY.to_string(),
+            key: create_delegation_key(&delegator_addr, &val_addr)?,
+        });
+
+        // create validator key to get validator structure
+        keys.push(KvKey {
+            path: STAKING_STORE_KEY.to_string(),
+            key: create_validator_key(&val_addr)?,
+        })
+    }
+
+    Ok(keys)
+}
+
+/// Updates keys of a registered Interchain Query to get delegations of particular **delegator**
+/// adding **add_validators** to it and removing **remove_validators** from it.
+/// Validators which are already in the query are skipped, staking params key is always kept first.
+///
+/// * **keys** are keys of the registered query created by `create_delegations_query_keys`;
+/// * **delegator** is an address of an account on remote chain for which the query gets delegations;
+/// * **add_validators** is a list of validators addresses to add to the query;
+/// * **remove_validators** is a list of validators addresses to remove from the query;
+pub fn update_delegations_query_keys(
+    keys: Vec<KvKey>,
+    delegator: String,
+    add_validators: Vec<String>,
+    remove_validators: Vec<String>,
+) -> NeutronResult<Vec<KvKey>> {
+    let new_keys = create_delegations_query_keys(delegator.clone(), add_validators)?;
+
+    merge_delegations_query_keys(&keys, &new_keys, &delegator, remove_validators)
+}
@@ -2,14 +2,15 @@
 // to make it available from v047 package (kinda proxy) since they work with Cosmos SDK 0.47 as usual
 pub use crate::interchain_queries::v045::register_queries::*;
 
-use crate::interchain_queries::helpers::decode_and_convert;
+use crate::interchain_queries::helpers::update_interchain_query;
+use crate::interchain_queries::queries::{check_query_type, get_registered_query};
 use crate::interchain_queries::types::QueryType;
-use crate::interchain_queries::v045::helpers::{create_delegation_key, create_validator_key};
-use crate::interchain_queries::v045::types::STAKING_STORE_KEY;
-use crate::interchain_queries::v047::types::STAKING_PARAMS_KEY;
+use crate::interchain_queries::v047::helpers::{
+    create_delegations_query_keys, update_delegations_query_keys,
+};
 use crate::NeutronResult;
-use cosmwasm_std::{Addr, CosmosMsg};
-use neutron_std::types::neutron::interchainqueries::{KvKey, MsgRegisterInterchainQuery};
+use cosmwasm_std::{Addr, CosmosMsg, Deps};
+use neutron_std::types::neutron::interchainqueries::MsgRegisterInterchainQuery;
 
 /// Creates a message to register an Interchain Query to get delegations of particular delegator on remote chain.
 ///
@@ -24,35 +25,7 @@ pub fn new_register_delegator_delegations_query_msg(
     validators: Vec<String>,
     update_period: u64,
 ) -> NeutronResult<CosmosMsg> {
-    let delegator_addr = decode_and_convert(&delegator)?;
-
-    // Allocate memory for such KV keys as:
-    // * staking module params to get staking denomination
-    // * validators structures to calculate amount of delegated tokens
-    // * delegations structures to get info about delegations itself
-    let mut keys: Vec<KvKey> = Vec::with_capacity(validators.len() * 2 + 1);
-
-    // create KV key to get Staking Params from staking module
-    keys.push(KvKey {
-        path: STAKING_STORE_KEY.to_string(),
-        key: vec![STAKING_PARAMS_KEY],
-    });
-
-    for v in validators {
-        let val_addr = decode_and_convert(&v)?;
-
-        // create delegation key to get delegation structure
-        keys.push(KvKey {
-            path: STAKING_STORE_KEY.to_string(),
-            key: create_delegation_key(&delegator_addr, &val_addr)?,
-        });
-
-        // create validator key to get validator structure
-        keys.push(KvKey {
-            path: STAKING_STORE_KEY.to_string(),
-            key: create_validator_key(&val_addr)?,
-        })
-    }
+    let keys = create_delegations_query_keys(delegator, validators)?;
 
     Ok(MsgRegisterInterchainQuery {
         query_type: QueryType::KV.into(),
@@ -64,3 +37,40 @@ pub fn new_register_delegator_delegations_query_msg(
     }
     .into())
 }
+
+/// Creates a message to update a registered Interchain Query to get delegations of particular delegator
+/// on remote chain adding and removing validators. Keys of the query are fetched from the chain,
+/// validators which are already in the query are skipped, the update period is kept.
+///
+/// * **deps** is used to fetch the registered query;
+/// * **contract** is an address of the contract that updates the query. Must be contract that sends this message.
+/// * **query_id** is an ID of the registered delegations query;
+/// * **delegator** is an address of an account on remote chain for which the query gets delegations;
+/// * **add_validators** is a list of validators addresses to add to the query;
+/// * **remove_validators** is a list of validators addresses to remove from the query.
+pub fn update_delegator_delegations_query_msg(
+    deps: Deps,
+    contract: Addr,
+    query_id: u64,
+    delegator: String,
+    add_validators: Vec<String>,
+    remove_validators: Vec<String>,
+) -> NeutronResult<CosmosMsg> {
+    let registered_query = get_registered_query(deps, query_id)?;
+    check_query_type(registered_query.query_type, QueryType::KV)?;
+
+    let keys = update_delegations_query_keys(
+        registered_query.keys,
+        delegator,
+        add_validators,
+        remove_validators,
+    )?;
+
+    update_interchain_query(
+        contract,
+        query_id,
+        keys,
+        registered_query.update_period,
+        None,
+    )
+}
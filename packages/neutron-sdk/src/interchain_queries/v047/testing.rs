@@ -1,17 +1,18 @@
 use crate::interchain_queries::helpers::decode_and_convert;
-use crate::interchain_queries::types::KVReconstruct;
+use crate::interchain_queries::legacy_dec::LegacyDec;
+use crate::interchain_queries::types::{KVConstruct, KVReconstruct, KVReconstructRef};
 use crate::interchain_queries::v045::helpers::create_params_store_key;
-use crate::interchain_queries::v045::types::KEY_BOND_DENOM;
+use crate::interchain_queries::v045::types::{DelegationsContext, KEY_BOND_DENOM};
 use crate::interchain_queries::v047::helpers::{
-    create_account_denom_balance_key, create_delegation_key, create_fee_pool_key,
-    create_gov_proposal_key, create_total_denom_key, create_validator_key,
-    create_validator_signing_info_key,
+    create_account_denom_balance_key, create_delegation_key, create_delegations_query_keys,
+    create_fee_pool_key, create_gov_proposal_key, create_total_denom_key, create_validator_key,
+    create_validator_signing_info_key, update_delegations_query_keys,
 };
 use crate::interchain_queries::v047::types::{
-    Balances, Delegations, FeePool, GovernmentProposal, Proposal, SigningInfo, StakingValidator,
-    StdDelegation, TallyResult, TotalSupply, UnbondingDelegations, UnbondingEntry,
-    UnbondingResponse, Validator as ContractValidator, ValidatorSigningInfo, DECIMAL_PLACES,
-    STAKING_PARAMS_KEY, STAKING_STORE_KEY,
+    Balances, BalancesRef, Delegations, DelegationsRef, FeePool, GovernmentProposal, Proposal,
+    SigningInfo, StakingValidator, StdDelegation, TallyResult, TotalSupply, UnbondingDelegations,
+    UnbondingEntry, UnbondingResponse, Validator as ContractValidator, ValidatorSigningInfo,
+    DECIMAL_PLACES, STAKING_PARAMS_KEY, STAKING_STORE_KEY,
 };
 use crate::{NeutronError, NeutronResult};
 use base64::prelude::*;
@@ -29,6 +30,7 @@ use cosmos_sdk_proto::traits::Message;
 use cosmwasm_std::{Addr, Binary, Coin as StdCoin, Decimal, Timestamp, Uint128, Uint256};
 use hex;
 use neutron_std::types::neutron::interchainqueries::StorageValue;
+use std::borrow::Cow;
 use std::ops::Mul;
 use std::str::FromStr;
 
@@ -44,6 +46,22 @@ pub const DELEGATOR_DELEGATIONS_HEX_RESPONSE: &str = "0a2d636f736d6f7331706d6a77
 pub const DELEGATOR_UNBONDING_DELEGATIONS_HEX_RESPONSE: &str = "0a2d636f736d6f73316d396c33353878756e6868776473303536387a6134396d7a68767578783975787265357475641234636f736d6f7376616c6f7065723138686c356339786e35647a6532673530756177306c326d723032657735377a6b3061756b746e1a2108ed02120c08ba97f9ac0610f6abf18f021a0531303030302205313030303028011a2008f902120b08c797f9ac0610e59a89011a053230303030220532303030302802";
 pub const VALIDATOR_SIGNING_INFO_HEX_RESPONSE: &str = "0a34636f736d6f7376616c636f6e73313966353366717132387636706d7a383737646e653735643464376c307236356432373530707718102200";
 
+/// Context to construct delegations to **validators** which have 1.5 shares per token
+fn delegations_context(denom: &str, validators: &[&str]) -> DelegationsContext {
+    DelegationsContext {
+        denom: denom.to_string(),
+        validators: validators
+            .iter()
+            .map(|operator_address| Validator {
+                operator_address: operator_address.to_string(),
+                tokens: "2000000".to_string(),
+                delegator_shares: "3000000000000000000000000".to_string(),
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
 #[test]
 fn test_balance_reconstruct() {
     struct TestCase {
@@ -255,9 +273,9 @@ fn test_staking_validators_reconstruct() {
                     details: Some("Validator details".to_string()),
                     unbonding_height: 0,
                     unbonding_time: Some(1203981203),
-                    rate: Some(Decimal::from_str("5").unwrap()),
-                    max_rate: Some(Decimal::from_str("20").unwrap()),
-                    max_change_rate: Some(Decimal::from_str("1").unwrap()),
+                    rate: Some(LegacyDec::from_str("5").unwrap()),
+                    max_rate: Some(LegacyDec::from_str("20").unwrap()),
+                    max_change_rate: Some(LegacyDec::from_str("1").unwrap()),
                     update_time: Some(56324234),
                     min_self_delegation: Decimal::from_str("0").unwrap(),
                     jailed: false,
@@ -869,7 +887,7 @@ fn test_delegations_reconstruct() {
             delegations: vec![],
             validators: vec![],
             expected_result: Err(NeutronError::InvalidQueryResultFormat(
-                "params is empty".into(),
+                "storage value 0: expected cosmos.staking.v1beta1.Params: value is empty".into(),
             )),
         },
         TestCase {
@@ -888,7 +906,7 @@ fn test_delegations_reconstruct() {
             }],
             validators: vec![],
             expected_result: Err(NeutronError::InvalidQueryResultFormat(
-                "validator is empty".into(),
+                "storage value 2: expected cosmos.staking.v1beta1.Validator: value is empty".into(),
             )),
         },
     ];
@@ -900,9 +918,10 @@ fn test_delegations_reconstruct() {
             key: vec![STAKING_PARAMS_KEY],
             value: {
                 if ts.staking_params.bond_denom.is_empty() {
-                    return Default::default();
+                    vec![]
+                } else {
+                    ts.staking_params.encode_to_vec()
                 }
-                ts.staking_params.encode_to_vec()
             },
             proof: None,
         }];
@@ -918,23 +937,34 @@ fn test_delegations_reconstruct() {
                 proof: None,
             });
 
-            if let Some(v) = ts.validators.get(i) {
-                st_values.push(StorageValue {
-                    storage_prefix: STAKING_STORE_KEY.to_string(),
-                    key: create_validator_key(&val_addr).unwrap(),
-                    value: v.encode_to_vec(),
-                    proof: None,
-                });
-            }
+            // absent validator is represented by an empty value
+            st_values.push(StorageValue {
+                storage_prefix: STAKING_STORE_KEY.to_string(),
+                key: create_validator_key(&val_addr).unwrap(),
+                value: ts
+                    .validators
+                    .get(i)
+                    .map(|v| v.encode_to_vec())
+                    .unwrap_or_default(),
+                proof: None,
+            });
         }
 
         // test reconstruction
         let delegations = Delegations::reconstruct(&st_values);
 
-        assert_eq!(
-            delegations.as_ref().unwrap(),
-            ts.expected_result.as_ref().unwrap()
-        )
+        match (&delegations, &ts.expected_result) {
+            (Ok(val), Ok(expected)) => assert_eq!(val, expected),
+            (Err(err), Err(expected_err)) => {
+                // Compare error messages since NeutronError doesn't implement PartialEq
+                assert_eq!(err.to_string(), expected_err.to_string())
+            }
+            _ => panic!(
+                "Result type mismatch: got {:?}, expected {:?}",
+                delegations.is_ok(),
+                ts.expected_result.is_ok()
+            ),
+        }
     }
 }
 
@@ -1049,9 +1079,10 @@ fn test_delegations_reconstruct_overflow() {
             key: create_params_store_key(STAKING_STORE_KEY, KEY_BOND_DENOM),
             value: {
                 if ts.staking_params.bond_denom.is_empty() {
-                    return Default::default();
+                    vec![]
+                } else {
+                    ts.staking_params.encode_to_vec()
                 }
-                ts.staking_params.encode_to_vec()
             },
             proof: None,
         }];
@@ -1067,14 +1098,17 @@ fn test_delegations_reconstruct_overflow() {
                 proof: None,
             });
 
-            if let Some(v) = ts.validators.get(i) {
-                st_values.push(StorageValue {
-                    storage_prefix: STAKING_STORE_KEY.to_string(),
-                    key: create_validator_key(&val_addr).unwrap(),
-                    value: v.encode_to_vec(),
-                    proof: None,
-                });
-            }
+            // absent validator is represented by an empty value
+            st_values.push(StorageValue {
+                storage_prefix: STAKING_STORE_KEY.to_string(),
+                key: create_validator_key(&val_addr).unwrap(),
+                value: ts
+                    .validators
+                    .get(i)
+                    .map(|v| v.encode_to_vec())
+                    .unwrap_or_default(),
+                proof: None,
+            });
         }
 
         // test reconstruction
@@ -1121,9 +1155,9 @@ fn test_staking_validators_reconstruct_from_hex() {
                 details: Some(String::from("A testnet validator")),
                 unbonding_height: 1841908u64,
                 unbonding_time: Some(1590982547u64),
-                rate: Some(Decimal::from_str("0.200000000000000000").unwrap()),
-                max_rate: Some(Decimal::from_str("1.00000000000000000").unwrap()),
-                max_change_rate: Some(Decimal::from_str("1.000000000000000000").unwrap()),
+                rate: Some(LegacyDec::from_str("0.200000000000000000").unwrap()),
+                max_rate: Some(LegacyDec::from_str("1.00000000000000000").unwrap()),
+                max_change_rate: Some(LegacyDec::from_str("1.000000000000000000").unwrap()),
                 update_time: Some(1580862591u64), // mutating
                 min_self_delegation: Decimal::from_str("1000000").unwrap(),
             }]
@@ -1359,3 +1393,118 @@ fn test_unbonding_delegations_reconstruct_from_hex() {
         }
     );
 }
+
+#[test]
+fn test_kv_construct_round_trip() {
+    let delegator = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs";
+    let validator = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3";
+
+    let balances = Balances {
+        coins: vec![
+            StdCoin::new(494213561u128, "uatom"),
+            StdCoin::new(0u128, "uosmo"),
+        ],
+    };
+    assert_eq!(
+        Balances::reconstruct(&balances.construct(delegator).unwrap()).unwrap(),
+        balances
+    );
+
+    let delegations = Delegations {
+        delegations: vec![StdDelegation {
+            delegator: Addr::unchecked(delegator),
+            validator: validator.to_string(),
+            amount: StdCoin::new(5177628u128, "uatom"),
+        }],
+    };
+    let storage_values = delegations
+        .construct(&delegations_context("uatom", &[validator]))
+        .unwrap();
+    assert_eq!(
+        storage_values
+            .iter()
+            .map(|kv| (kv.storage_prefix.clone(), kv.key.clone()))
+            .collect::<Vec<_>>(),
+        create_delegations_query_keys(delegator.to_string(), vec![validator.to_string()])
+            .unwrap()
+            .into_iter()
+            .map(|k| (k.path, k.key))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Delegations::reconstruct(&storage_values).unwrap(),
+        delegations
+    );
+}
+
+#[test]
+fn test_reconstruct_ref() {
+    let delegator = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs";
+    let validator = "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3";
+
+    let balances = Balances {
+        coins: vec![
+            StdCoin::new(494213561u128, "uatom"),
+            StdCoin::new(0u128, "uosmo"),
+        ],
+    };
+    let mut storage_values = balances.construct(delegator).unwrap();
+    assert_eq!(
+        Balances::from(BalancesRef::reconstruct_ref(&storage_values).unwrap()),
+        balances
+    );
+
+    storage_values[0].value = vec![0xff];
+    assert_eq!(
+        BalancesRef::reconstruct_ref(&storage_values)
+            .unwrap_err()
+            .to_string(),
+        Balances::reconstruct(&storage_values)
+            .unwrap_err()
+            .to_string()
+    );
+
+    let delegations = Delegations {
+        delegations: vec![StdDelegation {
+            delegator: Addr::unchecked(delegator),
+            validator: validator.to_string(),
+            amount: StdCoin::new(5177628u128, "uatom"),
+        }],
+    };
+    let storage_values = delegations
+        .construct(&delegations_context("uatom", &[validator]))
+        .unwrap();
+    let delegations_ref = DelegationsRef::reconstruct_ref(&storage_values).unwrap();
+    assert!(matches!(delegations_ref.denom, Cow::Borrowed("uatom")));
+    assert_eq!(Delegations::from(delegations_ref), delegations);
+}
+
+#[test]
+fn test_update_delegations_query_keys() {
+    let delegator = "osmo1yz54ncxj9csp7un3xled03q6thrrhy9cztkfzs".to_string();
+    let validators = vec![
+        "osmovaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqny4gj2e3".to_string(),
+        "osmovaloper1lzhlnpahvznwfv4jmay2tgaha5kmz5qxwmj9we".to_string(),
+    ];
+
+    let keys = create_delegations_query_keys(delegator.clone(), vec![]).unwrap();
+    let updated =
+        update_delegations_query_keys(keys, delegator.clone(), validators.clone(), vec![]).unwrap();
+    assert_eq!(
+        updated,
+        create_delegations_query_keys(delegator.clone(), validators.clone()).unwrap()
+    );
+    // staking params key stays first
+    assert_eq!(updated[0].key, vec![STAKING_PARAMS_KEY]);
+
+    // keys of a Cosmos SDK 0.45 query have another staking denom key
+    let keys = crate::interchain_queries::v045::helpers::create_delegations_query_keys(
+        delegator.clone(),
+        validators.clone(),
+    )
+    .unwrap();
+    assert!(matches!(
+        update_delegations_query_keys(keys, delegator, vec![], validators),
+        Err(NeutronError::IncompatibleQueryKeys(_))
+    ));
+}
@@ -1,4 +1,3 @@
-use std::ops::Div;
 // import all types from v045 package
 // to make it available from v047 package (kinda proxy) since they work with Cosmos SDK 0.47 as usual
 pub use crate::interchain_queries::v045::types::*;
@@ -6,24 +5,39 @@ pub use crate::interchain_queries::v045::types::*;
 // But at the same time we replace some structs from v045 with structs below to create structures
 // compatible with Cosmos SDK 0.47
 
-use crate::interchain_queries::types::KVReconstruct;
+use crate::interchain_queries::address::{decode_address_of_kind, AddressKind};
+use crate::interchain_queries::proto_reader::ProtoReader;
+use crate::interchain_queries::types::{
+    invalid_storage_value, reconstruct_each_with_status, KVConstruct, KVReconstruct,
+    KVReconstructRef, KVReconstructWithStatus, KVStatus, KeyStatus,
+};
+use crate::interchain_queries::v045::types::{
+    construct_delegation_pairs, new_storage_value, reconstruct_delegation_pairs,
+    reconstruct_delegation_pairs_ref, reconstruct_delegations_with_status,
+    split_delegations_storage_values, DelegationsContext, BANK_STORE_KEY, STAKING_STORE_KEY,
+};
 use crate::{errors::error::NeutronResult, NeutronError};
 
-use crate::interchain_queries::v047::helpers::deconstruct_account_denom_balance_key;
-use cosmos_sdk_proto::cosmos::staking::v1beta1::{
-    Delegation, Params, Validator as CosmosValidator,
+use crate::interchain_queries::v047::helpers::{
+    create_account_denom_balance_key, deconstruct_account_denom_balance_key_ref,
 };
+use cosmos_sdk_proto::cosmos::staking::v1beta1::Params;
 use cosmos_sdk_proto::traits::Message;
-use cosmwasm_std::{Addr, Coin, Decimal256, Uint128, Uint256};
+use cosmwasm_std::{Coin, Uint128};
 use neutron_std::types::neutron::interchainqueries::StorageValue;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use std::borrow::Cow;
+use std::str::{from_utf8, FromStr};
 
 /// Key for Staking Params in the **staking** module's storage
 /// <https://github.com/cosmos/cosmos-sdk/blob/54120626e9994b2f1cc7a2bebc60cfb99703028f/x/staking/types/keys.go#L56>
 pub const STAKING_PARAMS_KEY: u8 = 0x51;
 
+/// Shapes of **StorageValue**'s expected by reconstructors, used in errors about malformed values
+const BALANCE_SHAPE: &str = "decimal amount under an account denom balance key";
+const STAKING_PARAMS_SHAPE: &str = "cosmos.staking.v1beta1.Params";
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 /// A structure that can be reconstructed from **StorageValues**'s for the **Balance Interchain Query**.
 /// Contains amounts of coins that are held by some account on remote chain.
@@ -31,25 +45,114 @@ pub struct Balances {
     pub coins: Vec<Coin>,
 }
 
+/// Reconstructs balance of a single denom, an empty value means zero balance
+fn reconstruct_balance(kv: &StorageValue) -> NeutronResult<Coin> {
+    let (_, denom) = deconstruct_account_denom_balance_key_ref(&kv.key)?;
+    let amount = if kv.value.is_empty() {
+        Uint128::zero()
+    } else {
+        Uint128::from_str(from_utf8(&kv.value)?)?
+    };
+
+    Ok(Coin::new(amount.u128(), denom))
+}
+
 impl KVReconstruct for Balances {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<Balances> {
         let mut coins: Vec<Coin> = Vec::with_capacity(storage_values.len());
 
-        for kv in storage_values {
-            let (_, denom) = deconstruct_account_denom_balance_key(kv.key.to_vec())?;
-            let amount = if kv.value.is_empty() {
-                Uint128::zero()
-            } else {
-                Uint128::from_str(&String::from_utf8(kv.value.to_vec())?)?
-            };
-
-            coins.push(Coin::new(amount.u128(), denom))
+        for (i, kv) in storage_values.iter().enumerate() {
+            coins.push(
+                reconstruct_balance(kv).map_err(|e| invalid_storage_value(i, BALANCE_SHAPE, e))?,
+            )
         }
 
         Ok(Balances { coins })
     }
 }
 
+impl KVConstruct for Balances {
+    /// Bech32 address of the account which holds the balances
+    type Context = str;
+
+    fn construct(&self, address: &str) -> NeutronResult<Vec<StorageValue>> {
+        let address = decode_address_of_kind(address, AddressKind::Account)?;
+
+        self.coins
+            .iter()
+            .map(|coin| {
+                // zero balances are not stored on chain
+                let value = if coin.amount.is_zero() {
+                    vec![]
+                } else {
+                    coin.amount.to_string().into_bytes()
+                };
+
+                Ok(new_storage_value(
+                    BANK_STORE_KEY,
+                    create_account_denom_balance_key(&address, &coin.denom)?,
+                    value,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl KVReconstructWithStatus for Balances {
+    fn reconstruct_with_status(
+        storage_values: &[StorageValue],
+    ) -> NeutronResult<(Balances, Vec<KeyStatus>)> {
+        let (coins, statuses) = reconstruct_each_with_status(storage_values, reconstruct_balance);
+
+        Ok((Balances { coins }, statuses))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A zero-copy counterpart of `Balances` for the **Balance Interchain Query**.
+/// Denoms are borrowed from storage keys.
+pub struct BalancesRef<'a> {
+    pub coins: Vec<CoinRef<'a>>,
+}
+
+impl From<BalancesRef<'_>> for Balances {
+    fn from(balances: BalancesRef) -> Balances {
+        Balances {
+            coins: balances.coins.into_iter().map(Coin::from).collect(),
+        }
+    }
+}
+
+impl<'a> KVReconstructRef<'a> for BalancesRef<'a> {
+    fn reconstruct_ref(storage_values: &'a [StorageValue]) -> NeutronResult<BalancesRef<'a>> {
+        let mut coins: Vec<CoinRef> = Vec::with_capacity(storage_values.len());
+
+        for (i, kv) in storage_values.iter().enumerate() {
+            coins.push(
+                reconstruct_balance_ref(kv)
+                    .map_err(|e| invalid_storage_value(i, BALANCE_SHAPE, e))?,
+            )
+        }
+
+        Ok(BalancesRef { coins })
+    }
+}
+
+/// Reconstructs balance of a single denom without copying, an empty value means zero balance
+fn reconstruct_balance_ref(kv: &StorageValue) -> NeutronResult<CoinRef<'_>> {
+    let (_, denom) = deconstruct_account_denom_balance_key_ref(&kv.key)?;
+    let amount = if kv.value.is_empty() {
+        Uint128::zero()
+    } else {
+        Uint128::from_str(from_utf8(&kv.value)?)?
+    };
+
+    Ok(CoinRef {
+        denom,
+        amount: amount.into(),
+    })
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 /// A structure that can be reconstructed from **StorageValues**'s for the **Delegator Delegation Interchain Query**.
 /// Contains delegations which some delegator has on remote chain.
@@ -59,77 +162,134 @@ pub struct Delegations {
 
 impl KVReconstruct for Delegations {
     fn reconstruct(storage_values: &[StorageValue]) -> NeutronResult<Delegations> {
-        // We are taking 2 items chunks from starage_value to calculate one delegation
-        let mut delegations: Vec<StdDelegation> = Vec::with_capacity(storage_values.len() / 2);
+        // first StorageValue is staking params, the rest are delegations and validators alternately
+        let (params_kv, pairs) =
+            split_delegations_storage_values(storage_values, "staking params")?;
 
-        if storage_values.is_empty() {
-            return Err(NeutronError::InvalidQueryResultFormat(
-                "storage_values length is 0".into(),
-            ));
-        }
-        // first StorageValue is staking params
-        if storage_values[0].value.is_empty() {
+        if params_kv.value.is_empty() {
             // Incoming params cannot be empty, it should always be configured on chain.
             // If we receive empty params, that means incoming data structure is corrupted
             // and we cannot build `cosmwasm_std::Delegation`'s using this data.
-            return Err(NeutronError::InvalidQueryResultFormat(
-                "params is empty".into(),
+            return Err(invalid_storage_value(
+                0,
+                STAKING_PARAMS_SHAPE,
+                "value is empty",
             ));
         }
-        let params: Params = Params::decode(storage_values[0].value.as_slice())?;
-
-        // the rest are delegations and validators alternately
-        for chunk in storage_values[1..].chunks(2) {
-            if chunk[0].value.is_empty() {
-                // Incoming delegation can actually be empty, this just means that delegation
-                // is not present on remote chain, which is to be expected. So, if it doesn't
-                // exist, we can safely skip this and following chunk.
-                continue;
-            }
-            let delegation_sdk: Delegation = Delegation::decode(chunk[0].value.as_slice())?;
-
-            let mut delegation_std = StdDelegation {
-                delegator: Addr::unchecked(delegation_sdk.delegator_address.as_str()),
-                validator: delegation_sdk.validator_address,
-                amount: Default::default(),
-            };
-
-            if chunk[1].value.is_empty() {
-                // At this point, incoming validator cannot be empty, that would be invalid,
-                // because delegation is already defined, so, building `cosmwasm_std::Delegation`
-                // from this data is impossible, incoming data is corrupted.post
-                return Err(NeutronError::InvalidQueryResultFormat(
-                    "validator is empty".into(),
-                ));
-            }
-            let validator: CosmosValidator = CosmosValidator::decode(chunk[1].value.as_slice())?;
-
-            let delegation_shares = Decimal256::from_atomics(
-                Uint256::from_str(&delegation_sdk.shares)?,
-                DECIMAL_PLACES,
-            )?;
-
-            let delegator_shares = Decimal256::from_atomics(
-                Uint256::from_str(&validator.delegator_shares)?,
-                DECIMAL_PLACES,
-            )?;
-
-            let validator_tokens =
-                Decimal256::from_atomics(Uint128::from_str(&validator.tokens)?, 0)?;
-
-            // https://github.com/cosmos/cosmos-sdk/blob/35ae2c4c72d4aeb33447d5a7af23ca47f786606e/x/staking/keeper/querier.go#L463
-            // delegated_tokens = quotient(delegation.shares * validator.tokens / validator.total_shares);
-            let delegated_tokens = delegation_shares
-                .checked_mul(validator_tokens)?
-                .div(delegator_shares)
-                .atomics()
-                .div(Uint256::new(DECIMAL_FRACTIONAL));
-
-            delegation_std.amount = Coin::new(delegated_tokens, &params.bond_denom);
-
-            delegations.push(delegation_std);
+        let params: Params = Params::decode(params_kv.value.as_slice())
+            .map_err(|e| invalid_storage_value(0, STAKING_PARAMS_SHAPE, e))?;
+
+        Ok(Delegations {
+            delegations: reconstruct_delegation_pairs(pairs, &params.bond_denom)?,
+        })
+    }
+}
+
+impl KVConstruct for Delegations {
+    /// Bond denom is queried along with delegations as a part of staking params
+    type Context = DelegationsContext;
+
+    fn construct(&self, context: &DelegationsContext) -> NeutronResult<Vec<StorageValue>> {
+        let params = Params {
+            bond_denom: context.denom.clone(),
+            ..Default::default()
+        };
+
+        let mut storage_values = vec![new_storage_value(
+            STAKING_STORE_KEY,
+            vec![STAKING_PARAMS_KEY],
+            params.encode_to_vec(),
+        )];
+        storage_values.extend(construct_delegation_pairs(
+            &self.delegations,
+            &context.validators,
+        )?);
+
+        Ok(storage_values)
+    }
+}
+
+impl KVReconstructWithStatus for Delegations {
+    fn reconstruct_with_status(
+        storage_values: &[StorageValue],
+    ) -> NeutronResult<(Delegations, Vec<KeyStatus>)> {
+        let (params_kv, rest) = storage_values.split_first().ok_or_else(|| {
+            NeutronError::InvalidQueryResultFormat("storage_values length is 0".into())
+        })?;
+
+        // first StorageValue is staking params
+        let denom = if params_kv.value.is_empty() {
+            Err(KVStatus::Absent)
+        } else {
+            Params::decode(params_kv.value.as_slice())
+                .map(|params| params.bond_denom)
+                .map_err(|e| KVStatus::Malformed(e.to_string()))
+        };
+
+        let (delegations, statuses) = reconstruct_delegations_with_status(params_kv, denom, rest);
+
+        Ok((
+            Delegations {
+                delegations: delegations.delegations,
+            },
+            statuses,
+        ))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A zero-copy counterpart of `Delegations` for the **Delegator Delegation Interchain Query**.
+/// The bond denom is stored once instead of being copied into every delegation.
+pub struct DelegationsRef<'a> {
+    pub denom: Cow<'a, str>,
+    pub delegations: Vec<DelegationRef<'a>>,
+}
+
+impl From<DelegationsRef<'_>> for Delegations {
+    fn from(delegations: DelegationsRef) -> Delegations {
+        Delegations {
+            delegations: delegations
+                .delegations
+                .iter()
+                .map(|delegation| delegation.to_std_delegation(&delegations.denom))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> KVReconstructRef<'a> for DelegationsRef<'a> {
+    fn reconstruct_ref(storage_values: &'a [StorageValue]) -> NeutronResult<DelegationsRef<'a>> {
+        // first StorageValue is staking params, the rest are delegations and validators alternately
+        let (params_kv, pairs) =
+            split_delegations_storage_values(storage_values, "staking params")?;
+
+        if params_kv.value.is_empty() {
+            return Err(invalid_storage_value(
+                0,
+                STAKING_PARAMS_SHAPE,
+                "value is empty",
+            ));
         }
+        let denom = read_bond_denom(&params_kv.value)
+            .map_err(|e| invalid_storage_value(0, STAKING_PARAMS_SHAPE, e))?;
+
+        Ok(DelegationsRef {
+            denom: Cow::Borrowed(denom),
+            delegations: reconstruct_delegation_pairs_ref(pairs)?,
+        })
+    }
+}
+
+/// Reads bond denom of `cosmos.staking.v1beta1.Params`, the rest of the fields are skipped
+fn read_bond_denom(value: &[u8]) -> NeutronResult<&str> {
+    let mut bond_denom = "";
 
-        Ok(Delegations { delegations })
+    let mut reader = ProtoReader::new(value);
+    while let Some((number, field)) = reader.next_field()? {
+        if number == 5 {
+            bond_denom = field.str()?;
+        }
     }
+
+    Ok(bond_denom)
 }
@@ -1,5 +1,7 @@
+use crate::interchain_txs::ica_tx::msg_response_type_url;
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxMsgData;
 use cosmos_sdk_proto::traits::Message;
-use cosmwasm_std::{Addr, CosmosMsg, Deps, StdError, StdResult};
+use cosmwasm_std::{Addr, Binary, CosmosMsg, Deps, StdError, StdResult};
 use neutron_std::shim::Any;
 use neutron_std::types::cosmos::base::v1beta1::Coin;
 use neutron_std::types::ibc::core::channel::v1::Order;
@@ -15,6 +17,54 @@ pub fn decode_message_response<T: Message + Default>(item: &Vec<u8>) -> StdResul
     }
 }
 
+/// Format of an ICA acknowledgement detected by `decode_tx_msg_data_with_format`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AckFormat {
+    /// Responses are in the deprecated `data` field, as Cosmos SDK 0.45 chains send them
+    MsgData,
+    /// Responses are in the `msg_responses` field, as Cosmos SDK 0.46+ chains send them
+    MsgResponses,
+    /// There are no responses in the acknowledgement
+    Empty,
+}
+
+/// Decodes acknowledgement of a chain of any Cosmos SDK version into responses of the executed
+/// messages. Responses from the deprecated `data` field are converted into `Any` with the type URL
+/// of the message response, e.g. `/cosmos.staking.v1beta1.MsgDelegateResponse`, the same as
+/// they are in `msg_responses`.
+pub fn decode_tx_msg_data(data: Binary) -> StdResult<Vec<prost_types::Any>> {
+    decode_tx_msg_data_with_format(data).map(|(responses, _)| responses)
+}
+
+/// Same as `decode_tx_msg_data` but also returns the format of the acknowledgement.
+/// `msg_responses` take precedence if both fields are set.
+pub fn decode_tx_msg_data_with_format(
+    data: Binary,
+) -> StdResult<(Vec<prost_types::Any>, AckFormat)> {
+    let msg_data = TxMsgData::decode(data.as_slice())
+        .map_err(|e| StdError::msg(format!("Can't decode response: {}", e)))?;
+    if !msg_data.msg_responses.is_empty() {
+        return Ok((msg_data.msg_responses, AckFormat::MsgResponses));
+    }
+
+    #[allow(deprecated)]
+    let legacy_data = msg_data.data;
+    if legacy_data.is_empty() {
+        return Ok((vec![], AckFormat::Empty));
+    }
+
+    Ok((
+        legacy_data
+            .into_iter()
+            .map(|msg_data| prost_types::Any {
+                type_url: msg_response_type_url(&msg_data.msg_type),
+                value: msg_data.data,
+            })
+            .collect(),
+        AckFormat::MsgData,
+    ))
+}
+
 const CONTROLLER_PORT_PREFIX: &str = "icacontroller-";
 const ICA_OWNER_DELIMITER: &str = ".";
 
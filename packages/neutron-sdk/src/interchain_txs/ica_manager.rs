@@ -0,0 +1,306 @@
+//! Storage-backed lifecycle of interchain accounts.
+//!
+//! A contract registers an interchain account with `register_interchain_account` and learns its
+//! address from the `OpenAck` sudo call. If a packet on an ORDERED channel times out, the channel is
+//! closed and the account has to be registered again before it can execute transactions. `IcaManager`
+//! keeps an `IcaRecord` of every interchain account under its controller port and moves it through
+//! the `IcaState`'s:
+//! ```text
+//! Registering --OpenAck--> Open --timeout--> Closed --register--> Reregistering --OpenAck--> Open
+//! ```
+//! A pending registration can be sent again with `register` if the channel handshake never
+//! completes, the state doesn't change then.
+//! The channel is still open while the `Timeout` sudo call is handled, it's closed by IBC right
+//! after it, so the account can't be registered again from `sudo`: the registration fails because
+//! the port still has an active channel, and the whole sudo call is reverted with it. A contract
+//! registers a closed account again from `execute`, e.g. before submitting the next transaction:
+//! ```rust ignore
+//! const ICAS: IcaManager = IcaManager::new("icas");
+//!
+//! // execute, both to register an account for the first time and after its channel is closed
+//! let msg = ICAS.register(deps.storage, env.contract.address, connection_id, ica_id, fee, None)?;
+//! // sudo
+//! SudoMsg::OpenAck { port_id, channel_id, counterparty_channel_id, counterparty_version } => {
+//!     ICAS.on_open_ack(deps.storage, &port_id, channel_id, counterparty_channel_id, &counterparty_version)?;
+//! }
+//! SudoMsg::Timeout { request } => {
+//!     ICAS.on_timeout(deps.storage, &request)?;
+//! }
+//! ```
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_txs::helpers::{get_port_id, register_interchain_account};
+use crate::sudo::msg::{IcaMetadata, RequestPacket};
+use cosmwasm_std::{Addr, CosmosMsg, Order as StorageOrder, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+use neutron_std::types::cosmos::base::v1beta1::Coin;
+use neutron_std::types::ibc::core::channel::v1::Order;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Default number of records returned by `IcaManager::range`
+pub const DEFAULT_ICAS_LIMIT: u32 = 10;
+
+/// Max number of records returned by `IcaManager::range`
+pub const MAX_ICAS_LIMIT: u32 = 100;
+
+/// State of an interchain account tracked by `IcaManager`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IcaState {
+    /// Registration message is sent, waiting for `OpenAck`
+    Registering,
+    /// Channel is open, the account can execute transactions
+    Open,
+    /// ORDERED channel is closed after a packet timeout
+    Closed,
+    /// Registration message is sent again after the channel was closed, waiting for `OpenAck`
+    Reregistering,
+}
+
+/// Interchain account tracked by `IcaManager`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IcaRecord {
+    pub interchain_account_id: String,
+    pub connection_id: String,
+    /// ICA controller port of the account, see `get_port_id`
+    pub port_id: String,
+    pub state: IcaState,
+    /// address of the account on the remote chain, known after the first `OpenAck`
+    pub address: Option<String>,
+    /// channel of the account on Neutron side, known after `OpenAck`
+    pub channel_id: Option<String>,
+    /// channel of the account on the remote chain side, known after `OpenAck`
+    pub counterparty_channel_id: Option<String>,
+    pub ordering: Order,
+    /// fee the account was registered with
+    pub register_fee: Vec<Coin>,
+}
+
+impl IcaRecord {
+    /// Returns true if the account can execute transactions
+    pub fn is_open(&self) -> bool {
+        self.state == IcaState::Open
+    }
+}
+
+/// Keeps `IcaRecord`'s of interchain accounts of a contract, see the module docs
+pub struct IcaManager {
+    accounts: Map<String, IcaRecord>,
+}
+
+impl IcaManager {
+    /// Creates a manager which keeps records under **namespace**
+    pub const fn new(namespace: &'static str) -> Self {
+        IcaManager {
+            accounts: Map::new(namespace),
+        }
+    }
+
+    /// Returns a message to register an interchain account and starts tracking it.
+    /// An account can be registered unless its channel is open: if it's not tracked yet, its
+    /// channel is closed or a registration is pending, e.g. the channel handshake never completed
+    /// and no `OpenAck` is going to come, see `register_interchain_account` for the arguments.
+    pub fn register(
+        &self,
+        storage: &mut dyn Storage,
+        contract: Addr,
+        connection_id: String,
+        interchain_account_id: String,
+        register_fee: Vec<Coin>,
+        ordering: Option<Order>,
+    ) -> NeutronResult<CosmosMsg> {
+        let port_id = get_port_id(contract.as_str(), &interchain_account_id);
+        let (state, address) = match self.accounts.may_load(storage, port_id.clone())? {
+            None => (IcaState::Registering, None),
+            Some(record) if record.state != IcaState::Open => (
+                match record.state {
+                    IcaState::Registering => IcaState::Registering,
+                    _ => IcaState::Reregistering,
+                },
+                // the same account is reopened on the same connection
+                record
+                    .address
+                    .filter(|_| record.connection_id == connection_id),
+            ),
+            Some(record) => {
+                return Err(NeutronError::UnexpectedIcaState {
+                    port_id,
+                    state: format!("{:?}", record.state),
+                })
+            }
+        };
+
+        let record = IcaRecord {
+            interchain_account_id,
+            connection_id,
+            port_id: port_id.clone(),
+            state,
+            address,
+            channel_id: None,
+            counterparty_channel_id: None,
+            ordering: ordering.unwrap_or(Order::Ordered),
+            register_fee,
+        };
+        self.accounts.save(storage, port_id, &record)?;
+
+        Ok(register_msg(contract, &record))
+    }
+
+    /// Records the address and channels of the account on **port_id** from an `OpenAck`
+    /// sudo call and opens it
+    pub fn on_open_ack(
+        &self,
+        storage: &mut dyn Storage,
+        port_id: &str,
+        channel_id: String,
+        counterparty_channel_id: String,
+        counterparty_version: &str,
+    ) -> NeutronResult<IcaRecord> {
+        let mut record = self.load_by_port(storage, port_id)?;
+        if !matches!(
+            record.state,
+            IcaState::Registering | IcaState::Reregistering
+        ) {
+            return Err(NeutronError::UnexpectedIcaState {
+                port_id: port_id.to_string(),
+                state: format!("{:?}", record.state),
+            });
+        }
+
+        let metadata = IcaMetadata::parse(counterparty_version)?;
+        // the connection of an account is unknown if it was tracked before the manager was used
+        if record.connection_id.is_empty() {
+            record.connection_id = metadata.controller_connection_id;
+        } else if record.connection_id != metadata.controller_connection_id {
+            return Err(NeutronError::InvalidIcaMetadata(format!(
+                "controller connection {:?} is not the connection {:?} of the account",
+                metadata.controller_connection_id, record.connection_id
+            )));
+        }
+        record.state = IcaState::Open;
+        record.address = Some(metadata.address);
+        record.channel_id = Some(channel_id);
+        record.counterparty_channel_id = Some(counterparty_channel_id);
+        self.accounts.save(storage, port_id.to_string(), &record)?;
+
+        Ok(record)
+    }
+
+    /// Closes the account which packet in **request** has timed out if its channel is ORDERED and
+    /// returns its record. The account can be registered again with `register` once the `Timeout`
+    /// sudo call is handled, see the module docs.
+    /// Timeouts of packets on UNORDERED channels and on ports the manager doesn't track are ignored.
+    pub fn on_timeout(
+        &self,
+        storage: &mut dyn Storage,
+        request: &RequestPacket,
+    ) -> NeutronResult<Option<IcaRecord>> {
+        let Some(port_id) = request.source_port.as_ref() else {
+            return Ok(None);
+        };
+        let Some(mut record) = self.accounts.may_load(storage, port_id.clone())? else {
+            return Ok(None);
+        };
+        if record.ordering != Order::Ordered || record.state != IcaState::Open {
+            return Ok(None);
+        }
+
+        record.state = IcaState::Closed;
+        record.channel_id = None;
+        record.counterparty_channel_id = None;
+        self.accounts.save(storage, port_id.clone(), &record)?;
+
+        Ok(Some(record))
+    }
+
+    /// Loads the record of the account with **interchain_account_id** owned by **contract**
+    pub fn load(
+        &self,
+        storage: &dyn Storage,
+        contract: &Addr,
+        interchain_account_id: &str,
+    ) -> NeutronResult<IcaRecord> {
+        self.load_by_port(
+            storage,
+            &get_port_id(contract.as_str(), interchain_account_id),
+        )
+    }
+
+    /// Loads the record of the account on **port_id**
+    pub fn load_by_port(&self, storage: &dyn Storage, port_id: &str) -> NeutronResult<IcaRecord> {
+        self.accounts
+            .may_load(storage, port_id.to_string())?
+            .ok_or_else(|| NeutronError::IcaNotFound {
+                port_id: port_id.to_string(),
+            })
+    }
+
+    /// Loads the record of the account on **port_id** if it's tracked
+    pub fn may_load_by_port(
+        &self,
+        storage: &dyn Storage,
+        port_id: &str,
+    ) -> StdResult<Option<IcaRecord>> {
+        self.accounts.may_load(storage, port_id.to_string())
+    }
+
+    /// Returns the remote address of the account with **interchain_account_id** owned by
+    /// **contract** if the account is open, i.e. can execute transactions
+    pub fn address(
+        &self,
+        storage: &dyn Storage,
+        contract: &Addr,
+        interchain_account_id: &str,
+    ) -> NeutronResult<String> {
+        let record = self.load(storage, contract, interchain_account_id)?;
+        match record.address {
+            Some(address) if record.state == IcaState::Open => Ok(address),
+            _ => Err(NeutronError::UnexpectedIcaState {
+                port_id: record.port_id,
+                state: format!("{:?}", record.state),
+            }),
+        }
+    }
+
+    /// Returns up to **limit** records ordered by port and starting after **start_after** port
+    pub fn range(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<IcaRecord>> {
+        let limit = limit.unwrap_or(DEFAULT_ICAS_LIMIT).min(MAX_ICAS_LIMIT) as usize;
+
+        self.accounts
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                StorageOrder::Ascending,
+            )
+            .take(limit)
+            .map(|item| item.map(|(_, record)| record))
+            .collect()
+    }
+
+    /// Saves **record** as it is, e.g. to migrate accounts a contract tracked before using the manager
+    pub fn save(&self, storage: &mut dyn Storage, record: &IcaRecord) -> StdResult<()> {
+        self.accounts.save(storage, record.port_id.clone(), record)
+    }
+
+    /// Stops tracking the account on **port_id**
+    pub fn remove(&self, storage: &mut dyn Storage, port_id: &str) {
+        self.accounts.remove(storage, port_id.to_string());
+    }
+}
+
+fn register_msg(contract: Addr, record: &IcaRecord) -> CosmosMsg {
+    register_interchain_account(
+        contract,
+        record.connection_id.clone(),
+        record.interchain_account_id.clone(),
+        record.register_fee.clone(),
+        Some(record.ordering),
+    )
+}
@@ -0,0 +1,188 @@
+//! Builder of interchain transactions executed by interchain accounts.
+//!
+//! Messages of an interchain transaction are protobuf `Any`'s, and building them by hand makes it
+//! easy to put a wrong type URL, e.g. a type URL of a response instead of a type URL of a message,
+//! which is only noticed when the transaction fails on the remote chain. `IcaTx` derives type URLs
+//! from message types and returns the `submit_tx` message together with `IcaTxPayload` describing
+//! every message, to be saved by a contract and used to decode the acknowledgement later:
+//! ```rust ignore
+//! let (msg, payload) = IcaTx::new(connection_id, interchain_account_id, timeout)
+//!     .add_std_msg(MsgDelegate { .. })?
+//!     .with_fee(fee)
+//!     .build(env.contract.address)?;
+//! ```
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_txs::helpers::submit_tx;
+use cosmos_sdk_proto::traits::TypeUrl;
+use cosmwasm_std::{Addr, AnyMsg, CosmosMsg};
+use neutron_std::shim::Any;
+use neutron_std::types::neutron::feerefunder::Fee;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Default max number of messages in an interchain transaction,
+/// the default `msg_submit_tx_max_messages` param of the Interchain Transactions Module
+pub const DEFAULT_MAX_ICA_TX_MSGS: usize = 16;
+
+/// Returns type URL of a response of a message with **msg_type_url** executed by a Msg service,
+/// e.g. `/cosmos.staking.v1beta1.MsgDelegateResponse` for `/cosmos.staking.v1beta1.MsgDelegate`
+pub fn msg_response_type_url(msg_type_url: &str) -> String {
+    format!("{}Response", msg_type_url)
+}
+
+/// Message of an interchain transaction as it's described in `IcaTxPayload`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IcaTxMsgInfo {
+    /// type URL of the message
+    pub type_url: String,
+    /// type URL of a response to the message in the acknowledgement
+    pub response_type_url: String,
+}
+
+/// Description of an interchain transaction built with `IcaTx`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IcaTxPayload {
+    pub connection_id: String,
+    pub interchain_account_id: String,
+    /// messages of the transaction in the order they are executed,
+    /// which is the order of their responses in the acknowledgement
+    pub msgs: Vec<IcaTxMsgInfo>,
+}
+
+/// Builder of an interchain transaction, see the module docs
+#[derive(Clone, Debug, PartialEq)]
+pub struct IcaTx {
+    connection_id: String,
+    interchain_account_id: String,
+    msgs: Vec<Any>,
+    memo: String,
+    timeout: u64,
+    fee: Option<Fee>,
+    max_msgs: usize,
+}
+
+impl IcaTx {
+    /// Creates an empty transaction to be executed by an interchain account with
+    /// **interchain_account_id** on the other side of a connection with **connection_id**.
+    ///
+    /// * **timeout** is a timeout in seconds after which the packet times out.
+    pub fn new(
+        connection_id: impl Into<String>,
+        interchain_account_id: impl Into<String>,
+        timeout: u64,
+    ) -> Self {
+        IcaTx {
+            connection_id: connection_id.into(),
+            interchain_account_id: interchain_account_id.into(),
+            msgs: vec![],
+            memo: "".to_string(),
+            timeout,
+            fee: None,
+            max_msgs: DEFAULT_MAX_ICA_TX_MSGS,
+        }
+    }
+
+    /// Adds a `cosmos_sdk_proto` message, or any other message implementing its `TypeUrl` trait
+    pub fn add_msg<M: TypeUrl>(mut self, msg: &M) -> Self {
+        self.msgs.push(Any {
+            type_url: M::TYPE_URL.to_string(),
+            value: msg.encode_to_vec(),
+        });
+        self
+    }
+
+    /// Adds a `neutron_std` message, which type URL is known from its conversion into `CosmosMsg`
+    pub fn add_std_msg<M: Into<CosmosMsg>>(mut self, msg: M) -> NeutronResult<Self> {
+        let msg: CosmosMsg = msg.into();
+        match msg {
+            CosmosMsg::Any(AnyMsg { type_url, value }) => {
+                self.msgs.push(Any {
+                    type_url,
+                    value: value.to_vec(),
+                });
+                Ok(self)
+            }
+            msg => Err(NeutronError::InvalidIcaTxMsg(format!(
+                "expected a protobuf message, got {:?}",
+                msg
+            ))),
+        }
+    }
+
+    /// Adds a message already encoded into `Any`
+    pub fn add_any(mut self, msg: Any) -> Self {
+        self.msgs.push(msg);
+        self
+    }
+
+    /// Sets a memo of the transaction, it behaves like a memo in usual Cosmos transaction
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = memo.into();
+        self
+    }
+
+    /// Sets a fee used for different kinds of callbacks, see `submit_tx`
+    pub fn with_fee(mut self, fee: Option<Fee>) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    /// Sets max number of messages in the transaction, [DEFAULT_MAX_ICA_TX_MSGS] by default
+    pub fn with_max_msgs(mut self, max_msgs: usize) -> Self {
+        self.max_msgs = max_msgs;
+        self
+    }
+
+    /// Returns messages added to the transaction
+    pub fn msgs(&self) -> &[Any] {
+        &self.msgs
+    }
+
+    /// Returns description of the transaction to decode its acknowledgement later
+    pub fn payload(&self) -> IcaTxPayload {
+        IcaTxPayload {
+            connection_id: self.connection_id.clone(),
+            interchain_account_id: self.interchain_account_id.clone(),
+            msgs: self
+                .msgs
+                .iter()
+                .map(|msg| IcaTxMsgInfo {
+                    type_url: msg.type_url.clone(),
+                    response_type_url: msg_response_type_url(&msg.type_url),
+                })
+                .collect(),
+        }
+    }
+
+    /// Checks the number of messages and returns a message to submit the transaction
+    /// together with its description.
+    ///
+    /// * **contract** is a contract that is sending the message.
+    pub fn build(self, contract: Addr) -> NeutronResult<(CosmosMsg, IcaTxPayload)> {
+        if self.msgs.is_empty() {
+            return Err(NeutronError::InvalidIcaTxMsg(
+                "interchain transaction has no messages".to_string(),
+            ));
+        }
+        if self.msgs.len() > self.max_msgs {
+            return Err(NeutronError::TooManyIcaTxMsgs {
+                max: self.max_msgs,
+                actual: self.msgs.len(),
+            });
+        }
+
+        let payload = self.payload();
+        let msg = submit_tx(
+            contract,
+            self.connection_id,
+            self.interchain_account_id,
+            self.msgs,
+            self.memo,
+            self.timeout,
+            self.fee,
+        );
+
+        Ok((msg, payload))
+    }
+}
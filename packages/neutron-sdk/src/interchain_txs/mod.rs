@@ -1,4 +1,10 @@
 pub mod helpers;
+pub mod ica_manager;
+pub mod ica_tx;
+pub mod responses;
 
 pub mod v045;
 pub mod v047;
+
+#[cfg(test)]
+mod testing;
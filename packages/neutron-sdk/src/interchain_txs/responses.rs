@@ -0,0 +1,208 @@
+//! Typed responses of messages executed by interchain accounts.
+//!
+//! An ICA acknowledgement carries a protobuf `Any` response for every executed message, and
+//! matching them on type URL strings by hand is error-prone. `AckDecoder` decodes responses of
+//! well-known messages into `IcaMsgResponse` variants and responses of types registered by a contract
+//! into `IcaMsgResponse::Custom`. Responses are lined up by index with the messages that were sent,
+//! as described by `IcaTxPayload`:
+//! ```rust ignore
+//! let responses = AckDecoder::<Empty>::new().decode_aligned(&msg_responses, &payload)?;
+//! for (msg, response) in responses {
+//!     if let IcaMsgResponse::MsgUndelegate { completion_time } = response { .. }
+//! }
+//! ```
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::interchain_queries::v045::types::checked_timestamp;
+use crate::interchain_txs::helpers::decode_tx_msg_data;
+use crate::interchain_txs::ica_tx::{IcaTxMsgInfo, IcaTxPayload};
+use cosmos_sdk_proto::cosmos::distribution::v1beta1::MsgWithdrawDelegatorRewardResponse;
+use cosmos_sdk_proto::cosmos::staking::v1beta1::{
+    MsgBeginRedelegateResponse, MsgUndelegateResponse,
+};
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::{Binary, Coin, Empty, Timestamp, Uint256};
+use prost_types::Any;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Type URL of `MsgSend` response
+pub const MSG_SEND_RESPONSE_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSendResponse";
+/// Type URL of `MsgDelegate` response
+pub const MSG_DELEGATE_RESPONSE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgDelegateResponse";
+/// Type URL of `MsgUndelegate` response
+pub const MSG_UNDELEGATE_RESPONSE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgUndelegateResponse";
+/// Type URL of `MsgBeginRedelegate` response
+pub const MSG_BEGIN_REDELEGATE_RESPONSE_TYPE_URL: &str =
+    "/cosmos.staking.v1beta1.MsgBeginRedelegateResponse";
+/// Type URL of `MsgWithdrawDelegatorReward` response
+pub const MSG_WITHDRAW_DELEGATOR_REWARD_RESPONSE_TYPE_URL: &str =
+    "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorRewardResponse";
+/// Type URL of IBC `MsgTransfer` response
+pub const MSG_TRANSFER_RESPONSE_TYPE_URL: &str =
+    "/ibc.applications.transfer.v1.MsgTransferResponse";
+/// Type URL of CosmWasm `MsgExecuteContract` response
+pub const MSG_EXECUTE_CONTRACT_RESPONSE_TYPE_URL: &str =
+    "/cosmwasm.wasm.v1.MsgExecuteContractResponse";
+
+/// IBC MsgTransferResponse protobuf type
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgTransferResponse {
+    #[prost(uint64, tag = "1")]
+    pub sequence: u64,
+}
+
+/// CosmWasm MsgExecuteContractResponse protobuf type
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgExecuteContractResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: Vec<u8>,
+}
+
+/// Response of a message executed by an interchain account.
+/// **C** is a type of responses of custom messages registered in `AckDecoder`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IcaMsgResponse<C = Empty> {
+    MsgSend,
+    MsgDelegate,
+    MsgUndelegate {
+        completion_time: Option<Timestamp>,
+    },
+    MsgBeginRedelegate {
+        completion_time: Option<Timestamp>,
+    },
+    MsgWithdrawDelegatorReward {
+        amount: Vec<Coin>,
+    },
+    MsgTransfer {
+        sequence: u64,
+    },
+    MsgExecuteContract {
+        data: Binary,
+    },
+    /// Response of a type registered with `AckDecoder::register`
+    Custom(C),
+    /// Response of a type `AckDecoder` doesn't know
+    Unknown(Any),
+}
+
+/// Decodes a custom response from its protobuf encoded value
+pub type ResponseDecoder<C> = fn(&[u8]) -> NeutronResult<C>;
+
+/// Decodes ICA acknowledgements into `IcaMsgResponse`'s, see the module docs
+pub struct AckDecoder<C = Empty> {
+    custom: BTreeMap<String, ResponseDecoder<C>>,
+}
+
+impl<C> Default for AckDecoder<C> {
+    fn default() -> Self {
+        AckDecoder {
+            custom: BTreeMap::new(),
+        }
+    }
+}
+
+impl<C> AckDecoder<C> {
+    /// Creates a decoder of the well-known responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers **decoder** of responses with **type_url**, decoded responses are returned as
+    /// `IcaMsgResponse::Custom`. A decoder registered for a well-known type URL takes precedence.
+    pub fn register(mut self, type_url: impl Into<String>, decoder: ResponseDecoder<C>) -> Self {
+        self.custom.insert(type_url.into(), decoder);
+        self
+    }
+
+    /// Decodes a single **response**
+    pub fn decode_response(&self, response: &Any) -> NeutronResult<IcaMsgResponse<C>> {
+        if let Some(decoder) = self.custom.get(&response.type_url) {
+            return decoder(&response.value).map(IcaMsgResponse::Custom);
+        }
+
+        let value = response.value.as_slice();
+        Ok(match response.type_url.as_str() {
+            MSG_SEND_RESPONSE_TYPE_URL => IcaMsgResponse::MsgSend,
+            MSG_DELEGATE_RESPONSE_TYPE_URL => IcaMsgResponse::MsgDelegate,
+            MSG_UNDELEGATE_RESPONSE_TYPE_URL => IcaMsgResponse::MsgUndelegate {
+                completion_time: MsgUndelegateResponse::decode(value)?
+                    .completion_time
+                    .map(|t| checked_timestamp(t.seconds, t.nanos))
+                    .transpose()?,
+            },
+            MSG_BEGIN_REDELEGATE_RESPONSE_TYPE_URL => IcaMsgResponse::MsgBeginRedelegate {
+                completion_time: MsgBeginRedelegateResponse::decode(value)?
+                    .completion_time
+                    .map(|t| checked_timestamp(t.seconds, t.nanos))
+                    .transpose()?,
+            },
+            MSG_WITHDRAW_DELEGATOR_REWARD_RESPONSE_TYPE_URL => {
+                IcaMsgResponse::MsgWithdrawDelegatorReward {
+                    amount: MsgWithdrawDelegatorRewardResponse::decode(value)?
+                        .amount
+                        .into_iter()
+                        .map(|coin| -> NeutronResult<Coin> {
+                            Ok(Coin::new(Uint256::from_str(&coin.amount)?, coin.denom))
+                        })
+                        .collect::<NeutronResult<Vec<Coin>>>()?,
+                }
+            }
+            MSG_TRANSFER_RESPONSE_TYPE_URL => IcaMsgResponse::MsgTransfer {
+                sequence: MsgTransferResponse::decode(value)?.sequence,
+            },
+            MSG_EXECUTE_CONTRACT_RESPONSE_TYPE_URL => IcaMsgResponse::MsgExecuteContract {
+                data: Binary::new(MsgExecuteContractResponse::decode(value)?.data),
+            },
+            _ => IcaMsgResponse::Unknown(response.clone()),
+        })
+    }
+
+    /// Decodes all the **responses** in the order they are given
+    pub fn decode_responses(&self, responses: &[Any]) -> NeutronResult<Vec<IcaMsgResponse<C>>> {
+        responses
+            .iter()
+            .map(|response| self.decode_response(response))
+            .collect()
+    }
+
+    /// Decodes an acknowledgement **data** of a transaction executed on a chain of any
+    /// Cosmos SDK version, see `decode_tx_msg_data`
+    pub fn decode_acknowledgement(&self, data: Binary) -> NeutronResult<Vec<IcaMsgResponse<C>>> {
+        self.decode_responses(&decode_tx_msg_data(data)?)
+    }
+
+    /// Decodes **responses** to the messages of a transaction described by **payload** and
+    /// pairs every message with its response. Fails if the number of responses or type of any
+    /// response doesn't match the message at the same index.
+    pub fn decode_aligned(
+        &self,
+        responses: &[Any],
+        payload: &IcaTxPayload,
+    ) -> NeutronResult<Vec<(IcaTxMsgInfo, IcaMsgResponse<C>)>> {
+        if responses.len() != payload.msgs.len() {
+            return Err(NeutronError::MsgResponsesCountMismatch {
+                expected: payload.msgs.len(),
+                actual: responses.len(),
+            });
+        }
+
+        payload
+            .msgs
+            .iter()
+            .zip(responses)
+            .enumerate()
+            .map(|(index, (msg, response))| {
+                if response.type_url != msg.response_type_url {
+                    return Err(NeutronError::UnexpectedMsgResponse {
+                        index,
+                        expected: msg.response_type_url.clone(),
+                        actual: response.type_url.clone(),
+                    });
+                }
+
+                Ok((msg.clone(), self.decode_response(response)?))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,655 @@
+use crate::interchain_txs::helpers::{
+    decode_tx_msg_data, decode_tx_msg_data_with_format, get_port_id, register_interchain_account,
+    submit_tx, AckFormat,
+};
+use crate::interchain_txs::ica_manager::{IcaManager, IcaRecord, IcaState};
+use crate::interchain_txs::ica_tx::{IcaTx, IcaTxMsgInfo, IcaTxPayload, DEFAULT_MAX_ICA_TX_MSGS};
+use crate::interchain_txs::responses::{
+    AckDecoder, IcaMsgResponse, MsgExecuteContractResponse, MsgTransferResponse,
+};
+use crate::sudo::msg::{IcaEncoding, IcaMetadata, RequestPacket, SudoMsg};
+use crate::{NeutronError, NeutronResult};
+use cosmos_sdk_proto::cosmos::bank::v1beta1::MsgSend;
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::{MsgData, TxMsgData};
+use cosmos_sdk_proto::cosmos::base::v1beta1::Coin as CosmosCoin;
+use cosmos_sdk_proto::cosmos::distribution::v1beta1::MsgWithdrawDelegatorRewardResponse;
+use cosmos_sdk_proto::cosmos::staking::v1beta1::MsgUndelegateResponse;
+use cosmos_sdk_proto::traits::Message;
+use cosmwasm_std::testing::{MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Addr, BankMsg, Binary, Coin, Timestamp};
+use neutron_std::shim::Any;
+use neutron_std::types::cosmos::base::v1beta1::Coin as NeutronCoin;
+use neutron_std::types::cosmos::staking::v1beta1::MsgDelegate;
+use neutron_std::types::ibc::core::channel::v1::Order;
+
+#[test]
+fn test_ica_tx() {
+    let contract = Addr::unchecked(MOCK_CONTRACT_ADDR);
+    let delegate = MsgDelegate {
+        delegator_address: "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z".to_string(),
+        validator_address: "cosmosvaloper1r2u5q6t6w0wssrk6l66n3t2q3dw2uqnyzsav5h".to_string(),
+        amount: Some(NeutronCoin {
+            denom: "uatom".to_string(),
+            amount: "1000".to_string(),
+        }),
+    };
+    let send = MsgSend {
+        from_address: "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z".to_string(),
+        to_address: "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z".to_string(),
+        amount: vec![CosmosCoin {
+            denom: "uatom".to_string(),
+            amount: "1000".to_string(),
+        }],
+    };
+
+    let (msg, payload) = IcaTx::new("connection-0", "ica", 100)
+        .add_std_msg(delegate.clone())
+        .unwrap()
+        .add_msg(&send)
+        .with_memo("memo")
+        .build(contract.clone())
+        .unwrap();
+    assert_eq!(
+        msg,
+        submit_tx(
+            contract.clone(),
+            "connection-0".to_string(),
+            "ica".to_string(),
+            vec![
+                Any {
+                    type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+                    value: delegate.encode_to_vec(),
+                },
+                Any {
+                    type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                    value: send.encode_to_vec(),
+                },
+            ],
+            "memo".to_string(),
+            100,
+            None,
+        )
+    );
+    assert_eq!(
+        payload,
+        IcaTxPayload {
+            connection_id: "connection-0".to_string(),
+            interchain_account_id: "ica".to_string(),
+            msgs: vec![
+                IcaTxMsgInfo {
+                    type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+                    response_type_url: "/cosmos.staking.v1beta1.MsgDelegateResponse".to_string(),
+                },
+                IcaTxMsgInfo {
+                    type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                    response_type_url: "/cosmos.bank.v1beta1.MsgSendResponse".to_string(),
+                },
+            ],
+        }
+    );
+
+    // messages count is limited
+    let tx = (0..DEFAULT_MAX_ICA_TX_MSGS).fold(IcaTx::new("connection-0", "ica", 100), |tx, _| {
+        tx.add_msg(&send)
+    });
+    assert!(tx.clone().build(contract.clone()).is_ok());
+    assert!(matches!(
+        tx.clone().add_msg(&send).build(contract.clone()),
+        Err(NeutronError::TooManyIcaTxMsgs {
+            max: 16,
+            actual: 17
+        })
+    ));
+    assert!(matches!(
+        tx.with_max_msgs(2).build(contract.clone()),
+        Err(NeutronError::TooManyIcaTxMsgs { max: 2, actual: 16 })
+    ));
+
+    assert!(matches!(
+        IcaTx::new("connection-0", "ica", 100).build(contract.clone()),
+        Err(NeutronError::InvalidIcaTxMsg(_))
+    ));
+    assert!(matches!(
+        IcaTx::new("connection-0", "ica", 100).add_std_msg(BankMsg::Burn {
+            amount: vec![Coin::new(1000u128, "untrn")]
+        }),
+        Err(NeutronError::InvalidIcaTxMsg(_))
+    ));
+}
+
+#[test]
+fn test_ack_decoder() {
+    let response = |type_url: &str, value: Vec<u8>| prost_types::Any {
+        type_url: type_url.to_string(),
+        value,
+    };
+    let undelegate = response(
+        "/cosmos.staking.v1beta1.MsgUndelegateResponse",
+        MsgUndelegateResponse {
+            completion_time: Some(prost_types::Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 5,
+            }),
+        }
+        .encode_to_vec(),
+    );
+    let transfer = response(
+        "/ibc.applications.transfer.v1.MsgTransferResponse",
+        MsgTransferResponse { sequence: 42 }.encode_to_vec(),
+    );
+    let execute = response(
+        "/cosmwasm.wasm.v1.MsgExecuteContractResponse",
+        MsgExecuteContractResponse {
+            data: b"{}".to_vec(),
+        }
+        .encode_to_vec(),
+    );
+    let withdraw = response(
+        "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorRewardResponse",
+        MsgWithdrawDelegatorRewardResponse {
+            amount: vec![CosmosCoin {
+                denom: "uatom".to_string(),
+                amount: "1000".to_string(),
+            }],
+        }
+        .encode_to_vec(),
+    );
+    let delegate = response("/cosmos.staking.v1beta1.MsgDelegateResponse", vec![]);
+    let vote = response("/cosmos.gov.v1beta1.MsgVoteResponse", vec![]);
+
+    let decoder: AckDecoder = AckDecoder::new();
+    let data: Binary = TxMsgData {
+        #[allow(deprecated)]
+        data: vec![],
+        msg_responses: vec![
+            undelegate.clone(),
+            transfer.clone(),
+            execute.clone(),
+            withdraw.clone(),
+            delegate.clone(),
+            vote.clone(),
+        ],
+    }
+    .encode_to_vec()
+    .into();
+    assert_eq!(
+        decoder.decode_acknowledgement(data).unwrap(),
+        vec![
+            IcaMsgResponse::MsgUndelegate {
+                completion_time: Some(Timestamp::from_nanos(1_700_000_000_000_000_005))
+            },
+            IcaMsgResponse::MsgTransfer { sequence: 42 },
+            IcaMsgResponse::MsgExecuteContract {
+                data: Binary::from(b"{}")
+            },
+            IcaMsgResponse::MsgWithdrawDelegatorReward {
+                amount: vec![Coin::new(1000u128, "uatom")]
+            },
+            IcaMsgResponse::MsgDelegate,
+            IcaMsgResponse::Unknown(vote.clone()),
+        ]
+    );
+    assert!(decoder
+        .decode_response(&response(
+            "/ibc.applications.transfer.v1.MsgTransferResponse",
+            vec![0xff]
+        ))
+        .is_err());
+
+    // custom responses
+    fn decode_vote(value: &[u8]) -> NeutronResult<String> {
+        Ok(format!("vote {}", value.len()))
+    }
+    let decoder: AckDecoder<String> =
+        AckDecoder::new().register("/cosmos.gov.v1beta1.MsgVoteResponse", decode_vote);
+    assert_eq!(
+        decoder.decode_response(&vote).unwrap(),
+        IcaMsgResponse::Custom("vote 0".to_string())
+    );
+
+    // responses are lined up with the messages
+    let msg = |type_url: &str| Any {
+        type_url: type_url.to_string(),
+        value: vec![],
+    };
+    let payload = IcaTx::new("connection-0", "ica", 100)
+        .add_any(msg("/cosmos.staking.v1beta1.MsgDelegate"))
+        .add_any(msg("/cosmos.gov.v1beta1.MsgVote"))
+        .payload();
+    assert_eq!(
+        decoder
+            .decode_aligned(&[delegate.clone(), vote.clone()], &payload)
+            .unwrap(),
+        vec![
+            (payload.msgs[0].clone(), IcaMsgResponse::MsgDelegate),
+            (
+                payload.msgs[1].clone(),
+                IcaMsgResponse::Custom("vote 0".to_string())
+            ),
+        ]
+    );
+    assert!(matches!(
+        decoder.decode_aligned(&[delegate.clone()], &payload),
+        Err(NeutronError::MsgResponsesCountMismatch {
+            expected: 2,
+            actual: 1
+        })
+    ));
+    assert!(matches!(
+        decoder.decode_aligned(&[vote, delegate], &payload),
+        Err(NeutronError::UnexpectedMsgResponse { index: 0, .. })
+    ));
+}
+
+#[test]
+fn test_decode_tx_msg_data() {
+    let delegate_response = prost_types::Any {
+        type_url: "/cosmos.staking.v1beta1.MsgDelegateResponse".to_string(),
+        value: vec![],
+    };
+    let undelegate_response = MsgUndelegateResponse {
+        completion_time: Some(prost_types::Timestamp {
+            seconds: 1_700_000_000,
+            nanos: 0,
+        }),
+    }
+    .encode_to_vec();
+    let ack = |data: Vec<MsgData>, msg_responses: Vec<prost_types::Any>| -> Binary {
+        TxMsgData {
+            #[allow(deprecated)]
+            data,
+            msg_responses,
+        }
+        .encode_to_vec()
+        .into()
+    };
+
+    // Cosmos SDK 0.45 acknowledgement
+    let legacy = ack(
+        vec![
+            MsgData {
+                msg_type: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+                data: vec![],
+            },
+            MsgData {
+                msg_type: "/cosmos.staking.v1beta1.MsgUndelegate".to_string(),
+                data: undelegate_response.clone(),
+            },
+        ],
+        vec![],
+    );
+    let undelegate = prost_types::Any {
+        type_url: "/cosmos.staking.v1beta1.MsgUndelegateResponse".to_string(),
+        value: undelegate_response,
+    };
+    assert_eq!(
+        decode_tx_msg_data_with_format(legacy.clone()).unwrap(),
+        (
+            vec![delegate_response.clone(), undelegate.clone()],
+            AckFormat::MsgData
+        )
+    );
+
+    // Cosmos SDK 0.46+ acknowledgement
+    let modern = ack(vec![], vec![delegate_response.clone(), undelegate.clone()]);
+    assert_eq!(
+        decode_tx_msg_data_with_format(modern.clone()).unwrap(),
+        (
+            vec![delegate_response.clone(), undelegate.clone()],
+            AckFormat::MsgResponses
+        )
+    );
+    assert_eq!(
+        decode_tx_msg_data(legacy.clone()).unwrap(),
+        decode_tx_msg_data(modern).unwrap()
+    );
+
+    // msg_responses take precedence
+    let both = ack(
+        vec![MsgData {
+            msg_type: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            data: vec![],
+        }],
+        vec![delegate_response.clone()],
+    );
+    assert_eq!(
+        decode_tx_msg_data_with_format(both).unwrap(),
+        (vec![delegate_response], AckFormat::MsgResponses)
+    );
+
+    assert_eq!(
+        decode_tx_msg_data_with_format(ack(vec![], vec![])).unwrap(),
+        (vec![], AckFormat::Empty)
+    );
+    assert!(decode_tx_msg_data(Binary::from(vec![0xff])).is_err());
+
+    // both formats are decoded by AckDecoder
+    let decoder: AckDecoder = AckDecoder::new();
+    assert_eq!(
+        decoder.decode_acknowledgement(legacy).unwrap(),
+        vec![
+            IcaMsgResponse::MsgDelegate,
+            IcaMsgResponse::MsgUndelegate {
+                completion_time: Some(Timestamp::from_seconds(1_700_000_000))
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_ica_manager() {
+    const ICAS: IcaManager = IcaManager::new("icas");
+
+    let mut storage = MockStorage::new();
+    let contract = Addr::unchecked(MOCK_CONTRACT_ADDR);
+    let port_id = get_port_id(MOCK_CONTRACT_ADDR, "ica");
+    let fee = vec![NeutronCoin {
+        denom: "untrn".to_string(),
+        amount: "1000".to_string(),
+    }];
+    let register = register_interchain_account(
+        contract.clone(),
+        "connection-0".to_string(),
+        "ica".to_string(),
+        fee.clone(),
+        Some(Order::Ordered),
+    );
+    let version = r#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-1","address":"cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z","encoding":"proto3","tx_type":"sdk_multi_msg"}"#;
+
+    assert_eq!(
+        ICAS.register(
+            &mut storage,
+            contract.clone(),
+            "connection-0".to_string(),
+            "ica".to_string(),
+            fee.clone(),
+            None
+        )
+        .unwrap(),
+        register
+    );
+    // the registration is sent again if the channel handshake never completes
+    assert_eq!(
+        ICAS.register(
+            &mut storage,
+            contract.clone(),
+            "connection-0".to_string(),
+            "ica".to_string(),
+            fee.clone(),
+            None
+        )
+        .unwrap(),
+        register
+    );
+    assert_eq!(
+        ICAS.load(&storage, &contract, "ica").unwrap().state,
+        IcaState::Registering
+    );
+    assert!(ICAS.address(&storage, &contract, "ica").is_err());
+
+    // the account is open after OpenAck
+    assert!(ICAS
+        .on_open_ack(
+            &mut storage,
+            &port_id,
+            "channel-0".to_string(),
+            "channel-1".to_string(),
+            "{}"
+        )
+        .is_err());
+    let record = ICAS
+        .on_open_ack(
+            &mut storage,
+            &port_id,
+            "channel-0".to_string(),
+            "channel-1".to_string(),
+            version,
+        )
+        .unwrap();
+    assert_eq!(
+        record,
+        IcaRecord {
+            interchain_account_id: "ica".to_string(),
+            connection_id: "connection-0".to_string(),
+            port_id: port_id.clone(),
+            state: IcaState::Open,
+            address: Some("cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z".to_string()),
+            channel_id: Some("channel-0".to_string()),
+            counterparty_channel_id: Some("channel-1".to_string()),
+            ordering: Order::Ordered,
+            register_fee: fee.clone(),
+        }
+    );
+    assert_eq!(ICAS.load(&storage, &contract, "ica").unwrap(), record);
+    // an open account can't be registered again
+    assert!(matches!(
+        ICAS.register(
+            &mut storage,
+            contract.clone(),
+            "connection-0".to_string(),
+            "ica".to_string(),
+            fee.clone(),
+            None
+        ),
+        Err(NeutronError::UnexpectedIcaState { .. })
+    ));
+    assert_eq!(
+        ICAS.address(&storage, &contract, "ica").unwrap(),
+        "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z"
+    );
+    assert!(matches!(
+        ICAS.load(&storage, &contract, "other"),
+        Err(NeutronError::IcaNotFound { .. })
+    ));
+
+    // timeouts on other ports are ignored
+    let request = |port_id: &str| RequestPacket {
+        sequence: Some(1),
+        source_port: Some(port_id.to_string()),
+        source_channel: Some("channel-0".to_string()),
+        destination_port: None,
+        destination_channel: None,
+        data: None,
+        timeout_height: None,
+        timeout_timestamp: None,
+    };
+    assert_eq!(
+        ICAS.on_timeout(&mut storage, &request("transfer")).unwrap(),
+        None
+    );
+
+    // the account is closed after a timeout on an ordered channel
+    let closed = ICAS
+        .on_timeout(&mut storage, &request(&port_id))
+        .unwrap()
+        .unwrap();
+    assert_eq!(closed.state, IcaState::Closed);
+    assert_eq!(closed.channel_id, None);
+    assert_eq!(closed.counterparty_channel_id, None);
+    assert_eq!(ICAS.load_by_port(&storage, &port_id).unwrap(), closed);
+    assert_eq!(
+        ICAS.on_timeout(&mut storage, &request(&port_id)).unwrap(),
+        None
+    );
+
+    // and is registered again from execute keeping its address
+    assert_eq!(
+        ICAS.register(
+            &mut storage,
+            contract.clone(),
+            "connection-0".to_string(),
+            "ica".to_string(),
+            fee.clone(),
+            None
+        )
+        .unwrap(),
+        register
+    );
+    let record = ICAS.load(&storage, &contract, "ica").unwrap();
+    assert_eq!(record.state, IcaState::Reregistering);
+    assert_eq!(
+        record.address,
+        Some("cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z".to_string())
+    );
+    assert!(ICAS.address(&storage, &contract, "ica").is_err());
+    ICAS.on_open_ack(
+        &mut storage,
+        &port_id,
+        "channel-2".to_string(),
+        "channel-3".to_string(),
+        version,
+    )
+    .unwrap();
+    assert!(ICAS.load(&storage, &contract, "ica").unwrap().is_open());
+
+    // the connection of an account tracked without it is taken from OpenAck, and the connection
+    // of OpenAck must be the connection of the account otherwise
+    let legacy_port_id = get_port_id(MOCK_CONTRACT_ADDR, "legacy");
+    let legacy = IcaRecord {
+        interchain_account_id: "legacy".to_string(),
+        connection_id: "connection-1".to_string(),
+        port_id: legacy_port_id.clone(),
+        state: IcaState::Registering,
+        address: None,
+        channel_id: None,
+        counterparty_channel_id: None,
+        ordering: Order::Ordered,
+        register_fee: vec![],
+    };
+    ICAS.save(&mut storage, &legacy).unwrap();
+    assert!(matches!(
+        ICAS.on_open_ack(
+            &mut storage,
+            &legacy_port_id,
+            "channel-6".to_string(),
+            "channel-7".to_string(),
+            version,
+        ),
+        Err(NeutronError::InvalidIcaMetadata(_))
+    ));
+    ICAS.save(
+        &mut storage,
+        &IcaRecord {
+            connection_id: String::new(),
+            ..legacy
+        },
+    )
+    .unwrap();
+    let record = ICAS
+        .on_open_ack(
+            &mut storage,
+            &legacy_port_id,
+            "channel-6".to_string(),
+            "channel-7".to_string(),
+            version,
+        )
+        .unwrap();
+    assert_eq!(record.connection_id, "connection-0");
+    assert!(record.is_open());
+    ICAS.remove(&mut storage, &legacy_port_id);
+
+    // unordered channels are not closed on timeouts
+    ICAS.register(
+        &mut storage,
+        contract.clone(),
+        "connection-0".to_string(),
+        "unordered".to_string(),
+        fee,
+        Some(Order::Unordered),
+    )
+    .unwrap();
+    let unordered_port_id = get_port_id(MOCK_CONTRACT_ADDR, "unordered");
+    ICAS.on_open_ack(
+        &mut storage,
+        &unordered_port_id,
+        "channel-4".to_string(),
+        "channel-5".to_string(),
+        version,
+    )
+    .unwrap();
+    assert_eq!(
+        ICAS.on_timeout(&mut storage, &request(&unordered_port_id))
+            .unwrap(),
+        None
+    );
+    assert!(ICAS
+        .load_by_port(&storage, &unordered_port_id)
+        .unwrap()
+        .is_open());
+
+    assert_eq!(
+        ICAS.range(&storage, None, None)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.interchain_account_id)
+            .collect::<Vec<String>>(),
+        vec!["ica".to_string(), "unordered".to_string()]
+    );
+    assert_eq!(
+        ICAS.range(&storage, Some(port_id), Some(1)).unwrap().len(),
+        1
+    );
+}
+
+#[test]
+fn test_ica_metadata() {
+    let version = |encoding: &str, address: &str| {
+        format!(
+            r#"{{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-1","address":"{}","encoding":"{}","tx_type":"sdk_multi_msg"}}"#,
+            address, encoding
+        )
+    };
+    let open_ack = |counterparty_version: String| SudoMsg::OpenAck {
+        port_id: get_port_id(MOCK_CONTRACT_ADDR, "ica"),
+        channel_id: "channel-0".to_string(),
+        counterparty_channel_id: "channel-1".to_string(),
+        counterparty_version,
+    };
+
+    let metadata = open_ack(version(
+        "proto3",
+        "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z",
+    ))
+    .ica_metadata()
+    .unwrap();
+    assert_eq!(
+        metadata,
+        IcaMetadata {
+            version: "ics27-1".to_string(),
+            controller_connection_id: "connection-0".to_string(),
+            host_connection_id: "connection-1".to_string(),
+            address: "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z".to_string(),
+            encoding: IcaEncoding::Proto3,
+            tx_type: "sdk_multi_msg".to_string(),
+        }
+    );
+    assert_eq!(
+        IcaMetadata::parse(&version(
+            "proto3json",
+            "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z"
+        ))
+        .unwrap()
+        .encoding,
+        IcaEncoding::Proto3Json
+    );
+
+    assert!(matches!(
+        IcaMetadata::parse(&version(
+            "json",
+            "cosmos1yz54ncxj9csp7un3xled03q6thrrhy9c2s9e5z"
+        )),
+        Err(NeutronError::UnsupportedIcaEncoding { encoding }) if encoding == "json"
+    ));
+    assert!(matches!(
+        IcaMetadata::parse(&version("proto3", "")),
+        Err(NeutronError::InvalidIcaMetadata(details)) if details == "interchain account address is empty"
+    ));
+    assert!(matches!(
+        open_ack(r#"{"version":"ics27-1"}"#.to_string()).ica_metadata(),
+        Err(NeutronError::InvalidIcaMetadata(_))
+    ));
+    assert!(matches!(
+        SudoMsg::KVQueryResult { query_id: 1 }.ica_metadata(),
+        Err(NeutronError::InvalidIcaMetadata(_))
+    ));
+}
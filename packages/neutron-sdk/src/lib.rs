@@ -4,6 +4,7 @@
 #![warn(trivial_casts, trivial_numeric_casts, unused_import_braces)]
 
 pub mod bindings;
+pub mod chain_profile;
 mod errors;
 pub mod interchain_queries;
 pub mod interchain_txs;
@@ -0,0 +1,220 @@
+//! Dispatch of sudo calls to typed handlers.
+//!
+//! Instead of matching `SudoMsg` by hand, a contract implements `SudoHandler` for the variants it
+//! cares about, the rest are handled by default implementations which do nothing. `dispatch` calls
+//! the handler of a variant passing the packet an acknowledgement or a timeout is received for as
+//! `SudoPacket`, its sequence and channel are extracted only when a handler asks for them, so a
+//! variant which is ignored never fails:
+//! ```rust ignore
+//! struct Handler;
+//!
+//! impl SudoHandler for Handler {
+//!     fn on_kv_query_result(&self, deps: DepsMut, env: Env, query_id: u64) -> NeutronResult<Response> {
+//!         ..
+//!     }
+//! }
+//!
+//! #[entry_point]
+//! pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> NeutronResult<Response> {
+//!     dispatch(&Handler, deps, env, msg)
+//! }
+//! ```
+//! `TransferSudoHandler` and `dispatch_transfer` do the same for `TransferSudoMsg`.
+
+use crate::errors::error::{NeutronError, NeutronResult};
+use crate::sudo::msg::{Height, RequestPacket, SudoMsg, TransferSudoMsg};
+use cosmwasm_std::{Binary, DepsMut, Env, Response};
+
+/// Packet an acknowledgement or a timeout is received for.
+/// The sequence and the source channel are always set by Neutron, so a missing one means a fatal
+/// error occurred on Neutron side and is reported as `NeutronError::InvalidRequestPacket`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SudoPacket {
+    pub request: RequestPacket,
+}
+
+impl SudoPacket {
+    pub fn new(request: RequestPacket) -> SudoPacket {
+        SudoPacket { request }
+    }
+
+    /// Returns sequence of the packet in the channel
+    pub fn sequence(&self) -> NeutronResult<u64> {
+        self.request
+            .sequence
+            .ok_or_else(|| NeutronError::InvalidRequestPacket("sequence not found".to_string()))
+    }
+
+    /// Returns channel the packet was sent from
+    pub fn channel_id(&self) -> NeutronResult<&str> {
+        self.request
+            .source_channel
+            .as_deref()
+            .ok_or_else(|| NeutronError::InvalidRequestPacket("channel_id not found".to_string()))
+    }
+}
+
+/// Handlers of `SudoMsg` variants called by `dispatch`, a variant without its own handler
+/// is ignored
+pub trait SudoHandler {
+    /// Handles a successful acknowledgement of an interchain transaction
+    fn on_response(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _packet: SudoPacket,
+        _data: Binary,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+
+    /// Handles an error acknowledgement of an interchain transaction
+    fn on_error(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _packet: SudoPacket,
+        _details: String,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+
+    /// Handles a timeout of an interchain transaction
+    fn on_timeout(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _packet: SudoPacket,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+
+    /// Handles opening of an interchain account channel,
+    /// see `IcaMetadata` to parse **counterparty_version**
+    fn on_open_ack(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _port_id: String,
+        _channel_id: String,
+        _counterparty_channel_id: String,
+        _counterparty_version: String,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+
+    /// Handles a transaction submitted for a TX Interchain Query
+    fn on_tx_query_result(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _query_id: u64,
+        _height: Height,
+        _data: Binary,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+
+    /// Handles a result submitted for a KV Interchain Query
+    fn on_kv_query_result(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _query_id: u64,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+}
+
+/// Calls the **handler** of the variant of **msg**
+pub fn dispatch<H: SudoHandler + ?Sized>(
+    handler: &H,
+    deps: DepsMut,
+    env: Env,
+    msg: SudoMsg,
+) -> NeutronResult<Response> {
+    match msg {
+        SudoMsg::Response { request, data } => {
+            handler.on_response(deps, env, SudoPacket::new(request), data)
+        }
+        SudoMsg::Error { request, details } => {
+            handler.on_error(deps, env, SudoPacket::new(request), details)
+        }
+        SudoMsg::Timeout { request } => handler.on_timeout(deps, env, SudoPacket::new(request)),
+        SudoMsg::OpenAck {
+            port_id,
+            channel_id,
+            counterparty_channel_id,
+            counterparty_version,
+        } => handler.on_open_ack(
+            deps,
+            env,
+            port_id,
+            channel_id,
+            counterparty_channel_id,
+            counterparty_version,
+        ),
+        SudoMsg::TxQueryResult {
+            query_id,
+            height,
+            data,
+        } => handler.on_tx_query_result(deps, env, query_id, height, data),
+        SudoMsg::KVQueryResult { query_id } => handler.on_kv_query_result(deps, env, query_id),
+    }
+}
+
+/// Handlers of `TransferSudoMsg` variants called by `dispatch_transfer`, a variant without
+/// its own handler is ignored
+pub trait TransferSudoHandler {
+    /// Handles a successful acknowledgement of an IBC transfer
+    fn on_response(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _packet: SudoPacket,
+        _data: Binary,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+
+    /// Handles an error acknowledgement of an IBC transfer
+    fn on_error(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _packet: SudoPacket,
+        _details: String,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+
+    /// Handles a timeout of an IBC transfer
+    fn on_timeout(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _packet: SudoPacket,
+    ) -> NeutronResult<Response> {
+        Ok(Response::default())
+    }
+}
+
+/// Calls the **handler** of the variant of **msg**
+pub fn dispatch_transfer<H: TransferSudoHandler + ?Sized>(
+    handler: &H,
+    deps: DepsMut,
+    env: Env,
+    msg: TransferSudoMsg,
+) -> NeutronResult<Response> {
+    match msg {
+        TransferSudoMsg::Response { request, data } => {
+            handler.on_response(deps, env, SudoPacket::new(request), data)
+        }
+        TransferSudoMsg::Error { request, details } => {
+            handler.on_error(deps, env, SudoPacket::new(request), details)
+        }
+        TransferSudoMsg::Timeout { request } => {
+            handler.on_timeout(deps, env, SudoPacket::new(request))
+        }
+    }
+}
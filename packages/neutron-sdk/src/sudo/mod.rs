@@ -1 +1,5 @@
+pub mod handler;
 pub mod msg;
+
+#[cfg(test)]
+mod testing;
@@ -1,4 +1,6 @@
+use crate::errors::error::{NeutronError, NeutronResult};
 use cosmwasm_std::Binary;
+use neutron_std::types::ibc::core::client::v1::Height as IbcHeight;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -51,6 +53,95 @@ pub enum SudoMsg {
     },
 }
 
+impl SudoMsg {
+    /// Parses `counterparty_version` of `SudoMsg::OpenAck` into `IcaMetadata`.
+    /// Returns an error if the message is not `OpenAck` or the metadata is malformed.
+    pub fn ica_metadata(&self) -> NeutronResult<IcaMetadata> {
+        match self {
+            SudoMsg::OpenAck {
+                counterparty_version,
+                ..
+            } => IcaMetadata::parse(counterparty_version),
+            _ => Err(NeutronError::InvalidIcaMetadata(
+                "sudo message is not an OpenAck".to_string(),
+            )),
+        }
+    }
+}
+
+/// Encoding of messages in ICA packets, which is negotiated in ICA channel version metadata
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IcaEncoding {
+    Proto3,
+    Proto3Json,
+}
+
+impl IcaEncoding {
+    /// Returns the encoding as it's written in ICA channel version metadata
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IcaEncoding::Proto3 => "proto3",
+            IcaEncoding::Proto3Json => "proto3json",
+        }
+    }
+}
+
+/// Metadata of an ICA channel version, which the host chain sends as `counterparty_version`
+/// in `SudoMsg::OpenAck`
+/// <https://github.com/cosmos/ibc-go/blob/v7.3.0/proto/ibc/applications/interchain_accounts/v1/metadata.proto>
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IcaMetadata {
+    /// version of the ICA protocol, e.g. `ics27-1`
+    pub version: String,
+    pub controller_connection_id: String,
+    pub host_connection_id: String,
+    /// address of the interchain account on the host chain
+    pub address: String,
+    /// encoding of messages in ICA packets
+    pub encoding: IcaEncoding,
+    /// type of transactions the host chain executes, e.g. `sdk_multi_msg`
+    pub tx_type: String,
+}
+
+impl IcaMetadata {
+    /// Parses **counterparty_version** of an `OpenAck` and validates it
+    pub fn parse(counterparty_version: &str) -> NeutronResult<IcaMetadata> {
+        let metadata: IcaMetadata =
+            serde_json_wasm::from_str(counterparty_version).map_err(|e| {
+                // an unknown encoding is reported by its value instead of a generic parsing error
+                #[derive(Deserialize)]
+                struct RawEncoding {
+                    encoding: String,
+                }
+                match serde_json_wasm::from_str::<RawEncoding>(counterparty_version) {
+                    Ok(RawEncoding { encoding })
+                        if ![IcaEncoding::Proto3, IcaEncoding::Proto3Json]
+                            .iter()
+                            .any(|known| known.as_str() == encoding) =>
+                    {
+                        NeutronError::UnsupportedIcaEncoding { encoding }
+                    }
+                    _ => NeutronError::InvalidIcaMetadata(e.to_string()),
+                }
+            })?;
+        metadata.validate()?;
+
+        Ok(metadata)
+    }
+
+    /// Checks the interchain account address is set
+    pub fn validate(&self) -> NeutronResult<()> {
+        if self.address.is_empty() {
+            return Err(NeutronError::InvalidIcaMetadata(
+                "interchain account address is empty".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 /// TransferSudoMsg is a sudo response payload for a native ibc transfer
 /// SudoMsg for ibc transfer has fewer methods than SudoMsg for ica txs
 /// so we describe standalone type to not confuse users with useless variants
@@ -71,7 +162,23 @@ pub enum TransferSudoMsg {
 }
 
 /// Height is used for sudo call for `TxQueryResult` enum variant type
-#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+///
+/// Heights are ordered by revision number first and by height within a revision next,
+/// the same way IBC compares them.
+#[derive(
+    Default,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    JsonSchema,
+)]
 #[serde(rename_all = "snake_case")]
 pub struct Height {
     /// the revision that the client is currently on
@@ -81,3 +188,39 @@ pub struct Height {
     #[serde(default)]
     pub revision_height: u64,
 }
+
+impl Height {
+    pub fn new(revision_number: u64, revision_height: u64) -> Height {
+        Height {
+            revision_number,
+            revision_height,
+        }
+    }
+
+    /// Returns true if the height is not set
+    pub fn is_zero(&self) -> bool {
+        self.revision_number == 0 && self.revision_height == 0
+    }
+
+    /// Returns true if the height is in the same revision as **other** and after it,
+    /// e.g. to check a query result is newer than the previous one
+    pub fn is_after_in_revision(&self, other: &Height) -> bool {
+        self.revision_number == other.revision_number
+            && self.revision_height > other.revision_height
+    }
+}
+
+impl From<IbcHeight> for Height {
+    fn from(height: IbcHeight) -> Self {
+        Height::new(height.revision_number, height.revision_height)
+    }
+}
+
+impl From<Height> for IbcHeight {
+    fn from(height: Height) -> Self {
+        IbcHeight {
+            revision_number: height.revision_number,
+            revision_height: height.revision_height,
+        }
+    }
+}
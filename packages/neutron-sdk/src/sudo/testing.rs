@@ -0,0 +1,223 @@
+use crate::sudo::handler::{
+    dispatch, dispatch_transfer, SudoHandler, SudoPacket, TransferSudoHandler,
+};
+use crate::sudo::msg::{Height, RequestPacket, SudoMsg, TransferSudoMsg};
+use crate::{NeutronError, NeutronResult};
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+use cosmwasm_std::{Binary, DepsMut, Env, Response};
+
+struct Handler;
+
+impl SudoHandler for Handler {
+    fn on_response(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        packet: SudoPacket,
+        data: Binary,
+    ) -> NeutronResult<Response> {
+        Ok(Response::new()
+            .add_attribute("action", "response")
+            .add_attribute("sequence", packet.sequence()?.to_string())
+            .add_attribute("channel_id", packet.channel_id()?)
+            .add_attribute("data", data.to_base64()))
+    }
+
+    fn on_kv_query_result(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        query_id: u64,
+    ) -> NeutronResult<Response> {
+        Ok(Response::new()
+            .add_attribute("action", "kv_query_result")
+            .add_attribute("query_id", query_id.to_string()))
+    }
+}
+
+impl TransferSudoHandler for Handler {
+    fn on_timeout(&self, _deps: DepsMut, _env: Env, packet: SudoPacket) -> NeutronResult<Response> {
+        Ok(Response::new()
+            .add_attribute("action", "transfer_timeout")
+            .add_attribute("sequence", packet.sequence()?.to_string()))
+    }
+}
+
+fn request(sequence: Option<u64>, source_channel: Option<&str>) -> RequestPacket {
+    RequestPacket {
+        sequence,
+        source_port: Some("transfer".to_string()),
+        source_channel: source_channel.map(|channel| channel.to_string()),
+        destination_port: None,
+        destination_channel: None,
+        data: None,
+        timeout_height: None,
+        timeout_timestamp: None,
+    }
+}
+
+#[test]
+fn test_sudo_dispatch() {
+    let mut deps = mock_dependencies();
+
+    let res = dispatch(
+        &Handler,
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::Response {
+            request: request(Some(7), Some("channel-0")),
+            data: Binary::from(b"data"),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "response")
+            .add_attribute("sequence", "7")
+            .add_attribute("channel_id", "channel-0")
+            .add_attribute("data", Binary::from(b"data").to_base64())
+    );
+
+    let res = dispatch(
+        &Handler,
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::KVQueryResult { query_id: 3 },
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "kv_query_result")
+            .add_attribute("query_id", "3")
+    );
+
+    // variants without own handlers are ignored even if their packets are malformed
+    for msg in [
+        SudoMsg::Error {
+            request: request(Some(7), Some("channel-0")),
+            details: "error".to_string(),
+        },
+        SudoMsg::Timeout {
+            request: request(None, None),
+        },
+        SudoMsg::OpenAck {
+            port_id: "icacontroller-contract.ica".to_string(),
+            channel_id: "channel-0".to_string(),
+            counterparty_channel_id: "channel-1".to_string(),
+            counterparty_version: "{}".to_string(),
+        },
+        SudoMsg::TxQueryResult {
+            query_id: 1,
+            height: Height::new(0, 100),
+            data: Binary::default(),
+        },
+    ] {
+        assert_eq!(
+            dispatch(&Handler, deps.as_mut(), mock_env(), msg).unwrap(),
+            Response::default()
+        );
+    }
+
+    // packets without sequence or channel are rejected by handlers reading them
+    assert!(matches!(
+        dispatch(
+            &Handler,
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::Response {
+                request: request(None, Some("channel-0")),
+                data: Binary::default(),
+            },
+        ),
+        Err(NeutronError::InvalidRequestPacket(details)) if details == "sequence not found"
+    ));
+    assert!(matches!(
+        dispatch(
+            &Handler,
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::Response {
+                request: request(Some(7), None),
+                data: Binary::default(),
+            },
+        ),
+        Err(NeutronError::InvalidRequestPacket(details)) if details == "channel_id not found"
+    ));
+}
+
+#[test]
+fn test_transfer_sudo_dispatch() {
+    let mut deps = mock_dependencies();
+
+    let res = dispatch_transfer(
+        &Handler,
+        deps.as_mut(),
+        mock_env(),
+        TransferSudoMsg::Timeout {
+            request: request(Some(2), Some("channel-0")),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "transfer_timeout")
+            .add_attribute("sequence", "2")
+    );
+
+    assert_eq!(
+        dispatch_transfer(
+            &Handler,
+            deps.as_mut(),
+            mock_env(),
+            TransferSudoMsg::Error {
+                request: request(None, None),
+                details: "error".to_string(),
+            },
+        )
+        .unwrap(),
+        Response::default()
+    );
+    assert!(matches!(
+        dispatch_transfer(
+            &Handler,
+            deps.as_mut(),
+            mock_env(),
+            TransferSudoMsg::Timeout {
+                request: request(None, Some("channel-0")),
+            },
+        ),
+        Err(NeutronError::InvalidRequestPacket(_))
+    ));
+}
+
+#[test]
+fn test_height_ordering() {
+    let mut heights = vec![
+        Height::new(2, 1),
+        Height::new(1, 200),
+        Height::new(1, 100),
+        Height::default(),
+    ];
+    heights.sort();
+    assert_eq!(
+        heights,
+        vec![
+            Height::default(),
+            Height::new(1, 100),
+            Height::new(1, 200),
+            Height::new(2, 1),
+        ]
+    );
+    assert!(Height::new(2, 1) > Height::new(1, 200));
+    assert!(Height::default().is_zero());
+
+    assert!(Height::new(1, 200).is_after_in_revision(&Height::new(1, 100)));
+    assert!(!Height::new(1, 100).is_after_in_revision(&Height::new(1, 100)));
+    assert!(!Height::new(2, 1).is_after_in_revision(&Height::new(1, 100)));
+
+    let ibc_height: neutron_std::types::ibc::core::client::v1::Height = Height::new(1, 100).into();
+    assert_eq!(Height::from(ibc_height), Height::new(1, 100));
+}